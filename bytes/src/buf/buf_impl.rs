@@ -1,11 +1,17 @@
-use super::{reader, take, Chain, Reader, Take};
+#[cfg(feature = "std")]
+use super::{reader, Reader};
+use super::{take, Chain, ChunkIter, Chunks, Take};
 #[cfg(feature = "std")]
 use crate::{min_u64_usize, saturating_sub_usize_u64};
 use crate::{panic_advance, panic_does_not_fit, Bytes, BytesMut};
 use alloc::boxed::Box;
+use core::fmt;
 #[cfg(feature = "std")]
 use std::io::IoSlice;
 
+/// Maximum number of bytes a LEB128 encoded `u64` can occupy
+pub(crate) const MAX_VARINT_LEN: usize = 10;
+
 macro_rules! buf_get_impl {
     ($this:ident, $typ:tt::$conv:tt) => {{
         const SIZE: usize = core::mem::size_of::<$typ>();
@@ -62,12 +68,103 @@ macro_rules! buf_get_impl {
     }};
 }
 
+macro_rules! buf_try_get_impl {
+    ($this:ident, $typ:tt, $get:ident) => {{
+        const SIZE: usize = core::mem::size_of::<$typ>();
+
+        if $this.remaining() < SIZE {
+            return Err(TryGetError {
+                requested: SIZE,
+                available: $this.remaining(),
+            });
+        }
+
+        Ok($this.$get())
+    }};
+}
+
+/// Error returned by the `try_get_*` methods of `Buf` when there are not enough remaining bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryGetError {
+    /// Number of bytes needed to read the value
+    pub requested: usize,
+    /// Number of bytes remaining in the buffer
+    pub available: usize,
+}
+
+impl fmt::Display for TryGetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "not enough bytes remaining in buffer to read value (requested {} but only {} available)",
+            self.requested, self.available
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryGetError {}
+
+/// Error returned when a LEB128 varint can not be decoded from a `Buf`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarintError {
+    /// The buffer ended before the last byte of the varint
+    Truncated,
+    /// The varint does not fit into 64 bits
+    Overflow,
+}
+
+impl fmt::Display for VarintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VarintError::Truncated => f.write_str("varint is truncated"),
+            VarintError::Overflow => f.write_str("varint overflows 64 bits"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VarintError {}
+
+/// Error returned by `Buf::get_char` when the next bytes are not a valid UTF-8 sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Utf8DecodeError {
+    /// The buffer ends before the end of the sequence. Nothing is consumed
+    Truncated,
+    /// The byte can not start a UTF-8 sequence. The byte is consumed
+    InvalidLeadingByte(u8),
+    /// The byte is not a valid continuation of the sequence. The bytes before it are consumed
+    InvalidContinuation(u8),
+}
+
+impl fmt::Display for Utf8DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Utf8DecodeError::Truncated => f.write_str("utf-8 sequence is truncated"),
+            Utf8DecodeError::InvalidLeadingByte(b) => {
+                write!(f, "invalid utf-8 leading byte 0x{:02x}", b)
+            }
+            Utf8DecodeError::InvalidContinuation(b) => {
+                write!(f, "invalid utf-8 continuation byte 0x{:02x}", b)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Utf8DecodeError {}
+
 fn sign_extend(val: u64, nbytes: usize) -> i64 {
     let shift = (8 - nbytes) + 8;
 
     (val << shift) as i64 >> shift
 }
 
+#[inline]
+fn zigzag_decode(n: u64) -> i64 {
+    (n >> 1) as i64 ^ -((n & 1) as i64)
+}
+
 /// Read bytes from a buffer
 pub trait Buf {
     /// Returns the number of bytes between current position and the end of the buffer
@@ -79,6 +176,33 @@ pub trait Buf {
     #[cfg_attr(docsrs, doc(alias = "bytes"))]
     fn chunk(&self) -> &[u8];
 
+    /// Returns the contiguous region starting `offset` bytes into the remaining data, without
+    /// advancing. Returns an empty slice once `offset` reaches `remaining()`
+    ///
+    /// Buffers made of several regions should override this, the default only looks at `chunk`
+    fn chunk_at(&self, offset: usize) -> &[u8] {
+        self.chunk().get(offset..).unwrap_or(&[])
+    }
+
+    /// Returns the number of contiguous regions the remaining data is split into, 0 when empty
+    ///
+    /// The default walks `chunk_at`, buffers that know their layout can answer directly
+    fn chunk_count(&self) -> usize {
+        let mut count = 0;
+        let mut offset = 0;
+
+        loop {
+            let chunk = self.chunk_at(offset);
+
+            if chunk.is_empty() {
+                return count;
+            }
+
+            count += 1;
+            offset += chunk.len();
+        }
+    }
+
     /// Fills `dst` with potentially multiple slices starting at `self`'s current position
     #[cfg(feature = "std")]
     #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
@@ -98,6 +222,22 @@ pub trait Buf {
     /// Advance the internal cursor of the `Buf`
     fn advance(&mut self, cnt: usize);
 
+    /// Advances the internal cursor by `cnt` bytes if that many remain
+    ///
+    /// Otherwise returns the number of missing bytes and leaves `self` untouched
+    #[inline]
+    fn try_advance(&mut self, cnt: usize) -> Result<(), usize> {
+        let rem = self.remaining();
+
+        if cnt > rem {
+            return Err(cnt - rem);
+        }
+
+        self.advance(cnt);
+
+        Ok(())
+    }
+
     /// Returns true if there are any more bytes to consume
     fn has_remaining(&self) -> bool {
         self.remaining() > 0
@@ -120,6 +260,22 @@ pub trait Buf {
         }
     }
 
+    /// Copies the next `N` bytes out of `self` into an array
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `N` bytes remain
+    fn get_array<const N: usize>(&mut self) -> [u8; N]
+    where
+        Self: Sized,
+    {
+        let mut array = [0; N];
+
+        self.copy_to_slice(&mut array);
+
+        array
+    }
+
     /// Gets an unsigned 8 bit integer from `self`.
     fn get_u8(&mut self) -> u8 {
         if self.remaining() < 1 {
@@ -328,6 +484,275 @@ pub trait Buf {
         f64::from_bits(self.get_u64_ne())
     }
 
+    /// Gets an unsigned 8 bit integer from `self`, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_u8(&mut self) -> Result<u8, TryGetError> {
+        buf_try_get_impl!(self, u8, get_u8)
+    }
+
+    /// Gets a signed 8 bit integer from `self`, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_i8(&mut self) -> Result<i8, TryGetError> {
+        buf_try_get_impl!(self, i8, get_i8)
+    }
+
+    /// Gets an unsigned 16 bit integer from `self` in big-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_u16(&mut self) -> Result<u16, TryGetError> {
+        buf_try_get_impl!(self, u16, get_u16)
+    }
+
+    /// Gets an unsigned 16 bit integer from `self` in little-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_u16_le(&mut self) -> Result<u16, TryGetError> {
+        buf_try_get_impl!(self, u16, get_u16_le)
+    }
+
+    /// Gets an unsigned 16 bit integer from `self` in native-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_u16_ne(&mut self) -> Result<u16, TryGetError> {
+        buf_try_get_impl!(self, u16, get_u16_ne)
+    }
+
+    /// Gets an signed 16 bit integer from `self` in big-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_i16(&mut self) -> Result<i16, TryGetError> {
+        buf_try_get_impl!(self, i16, get_i16)
+    }
+
+    /// Gets an signed 16 bit integer from `self` in little-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_i16_le(&mut self) -> Result<i16, TryGetError> {
+        buf_try_get_impl!(self, i16, get_i16_le)
+    }
+
+    /// Gets an signed 16 bit integer from `self` in native-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_i16_ne(&mut self) -> Result<i16, TryGetError> {
+        buf_try_get_impl!(self, i16, get_i16_ne)
+    }
+
+    /// Gets an unsigned 32 bit integer from `self` in the big-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_u32(&mut self) -> Result<u32, TryGetError> {
+        buf_try_get_impl!(self, u32, get_u32)
+    }
+
+    /// Gets an unsigned 32 bit integer from `self` in the little-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_u32_le(&mut self) -> Result<u32, TryGetError> {
+        buf_try_get_impl!(self, u32, get_u32_le)
+    }
+
+    /// Gets an unsigned 32 bit integer from `self` in the native-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_u32_ne(&mut self) -> Result<u32, TryGetError> {
+        buf_try_get_impl!(self, u32, get_u32_ne)
+    }
+
+    /// Gets an signed 32 bit integer from `self` in the big-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_i32(&mut self) -> Result<i32, TryGetError> {
+        buf_try_get_impl!(self, i32, get_i32)
+    }
+
+    /// Gets an signed 32 bit integer from `self` in the little-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_i32_le(&mut self) -> Result<i32, TryGetError> {
+        buf_try_get_impl!(self, i32, get_i32_le)
+    }
+
+    /// Gets an signed 32 bit integer from `self` in the native-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_i32_ne(&mut self) -> Result<i32, TryGetError> {
+        buf_try_get_impl!(self, i32, get_i32_ne)
+    }
+
+    /// Gets an unsigned 64 bit integer from `self` in big-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_u64(&mut self) -> Result<u64, TryGetError> {
+        buf_try_get_impl!(self, u64, get_u64)
+    }
+
+    /// Gets an unsigned 64 bit integer from `self` in little-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_u64_le(&mut self) -> Result<u64, TryGetError> {
+        buf_try_get_impl!(self, u64, get_u64_le)
+    }
+
+    /// Gets an unsigned 64 bit integer from `self` in native-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_u64_ne(&mut self) -> Result<u64, TryGetError> {
+        buf_try_get_impl!(self, u64, get_u64_ne)
+    }
+
+    /// Gets an signed 64 bit integer from `self` in big-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_i64(&mut self) -> Result<i64, TryGetError> {
+        buf_try_get_impl!(self, i64, get_i64)
+    }
+
+    /// Gets an signed 64 bit integer from `self` in little-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_i64_le(&mut self) -> Result<i64, TryGetError> {
+        buf_try_get_impl!(self, i64, get_i64_le)
+    }
+
+    /// Gets an signed 64 bit integer from `self` in native-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_i64_ne(&mut self) -> Result<i64, TryGetError> {
+        buf_try_get_impl!(self, i64, get_i64_ne)
+    }
+
+    /// Gets an unsigned 128 bit integer from `self` in big-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_u128(&mut self) -> Result<u128, TryGetError> {
+        buf_try_get_impl!(self, u128, get_u128)
+    }
+
+    /// Gets an unsigned 128 bit integer from `self` in little-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_u128_le(&mut self) -> Result<u128, TryGetError> {
+        buf_try_get_impl!(self, u128, get_u128_le)
+    }
+
+    /// Gets an unsigned 128 bit integer from `self` in native-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_u128_ne(&mut self) -> Result<u128, TryGetError> {
+        buf_try_get_impl!(self, u128, get_u128_ne)
+    }
+
+    /// Gets an signed 128 bit integer from `self` in big-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_i128(&mut self) -> Result<i128, TryGetError> {
+        buf_try_get_impl!(self, i128, get_i128)
+    }
+
+    /// Gets an signed 128 bit integer from `self` in little-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_i128_le(&mut self) -> Result<i128, TryGetError> {
+        buf_try_get_impl!(self, i128, get_i128_le)
+    }
+
+    /// Gets an signed 128 bit integer from `self` in native-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_i128_ne(&mut self) -> Result<i128, TryGetError> {
+        buf_try_get_impl!(self, i128, get_i128_ne)
+    }
+
+    /// Gets an IEEE754 single-precision (4 bytes) floating point number from `self` in big-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_f32(&mut self) -> Result<f32, TryGetError> {
+        buf_try_get_impl!(self, f32, get_f32)
+    }
+
+    /// Gets an IEEE754 single-precision (4 bytes) floating point number from `self` in little-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_f32_le(&mut self) -> Result<f32, TryGetError> {
+        buf_try_get_impl!(self, f32, get_f32_le)
+    }
+
+    /// Gets an IEEE754 single-precision (4 bytes) floating point number from `self` in native-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_f32_ne(&mut self) -> Result<f32, TryGetError> {
+        buf_try_get_impl!(self, f32, get_f32_ne)
+    }
+
+    /// Gets an IEEE754 double-precision (8 bytes) floating point number from `self` in big-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_f64(&mut self) -> Result<f64, TryGetError> {
+        buf_try_get_impl!(self, f64, get_f64)
+    }
+
+    /// Gets an IEEE754 double-precision (8 bytes) floating point number from `self` in little-endian-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_f64_le(&mut self) -> Result<f64, TryGetError> {
+        buf_try_get_impl!(self, f64, get_f64_le)
+    }
+
+    /// Gets an IEEE754 double-precision (8 bytes) floating point number from `self` in native-endian byte order, returning an error instead of panicking if there are not enough remaining bytes.
+    fn try_get_f64_ne(&mut self) -> Result<f64, TryGetError> {
+        buf_try_get_impl!(self, f64, get_f64_ne)
+    }
+
+    /// Gets an unsigned LEB128 varint from `self`, reading at most 10 bytes.
+    fn get_uvarint(&mut self) -> u64 {
+        match self.try_get_uvarint() {
+            Ok(n) => n,
+            Err(VarintError::Truncated) => panic_advance(1, 0),
+            Err(VarintError::Overflow) => panic!("varint overflows 64 bits"),
+        }
+    }
+
+    /// Gets a zig-zag encoded signed LEB128 varint from `self`, reading at most 10 bytes.
+    fn get_ivarint(&mut self) -> i64 {
+        zigzag_decode(self.get_uvarint())
+    }
+
+    /// Gets an unsigned LEB128 varint from `self`, returning an error instead of panicking if the
+    /// buffer is truncated or the varint is malformed. The bytes read so far are consumed.
+    fn try_get_uvarint(&mut self) -> Result<u64, VarintError> {
+        let mut n = 0;
+
+        for i in 0..MAX_VARINT_LEN {
+            if !self.has_remaining() {
+                return Err(VarintError::Truncated);
+            }
+
+            let byte = self.get_u8();
+
+            // The last byte may only carry the most significant bit of a `u64`
+            if i == MAX_VARINT_LEN - 1 && byte > 1 {
+                return Err(VarintError::Overflow);
+            }
+
+            n |= u64::from(byte & 0x7f) << (7 * i);
+
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+
+        Err(VarintError::Overflow)
+    }
+
+    /// Gets a zig-zag encoded signed LEB128 varint from `self`, returning an error instead of
+    /// panicking if the buffer is truncated or the varint is malformed.
+    fn try_get_ivarint(&mut self) -> Result<i64, VarintError> {
+        self.try_get_uvarint().map(zigzag_decode)
+    }
+
+    /// Gets one UTF-8 encoded `char` from `self`, which may span multiple chunks.
+    ///
+    /// On a truncated sequence nothing is consumed. On an invalid sequence, the bytes up to the
+    /// offending one are consumed so that decoding can resume right after the error.
+    fn get_char(&mut self) -> Result<char, Utf8DecodeError> {
+        if !self.has_remaining() {
+            return Err(Utf8DecodeError::Truncated);
+        }
+
+        let lead = self.chunk()[0];
+
+        // Sequence length and the valid range of the second byte, which excludes overlong
+        // encodings, surrogates and code points above `char::MAX`
+        let (len, second) = match lead {
+            0x00..=0x7f => {
+                self.advance(1);
+
+                return Ok(lead as char);
+            }
+            0xc2..=0xdf => (2, 0x80..=0xbf),
+            0xe0 => (3, 0xa0..=0xbf),
+            0xe1..=0xec | 0xee..=0xef => (3, 0x80..=0xbf),
+            0xed => (3, 0x80..=0x9f),
+            0xf0 => (4, 0x90..=0xbf),
+            0xf1..=0xf3 => (4, 0x80..=0xbf),
+            0xf4 => (4, 0x80..=0x8f),
+            _ => {
+                self.advance(1);
+
+                return Err(Utf8DecodeError::InvalidLeadingByte(lead));
+            }
+        };
+
+        if self.remaining() < len {
+            return Err(Utf8DecodeError::Truncated);
+        }
+
+        self.advance(1);
+
+        let mut code = u32::from(lead) & (0x7f >> len);
+
+        for i in 1..len {
+            let byte = self.chunk()[0];
+            let valid = if i == 1 {
+                second.contains(&byte)
+            } else {
+                (0x80..=0xbf).contains(&byte)
+            };
+
+            if !valid {
+                return Err(Utf8DecodeError::InvalidContinuation(byte));
+            }
+
+            self.advance(1);
+
+            code = (code << 6) | u32::from(byte & 0x3f);
+        }
+
+        // SAFETY: the byte ranges above only accept valid scalar values
+        Ok(unsafe { char::from_u32_unchecked(code) })
+    }
+
     /// Consumes `len` bytes inside self and returns new instance of `Bytes` with this data.
     fn copy_to_bytes(&mut self, len: usize) -> Bytes {
         use super::BufMut;
@@ -343,6 +768,16 @@ pub trait Buf {
         ret.freeze()
     }
 
+    /// Consumes `len` bytes and returns them as `Bytes`, or returns `None` without consuming
+    /// anything if fewer than `len` bytes remain
+    fn try_copy_to_bytes(&mut self, len: usize) -> Option<Bytes> {
+        if self.remaining() < len {
+            return None;
+        }
+
+        Some(self.copy_to_bytes(len))
+    }
+
     /// Creates an adaptor which will read at most `limit` bytes from `self`.
     fn take(self, limit: usize) -> Take<Self>
     where
@@ -351,6 +786,24 @@ pub trait Buf {
         take::new(self, limit)
     }
 
+    /// Returns an iterator over the contiguous regions of the remaining data, in order, without
+    /// copying or advancing
+    fn chunk_iter(&self) -> ChunkIter<'_, Self>
+    where
+        Self: Sized,
+    {
+        ChunkIter::new(self)
+    }
+
+    /// Creates an iterator which consumes the buffer one contiguous region at a time, each
+    /// returned as `Bytes` through `copy_to_bytes`
+    fn chunks(self) -> Chunks<Self>
+    where
+        Self: Sized,
+    {
+        Chunks::new(self)
+    }
+
     /// Creates an adaptor which will chain this buffer with another.
     fn chain<U>(self, next: U) -> Chain<Self, U>
     where
@@ -383,6 +836,16 @@ macro_rules! deref_forward_buf {
             (**self).chunk()
         }
 
+        #[inline]
+        fn chunk_at(&self, offset: usize) -> &[u8] {
+            (**self).chunk_at(offset)
+        }
+
+        #[inline]
+        fn chunk_count(&self) -> usize {
+            (**self).chunk_count()
+        }
+
         #[cfg(feature = "std")]
         #[inline]
         fn chunks_vectored<'b>(&'b self, dst: &mut [IoSlice<'b>]) -> usize {
@@ -394,6 +857,11 @@ macro_rules! deref_forward_buf {
             (**self).advance(cnt)
         }
 
+        #[inline]
+        fn try_advance(&mut self, cnt: usize) -> Result<(), usize> {
+            (**self).try_advance(cnt)
+        }
+
         #[inline]
         fn has_remaining(&self) -> bool {
             (**self).has_remaining()
@@ -504,6 +972,36 @@ macro_rules! deref_forward_buf {
             (**self).get_i64_ne()
         }
 
+        #[inline]
+        fn get_u128(&mut self) -> u128 {
+            (**self).get_u128()
+        }
+
+        #[inline]
+        fn get_u128_le(&mut self) -> u128 {
+            (**self).get_u128_le()
+        }
+
+        #[inline]
+        fn get_u128_ne(&mut self) -> u128 {
+            (**self).get_u128_ne()
+        }
+
+        #[inline]
+        fn get_i128(&mut self) -> i128 {
+            (**self).get_i128()
+        }
+
+        #[inline]
+        fn get_i128_le(&mut self) -> i128 {
+            (**self).get_i128_le()
+        }
+
+        #[inline]
+        fn get_i128_ne(&mut self) -> i128 {
+            (**self).get_i128_ne()
+        }
+
         #[inline]
         fn get_uint(&mut self, nbytes: usize) -> u64 {
             (**self).get_uint(nbytes)
@@ -534,10 +1032,175 @@ macro_rules! deref_forward_buf {
             (**self).get_int_ne(nbytes)
         }
 
+        #[inline]
+        fn try_get_u8(&mut self) -> Result<u8, TryGetError> {
+            (**self).try_get_u8()
+        }
+
+        #[inline]
+        fn try_get_i8(&mut self) -> Result<i8, TryGetError> {
+            (**self).try_get_i8()
+        }
+
+        #[inline]
+        fn try_get_u16(&mut self) -> Result<u16, TryGetError> {
+            (**self).try_get_u16()
+        }
+
+        #[inline]
+        fn try_get_u16_le(&mut self) -> Result<u16, TryGetError> {
+            (**self).try_get_u16_le()
+        }
+
+        #[inline]
+        fn try_get_u16_ne(&mut self) -> Result<u16, TryGetError> {
+            (**self).try_get_u16_ne()
+        }
+
+        #[inline]
+        fn try_get_i16(&mut self) -> Result<i16, TryGetError> {
+            (**self).try_get_i16()
+        }
+
+        #[inline]
+        fn try_get_i16_le(&mut self) -> Result<i16, TryGetError> {
+            (**self).try_get_i16_le()
+        }
+
+        #[inline]
+        fn try_get_i16_ne(&mut self) -> Result<i16, TryGetError> {
+            (**self).try_get_i16_ne()
+        }
+
+        #[inline]
+        fn try_get_u32(&mut self) -> Result<u32, TryGetError> {
+            (**self).try_get_u32()
+        }
+
+        #[inline]
+        fn try_get_u32_le(&mut self) -> Result<u32, TryGetError> {
+            (**self).try_get_u32_le()
+        }
+
+        #[inline]
+        fn try_get_u32_ne(&mut self) -> Result<u32, TryGetError> {
+            (**self).try_get_u32_ne()
+        }
+
+        #[inline]
+        fn try_get_i32(&mut self) -> Result<i32, TryGetError> {
+            (**self).try_get_i32()
+        }
+
+        #[inline]
+        fn try_get_i32_le(&mut self) -> Result<i32, TryGetError> {
+            (**self).try_get_i32_le()
+        }
+
+        #[inline]
+        fn try_get_i32_ne(&mut self) -> Result<i32, TryGetError> {
+            (**self).try_get_i32_ne()
+        }
+
+        #[inline]
+        fn try_get_u64(&mut self) -> Result<u64, TryGetError> {
+            (**self).try_get_u64()
+        }
+
+        #[inline]
+        fn try_get_u64_le(&mut self) -> Result<u64, TryGetError> {
+            (**self).try_get_u64_le()
+        }
+
+        #[inline]
+        fn try_get_u64_ne(&mut self) -> Result<u64, TryGetError> {
+            (**self).try_get_u64_ne()
+        }
+
+        #[inline]
+        fn try_get_i64(&mut self) -> Result<i64, TryGetError> {
+            (**self).try_get_i64()
+        }
+
+        #[inline]
+        fn try_get_i64_le(&mut self) -> Result<i64, TryGetError> {
+            (**self).try_get_i64_le()
+        }
+
+        #[inline]
+        fn try_get_i64_ne(&mut self) -> Result<i64, TryGetError> {
+            (**self).try_get_i64_ne()
+        }
+
+        #[inline]
+        fn try_get_u128(&mut self) -> Result<u128, TryGetError> {
+            (**self).try_get_u128()
+        }
+
+        #[inline]
+        fn try_get_u128_le(&mut self) -> Result<u128, TryGetError> {
+            (**self).try_get_u128_le()
+        }
+
+        #[inline]
+        fn try_get_u128_ne(&mut self) -> Result<u128, TryGetError> {
+            (**self).try_get_u128_ne()
+        }
+
+        #[inline]
+        fn try_get_i128(&mut self) -> Result<i128, TryGetError> {
+            (**self).try_get_i128()
+        }
+
+        #[inline]
+        fn try_get_i128_le(&mut self) -> Result<i128, TryGetError> {
+            (**self).try_get_i128_le()
+        }
+
+        #[inline]
+        fn try_get_i128_ne(&mut self) -> Result<i128, TryGetError> {
+            (**self).try_get_i128_ne()
+        }
+
+        #[inline]
+        fn try_get_f32(&mut self) -> Result<f32, TryGetError> {
+            (**self).try_get_f32()
+        }
+
+        #[inline]
+        fn try_get_f32_le(&mut self) -> Result<f32, TryGetError> {
+            (**self).try_get_f32_le()
+        }
+
+        #[inline]
+        fn try_get_f32_ne(&mut self) -> Result<f32, TryGetError> {
+            (**self).try_get_f32_ne()
+        }
+
+        #[inline]
+        fn try_get_f64(&mut self) -> Result<f64, TryGetError> {
+            (**self).try_get_f64()
+        }
+
+        #[inline]
+        fn try_get_f64_le(&mut self) -> Result<f64, TryGetError> {
+            (**self).try_get_f64_le()
+        }
+
+        #[inline]
+        fn try_get_f64_ne(&mut self) -> Result<f64, TryGetError> {
+            (**self).try_get_f64_ne()
+        }
+
         #[inline]
         fn copy_to_bytes(&mut self, len: usize) -> Bytes {
             (**self).copy_to_bytes(len)
         }
+
+        #[inline]
+        fn try_copy_to_bytes(&mut self, len: usize) -> Option<Bytes> {
+            (**self).try_copy_to_bytes(len)
+        }
     };
 }
 
@@ -619,3 +1282,237 @@ impl<T: AsRef<[u8]>> Buf for std::io::Cursor<T> {
 // The existence of this function makes the compiler catch if the Buf
 // trait is "object-safe" or not.
 fn _assert_trait_object(_b: &dyn Buf) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BufMut;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_uvarint_boundaries() {
+        let cases: [(u64, &[u8]); 4] = [
+            (0, &[0x00]),
+            (127, &[0x7f]),
+            (128, &[0x80, 0x01]),
+            (
+                u64::MAX,
+                &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+            ),
+        ];
+
+        for (n, encoded) in cases {
+            let mut buf = Vec::new();
+
+            buf.put_uvarint(n);
+            assert_eq!(buf, encoded);
+
+            let mut src = &buf[..];
+
+            assert_eq!(src.get_uvarint(), n);
+            assert!(!src.has_remaining());
+        }
+    }
+
+    #[test]
+    fn test_ivarint_round_trip() {
+        for n in [0, -1, 1, -64, 64, i64::MIN, i64::MAX] {
+            let mut buf = Vec::new();
+
+            buf.put_ivarint(n);
+
+            assert_eq!((&buf[..]).get_ivarint(), n);
+        }
+
+        let mut buf = Vec::new();
+
+        buf.put_ivarint(-1);
+        assert_eq!(buf, [0x01]);
+    }
+
+    #[test]
+    fn test_try_get_uvarint_malformed() {
+        let mut truncated: &[u8] = &[0x80, 0x80];
+
+        assert_eq!(truncated.try_get_uvarint(), Err(VarintError::Truncated));
+
+        let mut overflow: &[u8] = &[0xff; 11];
+
+        assert_eq!(overflow.try_get_uvarint(), Err(VarintError::Overflow));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_get_uvarint_truncated_panics() {
+        let mut buf: &[u8] = &[0xff, 0xff];
+
+        buf.get_uvarint();
+    }
+
+    #[test]
+    fn test_try_get_short_buffer() {
+        let mut src = &[0x01, 0x02, 0x03][..];
+
+        assert_eq!(
+            src.try_get_u32(),
+            Err(TryGetError {
+                requested: 4,
+                available: 3,
+            })
+        );
+        // Nothing is consumed on error
+        assert_eq!(src.remaining(), 3);
+
+        assert_eq!(src.try_get_u16(), Ok(0x0102));
+        assert_eq!(src.try_get_u8(), Ok(0x03));
+        assert!(src.try_get_i8().is_err());
+    }
+
+    #[test]
+    fn test_try_get_forwarded() {
+        let mut src: Box<&[u8]> = Box::new(&[0x00, 0x00, 0x80, 0x3f][..]);
+
+        assert_eq!(Buf::try_get_f32_le(&mut src), Ok(1.0));
+        assert_eq!(
+            src.try_get_f64(),
+            Err(TryGetError {
+                requested: 8,
+                available: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_get_char() {
+        let mut src = "a\u{e9}\u{20ac}\u{1f980}".as_bytes();
+
+        assert_eq!(src.get_char(), Ok('a'));
+        assert_eq!(src.get_char(), Ok('\u{e9}'));
+        assert_eq!(src.get_char(), Ok('\u{20ac}'));
+        assert_eq!(src.get_char(), Ok('\u{1f980}'));
+        assert_eq!(src.get_char(), Err(Utf8DecodeError::Truncated));
+    }
+
+    #[test]
+    fn test_get_char_across_chunks() {
+        let bytes = "\u{1f980}".as_bytes();
+        let mut src = (&bytes[..1]).chain(&bytes[1..3]).chain(&bytes[3..]);
+
+        assert_eq!(src.get_char(), Ok('\u{1f980}'));
+        assert!(!src.has_remaining());
+    }
+
+    #[test]
+    fn test_get_char_invalid() {
+        // Truncated 3-byte sequence, nothing consumed
+        let mut src = &[0xe2, 0x82][..];
+
+        assert_eq!(src.get_char(), Err(Utf8DecodeError::Truncated));
+        assert_eq!(src.remaining(), 2);
+
+        // Invalid continuation, resumes at the offending byte
+        let mut src = &[0xe2, 0x41, 0x42][..];
+
+        assert_eq!(
+            src.get_char(),
+            Err(Utf8DecodeError::InvalidContinuation(0x41))
+        );
+        assert_eq!(src.get_char(), Ok('A'));
+
+        // Surrogates and overlong encodings are rejected
+        assert_eq!(
+            (&[0xed, 0xa0, 0x80][..]).get_char(),
+            Err(Utf8DecodeError::InvalidContinuation(0xa0))
+        );
+        assert_eq!(
+            (&[0xc0, 0x80][..]).get_char(),
+            Err(Utf8DecodeError::InvalidLeadingByte(0xc0))
+        );
+    }
+
+    #[test]
+    fn test_get_u128_forwarded() {
+        struct Fixed;
+
+        impl Buf for Fixed {
+            fn remaining(&self) -> usize {
+                16
+            }
+
+            fn chunk(&self) -> &[u8] {
+                &[0; 16]
+            }
+
+            fn advance(&mut self, _cnt: usize) {}
+
+            fn get_u128(&mut self) -> u128 {
+                u128::MAX
+            }
+
+            fn get_i128_le(&mut self) -> i128 {
+                -1
+            }
+        }
+
+        // The default implementations would read zeroes
+        let mut buf: Box<dyn Buf> = Box::new(Fixed);
+
+        assert_eq!(buf.get_u128(), u128::MAX);
+        assert_eq!(Buf::get_i128_le(&mut buf), -1);
+        assert_eq!(buf.get_u128_ne(), 0);
+    }
+
+    #[test]
+    fn test_try_advance() {
+        let mut buf = Bytes::from_static(b"abcd");
+
+        assert_eq!(buf.try_advance(7), Err(3));
+        assert_eq!(buf, b"abcd");
+        assert_eq!(buf.try_advance(1), Ok(()));
+        assert_eq!(buf, b"bcd");
+
+        // Through a chain the shortfall is counted over both halves
+        let mut chain = Bytes::from_static(b"ab").chain(&b"cd"[..]);
+
+        assert_eq!(chain.try_advance(5), Err(1));
+        assert_eq!(chain.remaining(), 4);
+        assert_eq!(chain.try_advance(3), Ok(()));
+        assert_eq!(chain.chunk(), b"d");
+    }
+
+    #[test]
+    fn test_try_copy_to_bytes() {
+        let mut buf = Bytes::from_static(b"abc").chain(&b"def"[..]);
+
+        assert_eq!(buf.try_copy_to_bytes(7), None);
+        assert_eq!(buf.remaining(), 6);
+        assert_eq!(buf.chunk(), b"abc");
+
+        assert_eq!(buf.try_copy_to_bytes(4).unwrap(), "abcd");
+        assert_eq!(buf.try_copy_to_bytes(3), None);
+        assert_eq!(buf.chunk(), b"ef");
+
+        // Through the deref forwarding
+        let mut boxed: Box<dyn Buf> = Box::new(&b"xy"[..]);
+
+        assert_eq!(boxed.try_copy_to_bytes(3), None);
+        assert_eq!(boxed.try_copy_to_bytes(2).unwrap(), "xy");
+    }
+
+    #[test]
+    fn test_get_array() {
+        let mut buf = Bytes::from_static(b"ab").chain(&b"cdef"[..]);
+
+        assert_eq!(buf.get_array::<3>(), *b"abc");
+        assert_eq!(buf.get_array::<0>(), [0u8; 0]);
+        assert_eq!(buf.get_array::<3>(), *b"def");
+    }
+
+    #[test]
+    #[should_panic(expected = "advance out of bounds")]
+    fn test_get_array_short() {
+        let mut buf = &b"abc"[..];
+
+        buf.get_array::<4>();
+    }
+}
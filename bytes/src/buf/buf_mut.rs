@@ -1,4 +1,6 @@
-use super::{limit, Buf, Chain, Limit, UninitSlice};
+use super::{
+    buf_impl::MAX_VARINT_LEN, endian, limit, Buf, Chain, Endian, Endianness, Limit, UninitSlice,
+};
 #[cfg(feature = "std")]
 use crate::buf::{writer, Writer};
 use crate::{panic_advance, panic_does_not_fit};
@@ -17,6 +19,26 @@ pub unsafe trait BufMut {
     /// Advance the internal cursor of the BufMut
     unsafe fn advance_mut(&mut self, cnt: usize);
 
+    /// Advances the internal cursor by `cnt` bytes if `remaining_mut()` allows it
+    ///
+    /// Otherwise returns the number of missing bytes and leaves `self` untouched
+    ///
+    /// # Safety
+    ///
+    /// As with `advance_mut`, the next `cnt` bytes must have been initialized
+    #[inline]
+    unsafe fn try_advance_mut(&mut self, cnt: usize) -> Result<(), usize> {
+        let rem = self.remaining_mut();
+
+        if cnt > rem {
+            return Err(cnt - rem);
+        }
+
+        unsafe { self.advance_mut(cnt) };
+
+        Ok(())
+    }
+
     /// Returns true if there is space in `self` for more bytes
     #[inline]
     fn has_remaining_mut(&self) -> bool {
@@ -54,6 +76,21 @@ pub unsafe trait BufMut {
         }
     }
 
+    /// Transfer at most `n` bytes into `self` from `src`, advancing both by the number of bytes
+    /// copied. Fewer are copied if `src` has less than `n` remaining
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` has less room than the bytes to copy
+    #[inline]
+    fn put_take<T>(&mut self, src: &mut T, n: usize)
+    where
+        T: super::Buf + ?Sized,
+        Self: Sized,
+    {
+        self.put(src.take(n))
+    }
+
     /// Transfer bytes into `self` from `src` and advance the cursor by the number of bytes written
     #[inline]
     fn put_slice(&mut self, mut src: &[u8]) {
@@ -73,6 +110,23 @@ pub unsafe trait BufMut {
         }
     }
 
+    /// Transfer the bytes of each slice into `self` in order, checking the room for all of them
+    /// up front
+    #[inline]
+    fn put_slices(&mut self, slices: &[&[u8]]) {
+        let cnt = slices
+            .iter()
+            .fold(0usize, |cnt, src| cnt.saturating_add(src.len()));
+
+        if self.remaining_mut() < cnt {
+            panic_advance(cnt, self.remaining_mut());
+        }
+
+        for src in slices {
+            self.put_slice(src);
+        }
+    }
+
     /// Puts `cnt` bytes `val` into `self`
     #[inline]
     fn put_bytes(&mut self, val: u8, mut cnt: usize) {
@@ -94,6 +148,72 @@ pub unsafe trait BufMut {
         }
     }
 
+    /// Writes `cnt` bytes drawn from `rng` into `self`, filling `chunk_mut()` in place
+    #[cfg(feature = "rand")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+    fn put_random<R>(&mut self, rng: &mut R, mut cnt: usize)
+    where
+        R: rand_core::RngCore + ?Sized,
+        Self: Sized,
+    {
+        if self.remaining_mut() < cnt {
+            panic_advance(cnt, self.remaining_mut());
+        }
+
+        while cnt > 0 {
+            let dst = self.chunk_mut();
+            let dst_len = usize::min(dst.len(), cnt);
+
+            // SAFETY: The pointer is valid for `dst_len <= dst.len()` bytes, which are zeroed
+            // first so the slice handed to the rng is initialized
+            unsafe {
+                ptr::write_bytes(dst.as_mut_ptr(), 0, dst_len);
+                rng.fill_bytes(core::slice::from_raw_parts_mut(dst.as_mut_ptr(), dst_len));
+            }
+
+            // SAFETY: We just initialized `dst_len` bytes in `dst`
+            unsafe { self.advance_mut(dst_len) };
+
+            cnt -= dst_len
+        }
+    }
+
+    /// Writes the UTF-8 bytes of a string slice into `self`
+    #[inline]
+    fn put_str(&mut self, s: &str) {
+        self.put_slice(s.as_bytes())
+    }
+
+    /// Writes a `char` into `self` encoded as UTF-8, taking one to four bytes
+    #[inline]
+    fn put_char(&mut self, c: char) {
+        let mut buf = [0; 4];
+
+        self.put_slice(c.encode_utf8(&mut buf).as_bytes())
+    }
+
+    /// Writes `src` followed by as many `pad` bytes as needed to fill `width` bytes
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` is longer than `width`, or if `self` doesn't have `width` bytes of room
+    #[inline]
+    fn put_padded(&mut self, src: &[u8], width: usize, pad: u8) {
+        assert!(
+            src.len() <= width,
+            "put_padded: source longer than width: {:?} > {:?}",
+            src.len(),
+            width
+        );
+
+        if self.remaining_mut() < width {
+            panic_advance(width, self.remaining_mut());
+        }
+
+        self.put_slice(src);
+        self.put_bytes(pad, width - src.len());
+    }
+
     /// Writes an unsigned 8-bit integer into `self`
     #[inline]
     fn put_u8(&mut self, n: u8) {
@@ -360,6 +480,39 @@ pub unsafe trait BufMut {
         self.put_u64_ne(n.to_bits());
     }
 
+    /// Writes an unsigned LEB128 varint to `self`, using between 1 and 10 bytes.
+    #[inline]
+    fn put_uvarint(&mut self, mut n: u64) {
+        let mut buf = [0; MAX_VARINT_LEN];
+        let mut len = 0;
+
+        while n >= 0x80 {
+            buf[len] = (n as u8) | 0x80;
+            n >>= 7;
+            len += 1;
+        }
+
+        buf[len] = n as u8;
+
+        self.put_slice(&buf[..=len]);
+    }
+
+    /// Writes a signed integer to `self` as a zig-zag encoded LEB128 varint.
+    #[inline]
+    fn put_ivarint(&mut self, n: i64) {
+        self.put_uvarint(((n << 1) ^ (n >> 63)) as u64);
+    }
+
+    /// Creates an adapter whose plain `put_*` methods write integers and floats in the given
+    /// byte order
+    #[inline]
+    fn with_endian(self, endian: Endianness) -> Endian<Self>
+    where
+        Self: Sized,
+    {
+        endian::new(self, endian)
+    }
+
     /// Creates an adapter which can write at most `limit` bytes to `self`
     #[inline]
     fn limit(self, limit: usize) -> Limit<Self>
@@ -408,11 +561,26 @@ macro_rules! deref_forward_bufmut {
             unsafe { (**self).advance_mut(cnt) }
         }
 
+        #[inline]
+        unsafe fn try_advance_mut(&mut self, cnt: usize) -> Result<(), usize> {
+            unsafe { (**self).try_advance_mut(cnt) }
+        }
+
         #[inline]
         fn put_slice(&mut self, src: &[u8]) {
             (**self).put_slice(src)
         }
 
+        #[inline]
+        fn put_slices(&mut self, slices: &[&[u8]]) {
+            (**self).put_slices(slices)
+        }
+
+        #[inline]
+        fn put_str(&mut self, s: &str) {
+            (**self).put_str(s)
+        }
+
         #[inline]
         fn put_u8(&mut self, n: u8) {
             (**self).put_u8(n)
@@ -512,6 +680,36 @@ macro_rules! deref_forward_bufmut {
         fn put_i64_ne(&mut self, n: i64) {
             (**self).put_i64_ne(n)
         }
+
+        #[inline]
+        fn put_u128(&mut self, n: u128) {
+            (**self).put_u128(n)
+        }
+
+        #[inline]
+        fn put_u128_le(&mut self, n: u128) {
+            (**self).put_u128_le(n)
+        }
+
+        #[inline]
+        fn put_u128_ne(&mut self, n: u128) {
+            (**self).put_u128_ne(n)
+        }
+
+        #[inline]
+        fn put_i128(&mut self, n: i128) {
+            (**self).put_i128(n)
+        }
+
+        #[inline]
+        fn put_i128_le(&mut self, n: i128) {
+            (**self).put_i128_le(n)
+        }
+
+        #[inline]
+        fn put_i128_ne(&mut self, n: i128) {
+            (**self).put_i128_ne(n)
+        }
     };
 }
 
@@ -688,6 +886,15 @@ unsafe impl BufMut for Vec<u8> {
         }
     }
 
+    #[inline]
+    fn put_slices(&mut self, slices: &[&[u8]]) {
+        self.reserve(slices.iter().map(|src| src.len()).sum());
+
+        for src in slices {
+            self.extend_from_slice(src);
+        }
+    }
+
     #[inline]
     fn put_slice(&mut self, src: &[u8]) {
         self.extend_from_slice(src);
@@ -704,3 +911,286 @@ unsafe impl BufMut for Vec<u8> {
 
 // The existence of this function makes the compiler catch if the BufMut trait is "object-safe" or not
 fn _assert_trait_object(_b: &dyn BufMut) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BytesMut;
+    use alloc::vec;
+
+    const LEN: usize = 4 * 1024 * 1024;
+
+    fn pattern(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_put_slice_large_init() {
+        let src = pattern(LEN);
+        let mut dst = vec![0xaa; LEN + 16];
+
+        {
+            let mut buf = &mut dst[..];
+
+            buf.put_slice(&src);
+
+            // The remaining slice must be the untouched tail
+            assert_eq!(buf.remaining_mut(), 16);
+            assert!(buf.iter().all(|&b| b == 0xaa));
+
+            buf.put_slice(&[1; 16]);
+            assert_eq!(buf.remaining_mut(), 0);
+        }
+
+        assert_eq!(&dst[..LEN], &src[..]);
+        assert_eq!(&dst[LEN..], &[1; 16]);
+    }
+
+    #[test]
+    fn test_put_slice_large_uninit() {
+        let src = pattern(LEN);
+        let mut dst = vec![MaybeUninit::new(0xaa); LEN + 16];
+
+        {
+            let mut buf = &mut dst[..];
+
+            buf.put_slice(&src);
+
+            assert_eq!(buf.remaining_mut(), 16);
+            assert!(buf.iter().all(|b| unsafe { b.assume_init() } == 0xaa));
+
+            buf.put_slice(&[1; 16]);
+            assert_eq!(buf.remaining_mut(), 0);
+        }
+
+        let dst: Vec<u8> = dst.iter().map(|b| unsafe { b.assume_init() }).collect();
+
+        assert_eq!(&dst[..LEN], &src[..]);
+        assert_eq!(&dst[LEN..], &[1; 16]);
+    }
+
+    #[test]
+    fn test_put_u128_forwarded() {
+        struct Counting(Vec<u8>, usize);
+
+        unsafe impl BufMut for Counting {
+            fn remaining_mut(&self) -> usize {
+                self.0.remaining_mut()
+            }
+
+            unsafe fn advance_mut(&mut self, cnt: usize) {
+                unsafe { self.0.advance_mut(cnt) }
+            }
+
+            fn chunk_mut(&mut self) -> &mut UninitSlice {
+                self.0.chunk_mut()
+            }
+
+            fn put_u128(&mut self, n: u128) {
+                self.1 += 1;
+                self.0.put_slice(&n.to_be_bytes());
+            }
+        }
+
+        let mut counting = Counting(Vec::new(), 0);
+        let mut buf: Box<&mut dyn BufMut> = Box::new(&mut counting);
+
+        buf.put_u128(1);
+        BufMut::put_u128(&mut buf, 2);
+        buf.put_i128_le(3);
+
+        drop(buf);
+
+        assert_eq!(counting.1, 2);
+        assert_eq!(counting.0.len(), 48);
+    }
+
+    #[test]
+    fn test_put_str_and_char() {
+        let mut buf = Vec::new();
+
+        buf.put_str("ok: ");
+        buf.put_char('a');
+        buf.put_char('é');
+        buf.put_char('€');
+        buf.put_char('🦀');
+
+        assert_eq!(buf, "ok: aé€🦀".as_bytes());
+        assert_eq!(buf.len(), 4 + 1 + 2 + 3 + 4);
+
+        // Through the deref forwarding
+        let mut boxed: Box<Vec<u8>> = Box::default();
+
+        boxed.put_str("grüß");
+
+        assert_eq!(*boxed, "grüß".as_bytes());
+    }
+
+    #[test]
+    #[should_panic(expected = "advance out of bounds")]
+    fn test_put_char_does_not_fit() {
+        let mut dst = [0u8; 2];
+        let mut buf = &mut dst[..];
+
+        buf.put_char('€');
+    }
+
+    #[test]
+    fn test_try_advance_mut() {
+        let mut dst = [0u8; 4];
+        let mut buf = &mut dst[..];
+
+        // The slice is always initialized
+        assert_eq!(unsafe { buf.try_advance_mut(6) }, Err(2));
+        assert_eq!(buf.remaining_mut(), 4);
+        assert_eq!(unsafe { buf.try_advance_mut(3) }, Ok(()));
+        assert_eq!(buf.remaining_mut(), 1);
+        assert_eq!(unsafe { buf.try_advance_mut(2) }, Err(1));
+        assert_eq!(buf.remaining_mut(), 1);
+    }
+
+    #[test]
+    fn test_put_slices_reserves_once() {
+        let slices: [&[u8]; 3] = [b"head:", b" value\r\n", b"body body"];
+        let total = 5 + 8 + 9;
+
+        // Growing once per slice would overshoot to a larger capacity
+        let mut vec = Vec::new();
+
+        vec.put_slices(&slices);
+
+        assert_eq!(vec, b"head: value\r\nbody body");
+        assert_eq!(vec.capacity(), total);
+
+        let mut buf = BytesMut::new();
+
+        buf.put_slices(&slices);
+
+        assert_eq!(buf, b"head: value\r\nbody body");
+        assert_eq!(buf.capacity(), total);
+    }
+
+    #[test]
+    #[should_panic(expected = "advance out of bounds")]
+    fn test_put_slices_does_not_fit() {
+        let mut dst = [0u8; 8];
+        let mut buf = &mut dst[..];
+
+        buf.put_slices(&[b"abcd", b"efgh", b"i"]);
+    }
+
+    #[test]
+    fn test_put_take() {
+        let mut src = &b"0123456789"[..];
+        let mut dst = [0u8; 3];
+        let mut buf = &mut dst[..];
+
+        // Only the bytes taken need to fit
+        buf.put_take(&mut src, 3);
+
+        assert_eq!(buf.remaining_mut(), 0);
+        assert_eq!(&dst, b"012");
+        assert_eq!(src, b"3456789");
+
+        // Capped by what the source has left
+        let mut vec = Vec::new();
+
+        vec.put_take(&mut src, 100);
+
+        assert_eq!(vec, b"3456789");
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "advance out of bounds")]
+    fn test_put_take_does_not_fit() {
+        let mut src = &b"0123456789"[..];
+        let mut dst = [0u8; 3];
+        let mut buf = &mut dst[..];
+
+        buf.put_take(&mut src, 4);
+    }
+
+    #[test]
+    fn test_put_padded() {
+        let mut buf = Vec::new();
+
+        buf.put_padded(b"name", 4, b' ');
+        buf.put_padded(b"ab", 5, 0);
+        buf.put_padded(b"", 2, b'-');
+
+        assert_eq!(buf, b"nameab\0\0\0--");
+    }
+
+    #[test]
+    #[should_panic(expected = "source longer than width")]
+    fn test_put_padded_too_long() {
+        let mut buf = Vec::new();
+
+        buf.put_padded(b"toolong", 4, 0);
+    }
+
+    #[cfg(feature = "rand")]
+    struct XorShift(u64);
+
+    #[cfg(feature = "rand")]
+    impl rand_core::RngCore for XorShift {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dest)
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_put_random() {
+        let fill = |seed, cnt| {
+            let mut buf = Vec::new();
+
+            buf.put_random(&mut XorShift(seed), cnt);
+            buf
+        };
+
+        assert_eq!(fill(1, 1000).len(), 1000);
+        assert_eq!(fill(1, 1000), fill(1, 1000));
+        assert_ne!(fill(1, 1000), fill(2, 1000));
+
+        let fill_after_header = |seed| {
+            let mut buf = BytesMut::new();
+
+            buf.put_slice(b"hdr");
+            buf.put_random(&mut XorShift(seed), 61);
+            buf
+        };
+        let buf = fill_after_header(7);
+
+        assert_eq!(buf.len(), 64);
+        assert_eq!(&buf[..3], b"hdr");
+        assert_eq!(buf, fill_after_header(7));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    #[should_panic]
+    fn test_put_random_overflow() {
+        let mut buf = [0u8; 4];
+
+        (&mut buf[..]).put_random(&mut XorShift(1), 5);
+    }
+}
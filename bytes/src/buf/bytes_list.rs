@@ -0,0 +1,261 @@
+use super::{Buf, BufMut};
+use crate::{Bytes, BytesMut};
+use alloc::collections::VecDeque;
+#[cfg(feature = "std")]
+use std::io::IoSlice;
+
+/// A `Buf` made of any number of `Bytes` segments
+///
+/// Unlike nesting [`Chain`](super::Chain)s, segments can be pushed at either end at runtime and
+/// are read in order without copying them into one contiguous buffer.
+#[derive(Debug, Default, Clone)]
+pub struct BytesList {
+    segments: VecDeque<Bytes>,
+    remaining: usize,
+}
+
+impl BytesList {
+    /// Creates a new empty `BytesList`
+    pub fn new() -> BytesList {
+        BytesList::default()
+    }
+
+    /// Appends a segment at the end of the list
+    pub fn push_back(&mut self, bytes: Bytes) {
+        // Empty segments are dropped so `chunk()` only returns an empty slice at the end
+        if !bytes.is_empty() {
+            self.remaining += bytes.len();
+            self.segments.push_back(bytes);
+        }
+    }
+
+    /// Prepends a segment at the start of the list
+    pub fn push_front(&mut self, bytes: Bytes) {
+        if !bytes.is_empty() {
+            self.remaining += bytes.len();
+            self.segments.push_front(bytes);
+        }
+    }
+
+    /// Returns the number of segments in the list
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+}
+
+impl Buf for BytesList {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    #[inline]
+    fn chunk(&self) -> &[u8] {
+        match self.segments.front() {
+            Some(bytes) => bytes.as_slice(),
+            None => &[],
+        }
+    }
+
+    fn chunk_at(&self, mut offset: usize) -> &[u8] {
+        for bytes in &self.segments {
+            if offset < bytes.len() {
+                return &bytes[offset..];
+            }
+
+            offset -= bytes.len();
+        }
+
+        &[]
+    }
+
+    #[inline]
+    fn chunk_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    #[cfg(feature = "std")]
+    fn chunks_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        let mut n = 0;
+
+        for (slot, bytes) in dst.iter_mut().zip(&self.segments) {
+            *slot = IoSlice::new(bytes);
+            n += 1;
+        }
+
+        n
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        assert!(
+            cnt <= self.remaining,
+            "cannot advance past `remaining`: {:?} <= {:?}",
+            cnt,
+            self.remaining
+        );
+
+        self.remaining -= cnt;
+
+        while cnt > 0 {
+            let front = self.segments.front_mut().unwrap();
+
+            if front.len() > cnt {
+                front.advance(cnt);
+                return;
+            }
+
+            cnt -= front.len();
+            self.segments.pop_front();
+        }
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        assert!(
+            len <= self.remaining,
+            "`len` greater than remaining: {:?} <= {:?}",
+            len,
+            self.remaining
+        );
+
+        let front_len = self.chunk().len();
+
+        // Fast paths, the bytes come from a single segment and need no copy
+        if front_len == len {
+            self.remaining -= len;
+
+            return self.segments.pop_front().unwrap_or_default();
+        }
+
+        if front_len > len {
+            self.remaining -= len;
+
+            return self.segments.front_mut().unwrap().split_to(len);
+        }
+
+        let mut ret = BytesMut::with_capacity(len);
+
+        ret.put(Buf::take(&mut *self, len));
+
+        ret.freeze()
+    }
+}
+
+impl From<Bytes> for BytesList {
+    fn from(bytes: Bytes) -> BytesList {
+        let mut list = BytesList::new();
+
+        list.push_back(bytes);
+
+        list
+    }
+}
+
+impl FromIterator<Bytes> for BytesList {
+    fn from_iter<I: IntoIterator<Item = Bytes>>(iter: I) -> BytesList {
+        let mut list = BytesList::new();
+
+        for bytes in iter {
+            list.push_back(bytes);
+        }
+
+        list
+    }
+}
+
+impl Extend<Bytes> for BytesList {
+    fn extend<I: IntoIterator<Item = Bytes>>(&mut self, iter: I) {
+        for bytes in iter {
+            self.push_back(bytes);
+        }
+    }
+}
+
+// ---- Tests ----
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn list() -> BytesList {
+        [
+            Bytes::from_static(b"hello"),
+            Bytes::new(),
+            Bytes::from_static(b", "),
+            Bytes::from_static(b"world"),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_chunks_vectored_three_segments() {
+        use std::io::Write;
+
+        let list = list();
+        let mut slices = [IoSlice::new(&[]); 4];
+
+        assert_eq!(list.segment_count(), 3);
+        assert_eq!(list.remaining(), 12);
+        assert_eq!(list.chunks_vectored(&mut slices), 3);
+
+        let mut dst = Vec::new();
+
+        assert_eq!(dst.write_vectored(&slices[..3]).unwrap(), 12);
+        assert_eq!(dst, b"hello, world");
+    }
+
+    #[test]
+    fn test_advance_across_segments() {
+        let mut list = list();
+
+        list.advance(6);
+        assert_eq!(list.chunk(), b" ");
+        assert_eq!(list.remaining(), 6);
+
+        list.push_front(Bytes::from_static(b"big"));
+        assert_eq!(list.copy_to_bytes(5), b"big w");
+        assert_eq!(list.remaining(), 4);
+        assert_eq!(list.chunk(), b"orld");
+    }
+
+    #[test]
+    fn test_copy_to_bytes_whole_segment() {
+        let first = Bytes::from_static(b"hello");
+        let second = Bytes::from_static(b"world");
+        let mut list = BytesList::from(first.clone());
+
+        list.push_back(second.clone());
+
+        assert_eq!(list.copy_to_bytes(5).as_ptr(), first.as_ptr());
+        assert_eq!(list.copy_to_bytes(2).as_ptr(), second.as_ptr());
+        assert_eq!(list.remaining(), 3);
+    }
+
+    #[test]
+    fn test_chunk_iter() {
+        let mut list = list();
+
+        list.advance(3);
+
+        let regions: Vec<&[u8]> = list.chunk_iter().collect();
+
+        assert_eq!(regions, [&b"lo"[..], b", ", b"world"]);
+        assert_eq!(list.remaining(), 9);
+    }
+
+    #[test]
+    fn test_chunk_count() {
+        let mut list = list();
+
+        assert_eq!(list.chunk_count(), 3);
+
+        list.advance(5);
+
+        assert_eq!(list.chunk_count(), 2);
+
+        list.advance(list.remaining());
+
+        assert_eq!(list.chunk_count(), 0);
+    }
+}
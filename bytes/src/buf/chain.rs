@@ -63,6 +63,20 @@ where
         }
     }
 
+    fn chunk_at(&self, offset: usize) -> &[u8] {
+        let a_rem = self.a.remaining();
+
+        if offset < a_rem {
+            self.a.chunk_at(offset)
+        } else {
+            self.b.chunk_at(offset - a_rem)
+        }
+    }
+
+    fn chunk_count(&self) -> usize {
+        self.a.chunk_count() + self.b.chunk_count()
+    }
+
     fn advance(&mut self, mut cnt: usize) {
         let a_rem = self.a.remaining();
 
@@ -0,0 +1,179 @@
+use super::{BufMut, UninitSlice};
+
+/// Byte order used by an `Endian` writer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    /// Big-endian, as written by the plain `put_*` methods
+    Big,
+    /// Little-endian, as written by the `put_*_le` methods
+    Little,
+    /// The target's byte order, as written by the `put_*_ne` methods
+    Native,
+}
+
+/// A `BufMut` adapter whose plain `put_*` integer and float methods write in a chosen byte order
+///
+/// The explicit `_le`/`_be`/`_ne` methods keep their own byte order, everything else is forwarded
+/// to the underlying buffer unchanged
+#[derive(Debug)]
+pub struct Endian<B> {
+    inner: B,
+    endian: Endianness,
+}
+
+pub(super) fn new<B>(inner: B, endian: Endianness) -> Endian<B> {
+    Endian { inner, endian }
+}
+
+impl<B> Endian<B> {
+    /// Consumes the `Endian`, returning the underlying value
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    /// Gets a reference to the underlying `BufMut`
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying `BufMut`
+    pub fn get_mut(&mut self) -> &mut B {
+        &mut self.inner
+    }
+
+    /// Returns the byte order used by the plain `put_*` methods
+    pub fn endianness(&self) -> Endianness {
+        self.endian
+    }
+
+    /// Sets the byte order used by the plain `put_*` methods
+    pub fn set_endianness(&mut self, endian: Endianness) {
+        self.endian = endian
+    }
+}
+
+macro_rules! endian_put {
+    ($($be:ident, $le:ident, $ne:ident, $typ:ty;)*) => {
+        $(
+            #[inline]
+            fn $be(&mut self, n: $typ) {
+                match self.endian {
+                    Endianness::Big => self.inner.$be(n),
+                    Endianness::Little => self.inner.$le(n),
+                    Endianness::Native => self.inner.$ne(n),
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! endian_put_sized {
+    ($($be:ident, $le:ident, $ne:ident, $typ:ty;)*) => {
+        $(
+            #[inline]
+            fn $be(&mut self, n: $typ, nbytes: usize) {
+                match self.endian {
+                    Endianness::Big => self.inner.$be(n, nbytes),
+                    Endianness::Little => self.inner.$le(n, nbytes),
+                    Endianness::Native => self.inner.$ne(n, nbytes),
+                }
+            }
+        )*
+    };
+}
+
+unsafe impl<B> BufMut for Endian<B>
+where
+    B: BufMut,
+{
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        self.inner.remaining_mut()
+    }
+
+    #[inline]
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        self.inner.chunk_mut()
+    }
+
+    #[inline]
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        unsafe { self.inner.advance_mut(cnt) }
+    }
+
+    #[inline]
+    fn put_slice(&mut self, src: &[u8]) {
+        self.inner.put_slice(src)
+    }
+
+    endian_put! {
+        put_u16, put_u16_le, put_u16_ne, u16;
+        put_i16, put_i16_le, put_i16_ne, i16;
+        put_u32, put_u32_le, put_u32_ne, u32;
+        put_i32, put_i32_le, put_i32_ne, i32;
+        put_u64, put_u64_le, put_u64_ne, u64;
+        put_i64, put_i64_le, put_i64_ne, i64;
+        put_u128, put_u128_le, put_u128_ne, u128;
+        put_i128, put_i128_le, put_i128_ne, i128;
+        put_f32, put_f32_le, put_f32_ne, f32;
+        put_f64, put_f64_le, put_f64_ne, f64;
+    }
+
+    endian_put_sized! {
+        put_uint, put_uint_le, put_uint_ne, u64;
+        put_int, put_int_le, put_int_ne, i64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{vec, vec::Vec};
+
+    #[test]
+    fn test_little_endian_matches_le_calls() {
+        let mut expected = Vec::new();
+
+        expected.put_u8(1);
+        expected.put_u16_le(0x0203);
+        expected.put_i32_le(-4);
+        expected.put_u64_le(0x0506_0708_090a_0b0c);
+        expected.put_u128_le(13);
+        expected.put_f32_le(1.5);
+        expected.put_f64_le(-2.25);
+        expected.put_uint_le(0x0e0f10, 3);
+        expected.put_slice(b"tail");
+
+        let mut buf = Vec::new().with_endian(Endianness::Little);
+
+        buf.put_u8(1);
+        buf.put_u16(0x0203);
+        buf.put_i32(-4);
+        buf.put_u64(0x0506_0708_090a_0b0c);
+        buf.put_u128(13);
+        buf.put_f32(1.5);
+        buf.put_f64(-2.25);
+        buf.put_uint(0x0e0f10, 3);
+        buf.put_slice(b"tail");
+
+        assert_eq!(buf.into_inner(), expected);
+    }
+
+    #[test]
+    fn test_switch_endianness() {
+        let mut buf = Vec::new().with_endian(Endianness::Big);
+
+        buf.put_u16(0x0102);
+        buf.set_endianness(Endianness::Little);
+        buf.put_u16(0x0102);
+
+        // Explicit byte orders are left alone
+        buf.put_u16_ne(0x0102);
+
+        let mut expected = vec![1, 2, 2, 1];
+        expected.extend_from_slice(&0x0102u16.to_ne_bytes());
+
+        assert_eq!(buf.endianness(), Endianness::Little);
+        assert_eq!(*buf.get_ref(), expected);
+    }
+}
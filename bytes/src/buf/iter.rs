@@ -1,4 +1,5 @@
 use super::Buf;
+use crate::{Bytes, BytesMut};
 
 /// Iterator over the bytes contained by the buffer
 #[derive(Debug)]
@@ -51,3 +52,198 @@ impl<T: Buf> Iterator for IntoIter<T> {
 }
 
 impl<T: Buf> ExactSizeIterator for IntoIter<T> {}
+
+/// Iterator over the contiguous regions of a buffer, see `Buf::chunk_iter`
+#[derive(Debug)]
+pub struct ChunkIter<'a, T> {
+    inner: &'a T,
+    offset: usize,
+}
+
+impl<'a, T> ChunkIter<'a, T> {
+    /// Creates an iterator over the regions of the buffer, starting at its current position
+    pub fn new(inner: &'a T) -> ChunkIter<'a, T> {
+        ChunkIter { inner, offset: 0 }
+    }
+}
+
+impl<'a, T: Buf> Iterator for ChunkIter<'a, T> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        let chunk = self.inner.chunk_at(self.offset);
+
+        if chunk.is_empty() {
+            return None;
+        }
+
+        self.offset += chunk.len();
+
+        Some(chunk)
+    }
+}
+
+/// Iterator consuming a buffer one contiguous region at a time, see `Buf::chunks`
+#[derive(Debug)]
+pub struct Chunks<T> {
+    inner: T,
+}
+
+impl<T> Chunks<T> {
+    /// Creates an iterator over the regions of the buffer, consuming them as it goes
+    pub fn new(inner: T) -> Chunks<T> {
+        Chunks { inner }
+    }
+
+    /// Consumes the `Chunks`, returning the underlying value
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: Buf> Iterator for Chunks<T> {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        let len = self.inner.chunk().len();
+
+        if len == 0 {
+            return None;
+        }
+
+        Some(self.inner.copy_to_bytes(len))
+    }
+
+    // Buffers relying on the default `chunk_at` only report their first region, so the count
+    // is only a lower bound
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.inner.chunk_count(), None)
+    }
+}
+
+// `Buf` can only be consumed from the front, so iterating from the back is limited to the
+// buffers that can be truncated
+macro_rules! double_ended_impl {
+    ($ty:ty) => {
+        impl DoubleEndedIterator for IntoIter<$ty> {
+            fn next_back(&mut self) -> Option<u8> {
+                let len = self.inner.len();
+                let b = *self.inner.last()?;
+
+                self.inner.truncate(len - 1);
+
+                Some(b)
+            }
+        }
+    };
+}
+
+double_ended_impl!(Bytes);
+double_ended_impl!(BytesMut);
+
+// ---- Tests ----
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_iter_both_ends() {
+        let src = b"abcdef";
+        let iter = Bytes::from_static(src).into_iter();
+
+        assert_eq!(iter.len(), 6);
+        assert_eq!(iter.size_hint(), (6, Some(6)));
+        assert_eq!(iter.collect::<Vec<_>>(), src);
+
+        let rev: Vec<u8> = BytesMut::from(&src[..]).into_iter().rev().collect();
+        let mut expected = src.to_vec();
+        expected.reverse();
+
+        assert_eq!(rev, expected);
+    }
+
+    #[test]
+    fn test_iter_meet_in_middle() {
+        let mut iter = Bytes::from_static(b"abc").into_iter();
+
+        assert_eq!(iter.next_back(), Some(b'c'));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next(), Some(b'a'));
+        assert_eq!(iter.next_back(), Some(b'b'));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn test_chunk_iter_nested_chain() {
+        let mut first = Bytes::from_static(b"xxhello");
+
+        first.advance(2);
+
+        let buf = first
+            .chain(Bytes::from_static(b", "))
+            .chain(Bytes::new().chain(Bytes::from_static(b"world!!").take(5)));
+
+        let regions: Vec<&[u8]> = buf.chunk_iter().collect();
+
+        assert_eq!(regions, [&b"hello"[..], b", ", b"world"]);
+        assert_eq!(regions.concat(), b"hello, world");
+        assert_eq!(buf.remaining(), 12);
+
+        // Iterating doesn't consume anything
+        assert_eq!(buf.chunk_iter().count(), 3);
+        assert_eq!(buf.chunk(), b"hello");
+    }
+
+    #[test]
+    fn test_chunk_iter_single_region() {
+        let bytes = Bytes::from_static(b"abc");
+
+        assert_eq!(bytes.chunk_iter().collect::<Vec<_>>(), [b"abc"]);
+        assert_eq!(Bytes::new().chunk_iter().next(), None);
+    }
+
+    #[test]
+    fn test_chunk_count_and_chunks() {
+        let buf = Bytes::from_static(b"hello").chain(Bytes::from_static(b" world"));
+
+        assert_eq!(buf.chunk_count(), 2);
+        assert_eq!(Bytes::new().chunk_count(), 0);
+        assert_eq!(Bytes::from_static(b"x").chunk_count(), 1);
+
+        let mut chunks = buf.chunks();
+
+        assert_eq!(chunks.size_hint(), (2, None));
+        assert_eq!(chunks.next().unwrap(), &b"hello"[..]);
+        assert_eq!(chunks.next().unwrap(), &b" world"[..]);
+        assert_eq!(chunks.next(), None);
+        assert_eq!(chunks.into_inner().remaining(), 0);
+    }
+
+    #[test]
+    fn test_chunks_size_hint_lower_bound() {
+        // Hands out one byte at a time without overriding `chunk_at`
+        struct Bytewise(&'static [u8]);
+
+        impl Buf for Bytewise {
+            fn remaining(&self) -> usize {
+                self.0.len()
+            }
+
+            fn chunk(&self) -> &[u8] {
+                &self.0[..self.0.len().min(1)]
+            }
+
+            fn advance(&mut self, cnt: usize) {
+                self.0 = &self.0[cnt..];
+            }
+        }
+
+        let chunks = Bytewise(b"abc").chunks();
+        let (lower, upper) = chunks.size_hint();
+
+        assert_eq!((lower, upper), (1, None));
+        assert_eq!(chunks.count(), 3);
+    }
+}
@@ -1,8 +1,12 @@
 mod buf_impl;
-pub use self::buf_impl::Buf;
+pub use self::buf_impl::{Buf, TryGetError, Utf8DecodeError, VarintError};
 
 mod iter;
-pub use self::iter::IntoIter;
+pub use self::iter::{ChunkIter, Chunks, IntoIter};
+
+/// Module for BytesList
+pub mod bytes_list;
+pub use self::bytes_list::BytesList;
 
 /// Module for trait BufMut
 pub mod buf_mut;
@@ -12,6 +16,10 @@ pub use self::buf_mut::BufMut;
 pub mod chain;
 pub use self::chain::Chain;
 
+/// Module for writing in a chosen byte order
+pub mod endian;
+pub use self::endian::{Endian, Endianness};
+
 /// Module for checking the limts of bounds for the bytes
 pub mod limit;
 pub use self::limit::Limit;
@@ -21,7 +29,9 @@ pub mod uninit_slice;
 pub use self::uninit_slice::UninitSlice;
 
 /// Module for Writer
+#[cfg(feature = "std")]
 pub mod writer;
+#[cfg(feature = "std")]
 pub use self::writer::Writer;
 
 /// Module for Take
@@ -29,7 +39,9 @@ pub mod take;
 pub use self::take::Take;
 
 /// Module for Reader
+#[cfg(feature = "std")]
 pub mod reader;
+#[cfg(feature = "std")]
 pub use self::reader::Reader;
 
 /// Module for VecDeque
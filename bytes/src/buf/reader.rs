@@ -1,8 +1,8 @@
 use crate::Buf;
-#[cfg(feature = "std")]
 use std::{cmp, io};
 
-/// A `Buf` adapter which implements `io::Read` for the inner value
+/// A `Buf` adapter which implements `io::Read` and `io::BufRead` for the inner value
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 #[derive(Debug)]
 pub struct Reader<B> {
     buf: B,
@@ -58,3 +58,36 @@ where
         self.buf.advance(amt);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Bytes;
+    use std::io::{BufRead, Read};
+    use std::vec::Vec;
+
+    #[test]
+    fn test_io_copy_chained_bytes() {
+        let buf = Bytes::from_static(b"hello ").chain(Bytes::from_static(b"world"));
+        let mut reader = buf.reader();
+        let mut dst = Vec::new();
+
+        assert_eq!(io::copy(&mut reader, &mut dst).unwrap(), 11);
+        assert_eq!(dst, b"hello world");
+        // EOF
+        assert_eq!(reader.read(&mut [0; 4]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_buf_read() {
+        let mut reader = Bytes::from_static(b"one\ntwo").reader();
+        let mut line = std::string::String::new();
+
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "one\n");
+
+        assert_eq!(reader.fill_buf().unwrap(), b"two");
+        reader.consume(3);
+        assert!(reader.fill_buf().unwrap().is_empty());
+    }
+}
@@ -20,6 +20,11 @@ impl<T> Take<T> {
         self.inner
     }
 
+    /// Consumes this `Take`, returning the underlying value and the limit left unread
+    pub fn into_parts(self) -> (T, usize) {
+        (self.inner, self.limit)
+    }
+
     /// Gets a reference to the underlying `Buf`
     pub fn get_ref(&self) -> &T {
         &self.inner
@@ -55,6 +60,16 @@ where
         &bytes[..cmp::min(bytes.len(), self.limit)]
     }
 
+    fn chunk_at(&self, offset: usize) -> &[u8] {
+        if offset >= self.limit {
+            return &[];
+        }
+
+        let bytes = self.inner.chunk_at(offset);
+
+        &bytes[..cmp::min(bytes.len(), self.limit - offset)]
+    }
+
     fn advance(&mut self, cnt: usize) {
         assert!(cnt <= self.limit);
 
@@ -72,3 +87,34 @@ where
         r
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_parts_continues_past_limit() {
+        let mut take = Bytes::from_static(b"\x00\x00\x00\x2aafter").take(4);
+
+        assert_eq!(take.get_u32(), 42);
+        assert!(!take.has_remaining());
+
+        let (mut inner, limit) = take.into_parts();
+
+        assert_eq!(limit, 0);
+        assert_eq!(inner.remaining(), 5);
+        assert_eq!(inner.copy_to_bytes(5), "after");
+    }
+
+    #[test]
+    fn test_into_parts_leftover_limit() {
+        let mut take = (&b"abcdef"[..]).take(4);
+
+        take.advance(1);
+
+        let (inner, limit) = take.into_parts();
+
+        assert_eq!(limit, 3);
+        assert_eq!(inner, b"bcdef");
+    }
+}
@@ -16,7 +16,71 @@ impl Buf for VecDeque<u8> {
         }
     }
 
+    fn chunk_at(&self, offset: usize) -> &[u8] {
+        let (s1, s2) = self.as_slices();
+
+        match s1.get(offset..) {
+            Some(s1) if !s1.is_empty() => s1,
+            _ => s2.get(offset.saturating_sub(s1.len())..).unwrap_or(&[]),
+        }
+    }
+
     fn advance(&mut self, cnt: usize) {
         self.drain(..cnt);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    // Builds a deque whose contents wrap around the end of its storage
+    fn wrapped() -> VecDeque<u8> {
+        let mut deque = VecDeque::with_capacity(8);
+
+        deque.extend([0xff, 0xff, 0xff, 0xff, 0xff, 1]);
+        deque.drain(..5);
+        deque.extend([2, 3, 4, 5, 6, 7]);
+
+        let (front, back) = deque.as_slices();
+
+        assert!(!front.is_empty() && !back.is_empty());
+
+        deque
+    }
+
+    #[test]
+    fn test_get_u32_across_boundary() {
+        let mut deque = wrapped();
+        let front = deque.as_slices().0.len();
+
+        // Leave fewer than four bytes before the boundary
+        deque.advance(front - 1);
+
+        let expected = u32::from_be_bytes([
+            front as u8,
+            front as u8 + 1,
+            front as u8 + 2,
+            front as u8 + 3,
+        ]);
+
+        assert_eq!(deque.get_u32(), expected);
+        assert_eq!(deque.remaining(), 7 - front - 3);
+    }
+
+    #[test]
+    fn test_chunks_cover_both_slices() {
+        let deque = wrapped();
+        let regions: Vec<&[u8]> = deque.chunk_iter().collect();
+
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions.concat(), [1, 2, 3, 4, 5, 6, 7]);
+
+        let mut dst = [0; 7];
+
+        wrapped().copy_to_slice(&mut dst);
+
+        assert_eq!(dst, [1, 2, 3, 4, 5, 6, 7]);
+    }
+}
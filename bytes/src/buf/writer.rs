@@ -1,8 +1,8 @@
 use crate::BufMut;
-#[cfg(feature = "std")]
 use std::{cmp, io};
 
-/// A `ButMut` adapter which implements `io::Write` for the inner value
+/// A `BufMut` adapter which implements `io::Write` for the inner value
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 #[derive(Debug)]
 pub struct Writer<B> {
     buf: B,
@@ -37,6 +37,8 @@ impl<B> io::Write for Writer<B>
 where
     B: BufMut + Sized,
 {
+    /// Writes as much of `buf` as fits in `remaining_mut()` and returns the number of bytes
+    /// written. A full buffer results in a short write, which `write_all` reports as `WriteZero`
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         let n = cmp::min(self.buf.remaining_mut(), buf.len());
 
@@ -49,3 +51,35 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BytesMut;
+    use std::io::Write;
+
+    #[test]
+    fn test_write_all_bytes_mut() {
+        let mut writer = BytesMut::new().writer();
+
+        writer.write_all(b"hello ").unwrap();
+        write!(writer, "world {}", 42).unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(writer.into_inner(), &b"hello world 42"[..]);
+    }
+
+    #[test]
+    fn test_write_full_buffer() {
+        let mut dst = [0u8; 4];
+        let mut writer = (&mut dst[..]).writer();
+
+        assert_eq!(writer.write(b"abcdef").unwrap(), 4);
+        assert_eq!(writer.write(b"g").unwrap(), 0);
+
+        let err = writer.write_all(b"h").unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+        assert_eq!(&dst, b"abcd");
+    }
+}
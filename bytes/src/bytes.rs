@@ -1,8 +1,8 @@
 extern crate alloc;
 
 use alloc::{
-    alloc::{dealloc, Layout},
-    borrow::Borrow,
+    alloc::{alloc, dealloc, handle_alloc_error, Layout},
+    borrow::{Borrow, Cow},
     boxed::Box,
     string::String,
     vec::Vec,
@@ -14,6 +14,7 @@ use core::{
     ops::{Deref, RangeBounds},
     ptr::{self, NonNull},
     slice,
+    str::{self, Utf8Error},
 };
 
 use super::quick::sync::atomic::AtomicMut;
@@ -60,6 +61,15 @@ impl Bytes {
         }
     }
 
+    /// Creates a new `Bytes` from a `Cow`, borrowing a static slice without copying and taking
+    /// ownership of an owned one
+    pub fn from_cow(cow: Cow<'static, [u8]>) -> Self {
+        match cow {
+            Cow::Borrowed(slice) => Bytes::from_static(slice),
+            Cow::Owned(vec) => Bytes::from(vec),
+        }
+    }
+
     /// Creates a new `Bytes` with length zero and the given pointer as the address
     fn new_empty_with_ptr(ptr: *const u8) -> Self {
         debug_assert!(!ptr.is_null());
@@ -78,6 +88,31 @@ impl Bytes {
     pub fn from_owner<T>(owner: T) -> Self
     where
         T: AsRef<[u8]> + Send + 'static,
+    {
+        // SAFETY: the owner is `Send`, so the `Bytes` may be moved and dropped on any thread
+        unsafe { Bytes::from_owner_unchecked(owner) }
+    }
+
+    /// Create a thread-local [`LocalBytes`](crate::LocalBytes) with a buffer whose lifetime is
+    /// controlled via an explicit owner that doesn't need to be `Send`, such as an `Rc<[u8]>`
+    #[cfg(feature = "local")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "local")))]
+    pub fn from_owner_local<T>(owner: T) -> crate::LocalBytes
+    where
+        T: AsRef<[u8]> + 'static,
+    {
+        // SAFETY: `LocalBytes` is neither `Send` nor `Sync` and never hands out the inner `Bytes`,
+        // so the owner is only ever touched and dropped on this thread
+        crate::LocalBytes::new(unsafe { Bytes::from_owner_unchecked(owner) })
+    }
+
+    /// # Safety
+    ///
+    /// Unless `T` is `Send`, the returned `Bytes` and all its clones must stay on the current
+    /// thread
+    unsafe fn from_owner_unchecked<T>(owner: T) -> Self
+    where
+        T: AsRef<[u8]> + 'static,
     {
         let owned = Box::into_raw(Box::new(Owned {
             lifetime: OwnedLifetime {
@@ -256,6 +291,48 @@ impl Bytes {
         ret
     }
 
+    /// Rejoins `other` onto the end of `self` if it directly follows it in the same shared
+    /// allocation, as the halves produced by `split_off` or `split_to` do
+    ///
+    /// Otherwise `other` is handed back unchanged
+    pub fn try_unsplit(&mut self, other: Bytes) -> Result<(), Bytes> {
+        if other.is_empty() {
+            return Ok(());
+        }
+
+        let data = self.data.load(Ordering::Acquire);
+
+        // The data points at the shared allocation, so equal data means the same buffer even when
+        // a promoted original and its clones ended up with different vtables. Static buffers have
+        // no data telling whether they come from the same slice
+        if !data.is_null()
+            && data == other.data.load(Ordering::Acquire)
+            && self.ptr.wrapping_add(self.len) == other.ptr
+        {
+            self.len += other.len;
+
+            // Dropping `other` releases its reference to the shared buffer
+            drop(other);
+
+            Ok(())
+        } else {
+            Err(other)
+        }
+    }
+
+    /// Returns two views of the data split at `mid` without modifying `self`, both sharing the
+    /// same underlying allocation
+    pub fn split_at(&self, mid: usize) -> (Bytes, Bytes) {
+        assert!(
+            mid <= self.len(),
+            "split_at out of bounds: {:?} <= {:?}",
+            mid,
+            self.len()
+        );
+
+        (self.slice(..mid), self.slice(mid..))
+    }
+
     /// Shortens the buffer, keeping the first `len` bytes and dropping the remaining
     #[inline]
     pub fn truncate(&mut self, len: usize) {
@@ -276,6 +353,27 @@ impl Bytes {
         self.truncate(0);
     }
 
+    /// Removes the first `n` bytes, advancing the start of the buffer without touching the
+    /// underlying allocation. This is the inherent counterpart of `Buf::advance`
+    pub fn skip_front(&mut self, n: usize) {
+        assert!(
+            n <= self.len(),
+            "skip_front out of bounds: {:?} <= {:?}",
+            n,
+            self.len()
+        );
+
+        unsafe { self.inc_start(n) };
+    }
+
+    /// Copies the data into a new `BytesMut` with exactly the needed capacity
+    ///
+    /// Unlike `clone` followed by `try_into_mut`, this always makes a single allocation and never
+    /// takes over the original buffer
+    pub fn to_bytes_mut(&self) -> BytesMut {
+        BytesMut::from(self.as_slice())
+    }
+
     /// Try to convert self ([`Bytes`]) into `ByesMut`
     pub fn try_into_mut(self) -> Result<BytesMut, Bytes> {
         if self.is_unique() {
@@ -306,6 +404,85 @@ impl Bytes {
         unsafe { slice::from_raw_parts(self.ptr, self.len) }
     }
 
+    /// Returns the data as a `&str` if it is valid UTF-8
+    #[inline]
+    pub fn as_str(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(self.as_slice())
+    }
+
+    /// Splits the data into its longest valid UTF-8 prefix and the bytes after it
+    ///
+    /// When a chunk of a stream ends in the middle of a character, the remainder holds the
+    /// incomplete character to prepend to the next chunk. If the data contains an invalid
+    /// sequence instead, the remainder starts with it
+    pub fn valid_utf8_prefix(&self) -> (&str, &[u8]) {
+        match str::from_utf8(self.as_slice()) {
+            Ok(s) => (s, &[]),
+            Err(err) => {
+                let (valid, rest) = self.as_slice().split_at(err.valid_up_to());
+
+                // SAFETY: `from_utf8` validated everything up to this point
+                (unsafe { str::from_utf8_unchecked(valid) }, rest)
+            }
+        }
+    }
+
+    /// Converts the data into a `String` if it is valid UTF-8, handing `self` back with the error
+    /// otherwise
+    pub fn into_string(self) -> Result<String, (Utf8Error, Bytes)> {
+        if let Err(err) = self.as_str() {
+            return Err((err, self));
+        }
+
+        // SAFETY: the data was validated above
+        Ok(unsafe { String::from_utf8_unchecked(Vec::from(self)) })
+    }
+
+    /// Returns a mutable slice of the data, copying it into a new allocation first unless this is
+    /// the only reference to the underlying buffer
+    ///
+    /// Static and owner-backed buffers are never unique, so they are always copied
+    pub fn make_mut(&mut self) -> &mut [u8] {
+        if !self.is_unique() {
+            *self = Bytes::copy_from_slice(self.as_slice());
+        }
+
+        // SAFETY: the buffer is either uniquely owned by `self` or empty
+        unsafe { slice::from_raw_parts_mut(self.ptr as *mut u8, self.len) }
+    }
+
+    /// Returns true if the start of the data is aligned to `align` bytes
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two
+    #[inline]
+    pub fn is_aligned(&self, align: usize) -> bool {
+        assert!(align.is_power_of_two(), "align must be a power of two");
+
+        self.ptr as usize & (align - 1) == 0
+    }
+
+    /// Returns a `Bytes` with the same data whose start is aligned to `align` bytes
+    ///
+    /// If `self` is already aligned this is a cheap clone, otherwise the data is copied into a
+    /// new allocation with the requested alignment
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two
+    pub fn ensure_aligned(&self, align: usize) -> Bytes {
+        if self.is_aligned(align) {
+            return self.clone();
+        }
+
+        if self.is_empty() {
+            return Bytes::new_empty_with_ptr(align as *const u8);
+        }
+
+        Bytes::from_owner(AlignedBuf::copy_from_slice(self.as_slice(), align))
+    }
+
     #[inline]
     unsafe fn inc_start(&mut self, by: usize) {
         unsafe {
@@ -425,6 +602,11 @@ impl Eq for Bytes {}
 
 impl PartialEq for Bytes {
     fn eq(&self, other: &Self) -> bool {
+        // Only an optimization: views of the same memory are equal without comparing the bytes
+        if self.as_ptr() == other.as_ptr() && self.len() == other.len() {
+            return true;
+        }
+
         self.as_slice() == other.as_slice()
     }
 }
@@ -561,6 +743,24 @@ impl PartialOrd<Bytes> for &str {
     }
 }
 
+impl<const N: usize> PartialEq<[u8; N]> for Bytes {
+    fn eq(&self, other: &[u8; N]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<const N: usize> PartialEq<Bytes> for [u8; N] {
+    fn eq(&self, other: &Bytes) -> bool {
+        *other == *self
+    }
+}
+
+impl<const N: usize> PartialEq<Bytes> for &[u8; N] {
+    fn eq(&self, other: &Bytes) -> bool {
+        *other == **self
+    }
+}
+
 impl<'a, T: ?Sized> PartialEq<&'a T> for Bytes
 where
     Bytes: PartialEq<T>,
@@ -599,6 +799,31 @@ impl From<&'static str> for Bytes {
     }
 }
 
+impl From<Cow<'static, [u8]>> for Bytes {
+    fn from(cow: Cow<'static, [u8]>) -> Bytes {
+        Bytes::from_cow(cow)
+    }
+}
+
+impl From<Cow<'static, str>> for Bytes {
+    fn from(cow: Cow<'static, str>) -> Bytes {
+        match cow {
+            Cow::Borrowed(s) => Bytes::from_static(s.as_bytes()),
+            Cow::Owned(s) => Bytes::from(s),
+        }
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Bytes {
+    fn from(array: [u8; N]) -> Bytes {
+        if N == 0 {
+            return Bytes::new();
+        }
+
+        Bytes::from(Vec::from(array))
+    }
+}
+
 impl From<Vec<u8>> for Bytes {
     fn from(vec: Vec<u8>) -> Bytes {
         let mut vec = ManuallyDrop::new(vec);
@@ -734,6 +959,46 @@ unsafe fn static_is_unique(_: &AtomicPtr<()>) -> bool {
 
 unsafe fn static_drop(_: &mut AtomicPtr<()>, _: *const u8, _: usize) {}
 
+// ---- AlignedBuf ----
+/// An over-aligned heap buffer, used as the owner of the data returned by `Bytes::ensure_aligned`
+struct AlignedBuf {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+unsafe impl Send for AlignedBuf {}
+
+impl AlignedBuf {
+    fn copy_from_slice(data: &[u8], align: usize) -> AlignedBuf {
+        debug_assert!(!data.is_empty());
+
+        let layout = Layout::from_size_align(data.len(), align).expect("invalid alignment");
+
+        unsafe {
+            let ptr = match NonNull::new(alloc(layout)) {
+                Some(ptr) => ptr,
+                None => handle_alloc_error(layout),
+            };
+
+            ptr::copy_nonoverlapping(data.as_ptr(), ptr.as_ptr(), data.len());
+
+            AlignedBuf { ptr, layout }
+        }
+    }
+}
+
+impl AsRef<[u8]> for AlignedBuf {
+    fn as_ref(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.layout.size()) }
+    }
+}
+
+impl Drop for AlignedBuf {
+    fn drop(&mut self) {
+        unsafe { dealloc(self.ptr.as_ptr(), self.layout) }
+    }
+}
+
 // ---- impl OwnedVtable ----
 #[repr(C)]
 struct OwnedLifetime {
@@ -869,7 +1134,7 @@ unsafe fn promotable_to_vec(
             shared_to_vec_impl(shared.cast(), ptr, len)
         } else {
             // If Bytes hold a Vec, then offset must be 0
-            debug_assert_eq!(kind, KIND_ARC);
+            debug_assert_eq!(kind, KIND_VEC);
 
             let buf = f(shared);
             let cap = offset_from(ptr, buf) + len;
@@ -890,7 +1155,7 @@ unsafe fn promotable_to_mut(
 ) -> BytesMut {
     unsafe {
         let shared = data.load(Ordering::Acquire);
-        let kind = shared as usize * KIND_MASK;
+        let kind = shared as usize & KIND_MASK;
 
         if kind == KIND_ARC {
             shared_to_mut_impl(shared.cast(), ptr, len)
@@ -1146,7 +1411,7 @@ unsafe fn shared_drop(data: &mut AtomicPtr<()>, _ptr: *const u8, _len: usize) {
 
 unsafe fn shallow_clone_arc(shared: *mut Shared, ptr: *const u8, len: usize) -> Bytes {
     unsafe {
-        let old_size = (*shared).ref_cnt.load(Ordering::Relaxed);
+        let old_size = (*shared).ref_cnt.fetch_add(1, Ordering::Relaxed);
 
         if old_size > usize::MAX >> 1 {
             super::abort();
@@ -1234,3 +1499,425 @@ where
 fn without_provenance(ptr: usize) -> *const u8 {
     core::ptr::null::<u8>().wrapping_add(ptr)
 }
+
+// ---- Tests ----
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[repr(align(8))]
+    struct Aligned([u8; 16]);
+
+    static DATA: Aligned = Aligned([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+    #[test]
+    fn test_make_mut_unique_in_place() {
+        let mut vec = Vec::with_capacity(8);
+        vec.extend_from_slice(b"abc");
+
+        let mut bytes = Bytes::from(vec);
+        let ptr = bytes.as_ptr();
+
+        bytes.make_mut()[0] = b'x';
+
+        assert_eq!(bytes.as_ptr(), ptr);
+        assert_eq!(bytes, &b"xbc"[..]);
+    }
+
+    #[test]
+    fn test_make_mut_shared_copies() {
+        let mut vec = Vec::with_capacity(8);
+        vec.extend_from_slice(b"abc");
+
+        let mut bytes = Bytes::from(vec);
+        let other = bytes.clone();
+
+        bytes.make_mut()[0] = b'x';
+
+        assert_ne!(bytes.as_ptr(), other.as_ptr());
+        assert_eq!(bytes, &b"xbc"[..]);
+        assert_eq!(other, &b"abc"[..]);
+
+        // Now the only reference, mutates in place
+        let mut other = other;
+        let ptr = other.as_ptr();
+
+        other.make_mut()[2] = b'z';
+
+        assert_eq!(other.as_ptr(), ptr);
+        assert_eq!(other, &b"abz"[..]);
+
+        let mut stat = Bytes::from_static(b"static");
+
+        stat.make_mut()[0] = b'S';
+
+        assert_eq!(stat, &b"Static"[..]);
+    }
+
+    #[test]
+    fn test_split_at_shares_allocation() {
+        let mut vec = Vec::with_capacity(8);
+        vec.extend_from_slice(b"abcdef");
+
+        let bytes = Bytes::from(vec);
+        let ref_cnt = || unsafe {
+            (*bytes.data.load(Ordering::Relaxed).cast::<Shared>())
+                .ref_cnt
+                .load(Ordering::Relaxed)
+        };
+
+        assert!(bytes.is_unique());
+
+        let (head, tail) = bytes.split_at(2);
+
+        assert_eq!(ref_cnt(), 3);
+        assert!(!bytes.is_unique());
+        assert_eq!(head, &b"ab"[..]);
+        assert_eq!(tail, &b"cdef"[..]);
+        assert_eq!(tail.as_ptr(), unsafe { bytes.as_ptr().add(2) });
+
+        drop(head);
+        drop(tail);
+
+        assert_eq!(ref_cnt(), 1);
+        assert!(bytes.is_unique());
+    }
+
+    #[test]
+    fn test_split_at_releases_owner() {
+        use alloc::sync::Arc;
+        use core::sync::atomic::{AtomicBool, Ordering};
+
+        struct Owner(Vec<u8>, Arc<AtomicBool>);
+
+        impl AsRef<[u8]> for Owner {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl Drop for Owner {
+            fn drop(&mut self) {
+                self.1.store(true, Ordering::Relaxed);
+            }
+        }
+
+        let dropped = Arc::new(AtomicBool::new(false));
+        let bytes = Bytes::from_owner(Owner(b"abcdef".to_vec(), dropped.clone()));
+        let (head, tail) = bytes.split_at(6);
+
+        assert!(tail.is_empty());
+
+        drop(bytes);
+        assert!(!dropped.load(Ordering::Relaxed));
+
+        drop(tail);
+        drop(head);
+        assert!(dropped.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_at_out_of_bounds() {
+        let _ = Bytes::from_static(b"abc").split_at(4);
+    }
+
+    #[test]
+    fn test_from_array() {
+        let bytes = Bytes::from([1u8, 2, 3]);
+
+        assert_eq!(bytes, [1, 2, 3]);
+        assert_eq!(bytes, &[1, 2, 3]);
+        assert_eq!([1, 2, 3], bytes);
+        assert_eq!(&[1, 2, 3], bytes);
+        assert_eq!(bytes, &[1, 2, 3][..]);
+        assert_eq!(bytes, vec![1, 2, 3]);
+        assert_ne!(bytes, [1, 2]);
+
+        let empty = Bytes::from([0u8; 0]);
+
+        assert!(empty.is_empty());
+        assert!(empty.data.load(Ordering::Relaxed).is_null());
+    }
+
+    #[test]
+    fn test_ensure_aligned_copies_misaligned() {
+        let bytes = Bytes::from_static(&DATA.0).slice(1..);
+
+        assert!(!bytes.is_aligned(8));
+
+        let aligned = bytes.ensure_aligned(8);
+
+        assert!(aligned.is_aligned(8));
+        assert_ne!(aligned.as_ptr(), bytes.as_ptr());
+        assert_eq!(aligned, bytes);
+    }
+
+    #[test]
+    fn test_ensure_aligned_keeps_aligned() {
+        let bytes = Bytes::from_static(&DATA.0).slice(8..);
+        let aligned = bytes.ensure_aligned(8);
+
+        assert_eq!(aligned.as_ptr(), bytes.as_ptr());
+
+        // Over-aligned copies are shared as well
+        let over = bytes.ensure_aligned(64);
+        let again = over.ensure_aligned(64);
+
+        assert!(over.is_aligned(64));
+        assert_eq!(again.as_ptr(), over.as_ptr());
+        assert_eq!(again, bytes);
+        assert!(Bytes::new().ensure_aligned(16).is_aligned(16));
+    }
+
+    #[test]
+    fn test_skip_front() {
+        let mut vec = Vec::with_capacity(8);
+        vec.extend_from_slice(b"abcdef");
+
+        let mut bytes = Bytes::from(vec);
+        let ptr = bytes.as_ptr();
+        let data = bytes.data.load(Ordering::Relaxed);
+
+        bytes.skip_front(2);
+
+        assert_eq!(bytes, b"cdef");
+        assert_eq!(bytes.as_ptr(), unsafe { ptr.add(2) });
+        assert_eq!(bytes.data.load(Ordering::Relaxed), data);
+        assert!(bytes.is_unique());
+
+        bytes.skip_front(4);
+
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_skip_front_out_of_bounds() {
+        Bytes::from_static(b"abc").skip_front(4);
+    }
+
+    #[test]
+    fn test_from_cow() {
+        static LITERAL: &[u8] = b"literal";
+
+        // Borrowed static data is referenced in place
+        let bytes = Bytes::from_cow(Cow::Borrowed(LITERAL));
+
+        assert_eq!(bytes, LITERAL);
+        assert_eq!(bytes.as_ptr(), LITERAL.as_ptr());
+        assert!(bytes.data.load(Ordering::Relaxed).is_null());
+
+        // Owned data is moved in without copying, but does need an allocation
+        let vec = b"computed".to_vec();
+        let ptr = vec.as_ptr();
+        let bytes = Bytes::from_cow(Cow::Owned(vec));
+
+        assert_eq!(bytes, b"computed");
+        assert_eq!(bytes.as_ptr(), ptr);
+        assert!(!bytes.data.load(Ordering::Relaxed).is_null());
+    }
+
+    #[test]
+    fn test_from_cow_str() {
+        let literal = "literal";
+        let bytes = Bytes::from(Cow::Borrowed(literal));
+
+        assert_eq!(bytes, "literal");
+        assert_eq!(bytes.as_ptr(), literal.as_ptr());
+        assert!(bytes.data.load(Ordering::Relaxed).is_null());
+
+        let bytes = Bytes::from(Cow::<str>::Owned(String::from("computed")));
+
+        assert_eq!(bytes, "computed");
+        assert!(!bytes.data.load(Ordering::Relaxed).is_null());
+    }
+
+    #[test]
+    fn test_as_str_into_string() {
+        let bytes = Bytes::from_static(b"ascii");
+
+        assert_eq!(bytes.as_str(), Ok("ascii"));
+        assert_eq!(bytes.into_string().unwrap(), "ascii");
+
+        let bytes = Bytes::from("grüße, 世界");
+
+        assert_eq!(bytes.as_str(), Ok("grüße, 世界"));
+        assert_eq!(bytes.into_string().unwrap(), "grüße, 世界");
+
+        // Cut in the middle of the three byte '世'
+        let bytes = Bytes::from("世界").slice(..4);
+        let err = bytes.as_str().unwrap_err();
+
+        assert_eq!(err.valid_up_to(), 3);
+        assert_eq!(err.error_len(), None);
+
+        let (err, bytes) = bytes.into_string().unwrap_err();
+
+        assert_eq!(err.valid_up_to(), 3);
+        assert_eq!(bytes, &"世界".as_bytes()[..4]);
+    }
+
+    #[test]
+    fn test_eq_same_view_and_copy() {
+        let bytes = Bytes::from(vec![7u8; 1024]);
+        let clone = bytes.clone();
+
+        // A clone points at the same memory, so it takes the pointer path
+        assert_eq!(clone.as_ptr(), bytes.as_ptr());
+        assert_eq!(clone, bytes);
+
+        // Equal contents in a different allocation still compare equal
+        let copy = Bytes::copy_from_slice(&bytes);
+
+        assert_ne!(copy.as_ptr(), bytes.as_ptr());
+        assert_eq!(copy, bytes);
+
+        // Same start but a different length isn't equal
+        assert_ne!(bytes.slice(..512), bytes);
+    }
+
+    #[test]
+    fn test_promotable_into_mut() {
+        let bytes = Bytes::from(vec![1u8, 2, 3, 4].into_boxed_slice());
+
+        // Still unpromoted, the buffer is taken back as a vec
+        let mut buf = BytesMut::from(bytes.slice(1..));
+
+        buf[0] = 9;
+        buf.extend_from_slice(b"\x05");
+
+        assert_eq!(buf, [9, 3, 4, 5]);
+
+        // Cloning promotes it to a shared buffer which must not be taken over
+        let bytes = Bytes::from(vec![1u8, 2, 3, 4].into_boxed_slice());
+        let clone = bytes.clone();
+        let mut buf = BytesMut::from(bytes);
+
+        buf[0] = 9;
+
+        assert_eq!(buf, [9, 2, 3, 4]);
+        assert_eq!(clone, [1, 2, 3, 4]);
+
+        drop(clone);
+
+        buf[1] = 8;
+
+        assert_eq!(buf, [9, 8, 3, 4]);
+    }
+
+    #[test]
+    fn test_promotable_into_vec() {
+        let mut bytes = Bytes::from(vec![1u8, 2, 3, 4, 5].into_boxed_slice());
+
+        bytes.advance(2);
+
+        // The data is moved back to the start of the original allocation
+        let vec = Vec::from(bytes);
+
+        assert_eq!(vec, [3, 4, 5]);
+        assert_eq!(vec.len(), 3);
+        assert_eq!(vec.capacity(), 5);
+
+        // A promoted buffer still shared with a clone is copied out instead
+        let bytes = Bytes::from(vec![1u8, 2, 3].into_boxed_slice());
+        let clone = bytes.clone();
+        let vec = Vec::from(bytes);
+
+        assert_eq!(vec, [1, 2, 3]);
+        assert_eq!(clone, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_to_bytes_mut() {
+        let bytes = Bytes::from(vec![1u8, 2, 3, 4, 5, 6]).slice(1..5);
+        let clone = bytes.clone();
+        let mut buf = bytes.to_bytes_mut();
+
+        assert_eq!(buf, [2, 3, 4, 5]);
+        assert_eq!(buf.capacity(), 4);
+        assert_ne!(buf.as_ptr(), bytes.as_ptr());
+
+        buf[0] = 0;
+
+        assert_eq!(bytes, [2, 3, 4, 5]);
+        assert_eq!(clone, [2, 3, 4, 5]);
+
+        // A unique buffer is copied as well
+        let unique = Bytes::from(vec![7u8; 3]);
+        let buf = unique.to_bytes_mut();
+
+        assert_ne!(buf.as_ptr(), unique.as_ptr());
+        assert_eq!(buf, unique.as_slice());
+    }
+
+    #[test]
+    fn test_try_unsplit() {
+        let mut bytes = Bytes::from(vec![1u8, 2, 3, 4, 5, 6]);
+        let mut tail = bytes.split_off(2);
+        let end = tail.split_off(2);
+
+        let shared = bytes.data.load(Ordering::Relaxed).cast::<Shared>();
+
+        assert_eq!(unsafe { (*shared).ref_cnt.load(Ordering::Relaxed) }, 3);
+
+        // Not adjacent
+        let end = bytes.try_unsplit(end).unwrap_err();
+
+        bytes.try_unsplit(tail).unwrap();
+        bytes.try_unsplit(end).unwrap();
+
+        assert_eq!(bytes, [1, 2, 3, 4, 5, 6]);
+        assert_eq!(unsafe { (*shared).ref_cnt.load(Ordering::Relaxed) }, 1);
+
+        // Rejoining an empty half is a no-op
+        bytes.try_unsplit(Bytes::new()).unwrap();
+
+        assert_eq!(bytes.len(), 6);
+    }
+
+    #[test]
+    fn test_try_unsplit_unrelated() {
+        let mut bytes = Bytes::from(vec![1u8, 2, 3, 4]);
+        let other = Bytes::from(vec![5u8, 6]);
+        let other = bytes.try_unsplit(other).unwrap_err();
+
+        assert_eq!(bytes, [1, 2, 3, 4]);
+        assert_eq!(other, [5, 6]);
+
+        // Adjacent static slices are never joined
+        static DATA: &[u8] = b"abcd";
+
+        let mut first = Bytes::from_static(DATA);
+        let second = first.split_off(2);
+
+        assert!(first.try_unsplit(second).is_err());
+    }
+
+    #[test]
+    fn test_valid_utf8_prefix() {
+        let bytes = Bytes::from("añ€");
+
+        assert_eq!(bytes.valid_utf8_prefix(), ("añ€", &[][..]));
+
+        // Ends after the first two of the three bytes of '€'
+        let chunk = bytes.slice(..5);
+        let (valid, rest) = chunk.valid_utf8_prefix();
+
+        assert_eq!(valid, "añ");
+        assert_eq!(rest, &"€".as_bytes()[..2]);
+
+        // The remainder completes with the start of the next chunk
+        let mut joined = rest.to_vec();
+
+        joined.extend_from_slice(&bytes[5..]);
+
+        assert_eq!(Bytes::from(joined).valid_utf8_prefix(), ("€", &[][..]));
+
+        // An invalid byte stops the prefix as well
+        let bytes = Bytes::from_static(b"ok\xffok");
+
+        assert_eq!(bytes.valid_utf8_prefix(), ("ok", &b"\xffok"[..]));
+    }
+}
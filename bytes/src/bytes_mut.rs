@@ -19,9 +19,11 @@ use core::{
     cmp, fmt, hash, isize,
     iter::FromIterator,
     mem::{self, ManuallyDrop, MaybeUninit},
-    ops::{Deref, DerefMut},
+    ops::{Deref, DerefMut, RangeBounds},
     ptr::{self, NonNull},
-    slice, usize,
+    slice,
+    str::{self, Utf8Error},
+    usize,
 };
 
 /// A unique reference to a contiguous slice of memory
@@ -177,6 +179,15 @@ impl BytesMut {
         }
     }
 
+    /// Returns a read-only `Bytes` over the current contents, keeping the spare capacity in
+    /// `self`
+    ///
+    /// Afterwards `self` is empty and starts right after the shared bytes, so appends either go
+    /// into the remaining capacity or reallocate, and the shared region is never mutated again
+    pub fn share(&mut self) -> Bytes {
+        self.split().freeze()
+    }
+
     /// Shortens the buffer, keeping the first `len` bytes and dropping the rest
     pub fn truncate(&mut self, len: usize) {
         if len <= self.len() {
@@ -191,6 +202,20 @@ impl BytesMut {
         unsafe { self.set_len(0) };
     }
 
+    /// Removes the first `n` bytes, advancing the start of the buffer without touching the
+    /// underlying allocation. This is the inherent counterpart of `Buf::advance`
+    pub fn skip_front(&mut self, n: usize) {
+        assert!(
+            n <= self.len(),
+            "skip_front out of bounds: {:?} <= {:?}",
+            n,
+            self.len()
+        );
+
+        // SAFETY: `n` <= `self.len` <= `self.cap`
+        unsafe { self.advance_unchecked(n) };
+    }
+
     /// Resizes the buffer so that `len` is equal to `new_len`
     pub fn resize(&mut self, new_len: usize, value: u8) {
         let additional = if let Some(additional) = new_len.checked_sub(self.len()) {
@@ -242,10 +267,31 @@ impl BytesMut {
         let _ = self.reserve_inner(additional, true);
     }
 
+    /// Reserves capacity for exactly `additional` more bytes, without the extra room `reserve`
+    /// leaves for future growth
+    ///
+    /// Like `Vec::reserve_exact`, the allocator may still hand out a little more
+    pub fn reserve_exact(&mut self, additional: usize) {
+        let len = self.len();
+        let rem = self.capacity() - len;
+
+        if additional <= rem {
+            return;
+        }
+
+        // Infallible
+        let _ = self.reserve_impl(additional, true, true);
+    }
+
     /// In separate function to allow the short-circuits in `reserve` and `try_reclaim` to be
     /// inline-able. Significantly helps performance. Returns false if the operation fails
     #[inline]
     pub fn reserve_inner(&mut self, additional: usize, allocate: bool) -> bool {
+        self.reserve_impl(additional, allocate, false)
+    }
+
+    #[inline]
+    fn reserve_impl(&mut self, additional: usize, allocate: bool, exact: bool) -> bool {
         let len = self.len();
         let kind = self.kind();
 
@@ -279,7 +325,11 @@ impl BytesMut {
                         offset,
                     ));
 
-                    v.reserve(additional);
+                    if exact {
+                        v.reserve_exact(additional);
+                    } else {
+                        v.reserve(additional);
+                    }
 
                     self.ptr = vptr(v.as_mut_ptr().add(offset));
                     self.cap = v.capacity() - offset;
@@ -324,14 +374,16 @@ impl BytesMut {
 
                     new_cap = new_cap.checked_add(offset).expect("Overflow");
 
-                    let double = v.capacity().checked_shl(1).unwrap_or(new_cap);
+                    if !exact {
+                        let double = v.capacity().checked_shl(1).unwrap_or(new_cap);
 
-                    new_cap = cmp::max(double, new_cap);
+                        new_cap = cmp::max(double, new_cap);
+                    }
 
                     debug_assert!(offset + len <= v.capacity());
 
                     v.set_len(offset + len);
-                    v.reserve(new_cap - v.len());
+                    v.reserve_exact(new_cap - v.len());
 
                     self.ptr = vptr(v.as_mut_ptr().add(offset));
                     self.cap = v.capacity() - offset;
@@ -348,7 +400,9 @@ impl BytesMut {
         let original_capacity_repr = unsafe { (*shared).original_capacity_repr };
         let original_capacity = original_capacity_from_repr(original_capacity_repr);
 
-        new_cap = cmp::max(new_cap, original_capacity);
+        if !exact {
+            new_cap = cmp::max(new_cap, original_capacity);
+        }
 
         let mut v = ManuallyDrop::new(Vec::with_capacity(new_cap));
 
@@ -400,6 +454,54 @@ impl BytesMut {
         unsafe { self.advance_mut(cnt) };
     }
 
+    /// Appends a copy of the given range of `self` to the end of the buffer
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds
+    pub fn extend_from_within(&mut self, range: impl RangeBounds<usize>) {
+        use core::ops::Bound;
+
+        let len = self.len();
+        let begin = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.checked_add(1).expect("Bound out of range"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n.checked_add(1).expect("Bound out of range"),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => len,
+        };
+
+        assert!(
+            begin <= end,
+            "range start must not be greater than end: {:?} <= {:?}",
+            begin,
+            end
+        );
+        assert!(
+            end <= len,
+            "range end out of bounds: {:?} <= {:?}",
+            end,
+            len
+        );
+
+        let cnt = end - begin;
+
+        self.reserve(cnt);
+
+        // `reserve` may have moved the buffer, so the source is resolved afterwards
+        unsafe {
+            let src = self.ptr.as_ptr().add(begin);
+            let dst = self.ptr.as_ptr().add(len);
+
+            ptr::copy(src, dst, cnt);
+
+            self.advance_mut(cnt);
+        }
+    }
+
     /// Absorbs a `BytesMut` that was previously split off
     pub fn unsplit(&mut self, other: BytesMut) {
         if self.is_empty() {
@@ -437,6 +539,23 @@ impl BytesMut {
         unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
     }
 
+    /// Returns the data as a `&str` if it is valid UTF-8
+    #[inline]
+    pub fn as_str(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(self.as_slice())
+    }
+
+    /// Converts the data into a `String` if it is valid UTF-8, handing `self` back with the error
+    /// otherwise
+    pub fn into_string(self) -> Result<String, (Utf8Error, BytesMut)> {
+        if let Err(err) = self.as_str() {
+            return Err((err, self));
+        }
+
+        // SAFETY: the data was validated above
+        Ok(unsafe { String::from_utf8_unchecked(Vec::from(self)) })
+    }
+
     #[inline]
     fn as_slice_mut(&mut self) -> &mut [u8] {
         unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
@@ -561,6 +680,21 @@ impl BytesMut {
             slice::from_raw_parts_mut(ptr.cast(), len)
         }
     }
+
+    /// Returns the initialized part of the buffer together with its spare capacity
+    ///
+    /// The two regions are disjoint, so the initialized bytes can be inspected while the
+    /// spare capacity is being filled. Use `advance_mut` afterwards to commit the written bytes.
+    #[inline]
+    pub fn split_spare_mut(&mut self) -> (&mut [u8], &mut UninitSlice) {
+        unsafe {
+            let ptr = self.ptr.as_ptr();
+            let init = slice::from_raw_parts_mut(ptr, self.len);
+            let spare = UninitSlice::from_raw_parts_mut(ptr.add(self.len), self.cap - self.len);
+
+            (init, spare)
+        }
+    }
 }
 
 impl Drop for BytesMut {
@@ -661,6 +795,14 @@ unsafe impl BufMut for BytesMut {
         self.extend_from_slice(src);
     }
 
+    fn put_slices(&mut self, slices: &[&[u8]]) {
+        self.reserve(slices.iter().map(|src| src.len()).sum());
+
+        for src in slices {
+            self.extend_from_slice(src);
+        }
+    }
+
     fn put_bytes(&mut self, val: u8, cnt: usize) {
         self.reserve(cnt);
 
@@ -719,6 +861,12 @@ impl<'a> From<&'a str> for BytesMut {
     }
 }
 
+impl<const N: usize> From<[u8; N]> for BytesMut {
+    fn from(value: [u8; N]) -> Self {
+        BytesMut::from_vec(Vec::from(value))
+    }
+}
+
 impl From<BytesMut> for Bytes {
     fn from(value: BytesMut) -> Self {
         value.freeze()
@@ -729,6 +877,11 @@ impl Eq for BytesMut {}
 
 impl PartialEq for BytesMut {
     fn eq(&self, other: &Self) -> bool {
+        // Only an optimization: views of the same memory are equal without comparing the bytes
+        if self.as_ptr() == other.as_ptr() && self.len() == other.len() {
+            return true;
+        }
+
         self.as_slice() == other.as_slice()
     }
 }
@@ -1024,6 +1177,24 @@ impl PartialOrd<BytesMut> for String {
     }
 }
 
+impl<const N: usize> PartialEq<[u8; N]> for BytesMut {
+    fn eq(&self, other: &[u8; N]) -> bool {
+        &**self == other
+    }
+}
+
+impl<const N: usize> PartialEq<BytesMut> for [u8; N] {
+    fn eq(&self, other: &BytesMut) -> bool {
+        *other == *self
+    }
+}
+
+impl<const N: usize> PartialEq<BytesMut> for &[u8; N] {
+    fn eq(&self, other: &BytesMut) -> bool {
+        *other == **self
+    }
+}
+
 impl<'a, T: ?Sized> PartialEq<&'a T> for BytesMut
 where
     BytesMut: PartialEq<T>,
@@ -1298,4 +1469,235 @@ mod tests {
         assert_eq!(min_cap * 32, original_capacity_from_repr(6));
         assert_eq!(min_cap * 64, original_capacity_from_repr(7));
     }
+
+    #[test]
+    fn test_split_spare_mut() {
+        let mut buf = BytesMut::with_capacity(16);
+        buf.extend_from_slice(b"head");
+
+        let cap = buf.capacity();
+        let (init, spare) = buf.split_spare_mut();
+
+        assert_eq!(init, b"head");
+        assert_eq!(spare.len(), cap - 4);
+
+        spare[..4].copy_from_slice(b"tail");
+
+        unsafe { buf.advance_mut(4) };
+
+        assert_eq!(&buf[..], b"headtail");
+    }
+
+    #[test]
+    fn test_split_spare_mut_full() {
+        let mut buf = BytesMut::zeroed(8);
+        let cap = buf.capacity();
+
+        let (init, spare) = buf.split_spare_mut();
+        init[0] = 1;
+
+        assert_eq!(init.len(), 8);
+        assert_eq!(spare.len(), cap - 8);
+        assert_eq!(buf[0], 1);
+    }
+
+    #[test]
+    fn test_from_array() {
+        let buf = BytesMut::from([1u8, 2, 3]);
+
+        assert_eq!(buf, [1, 2, 3]);
+        assert_eq!(buf, &[1, 2, 3]);
+        assert_eq!([1, 2, 3], buf);
+        assert_eq!(&[1, 2, 3], buf);
+        assert_eq!(buf, &[1, 2, 3][..]);
+        assert_eq!(buf, Vec::from([1, 2, 3]));
+        assert_ne!(buf, [1, 2, 3, 4]);
+
+        let empty = BytesMut::from([0u8; 0]);
+
+        assert!(empty.is_empty());
+        assert_eq!(empty.capacity(), 0);
+    }
+
+    #[test]
+    fn test_skip_front() {
+        let mut buf = BytesMut::with_capacity(16);
+        buf.extend_from_slice(b"abcdef");
+
+        let ptr = buf.as_ptr();
+        let cap = buf.capacity();
+
+        buf.skip_front(2);
+
+        assert_eq!(buf, b"cdef");
+        assert_eq!(buf.as_ptr(), unsafe { ptr.add(2) });
+        assert_eq!(buf.capacity(), cap - 2);
+        assert_eq!(buf.kind(), KIND_VEC);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_skip_front_out_of_bounds() {
+        BytesMut::from(&b"abc"[..]).skip_front(4);
+    }
+
+    #[test]
+    fn test_share_then_append() {
+        let mut buf = BytesMut::with_capacity(64);
+        buf.extend_from_slice(b"hello");
+
+        let shared = buf.share();
+
+        assert!(buf.is_empty());
+        assert_eq!(buf.kind(), KIND_ARC);
+
+        // Appends within capacity continue right after the shared bytes
+        buf.extend_from_slice(b" world");
+
+        assert_eq!(shared, b"hello");
+        assert_eq!(buf, b" world");
+        assert_eq!(buf.as_ptr(), unsafe { shared.as_ptr().add(5) });
+
+        // Outgrowing the capacity can't reclaim the shared region
+        buf.reserve(128);
+        buf.extend_from_slice(&[b'!'; 128]);
+
+        assert_eq!(shared, b"hello");
+        assert_eq!(&buf[..6], b" world");
+    }
+
+    #[test]
+    fn test_extend_from_within() {
+        let mut buf = BytesMut::with_capacity(64);
+
+        buf.extend_from_slice(b"hello ");
+        buf.extend_from_within(..5);
+
+        assert_eq!(buf, b"hello hello");
+
+        buf.extend_from_within(6..=6);
+
+        assert_eq!(buf, b"hello helloh");
+    }
+
+    #[test]
+    fn test_extend_from_within_overlapping() {
+        let mut buf = BytesMut::with_capacity(64);
+
+        buf.extend_from_slice(b"abcd");
+
+        // The source runs right up to the destination
+        buf.extend_from_within(1..);
+        buf.extend_from_within(..);
+
+        assert_eq!(buf, b"abcdbcdabcdbcd");
+    }
+
+    #[test]
+    fn test_extend_from_within_reallocates() {
+        let mut buf = BytesMut::from(&b"0123456789"[..]);
+
+        assert_eq!(buf.capacity(), buf.len());
+
+        buf.extend_from_within(2..);
+
+        assert_eq!(buf, b"012345678923456789");
+
+        // Reallocating out of a shared buffer leaves the other half untouched
+        let mut buf = BytesMut::from(&b"0123456789"[..]);
+        let other = buf.split_off(5);
+
+        buf.extend_from_within(..);
+
+        assert_eq!(buf, b"0123401234");
+        assert_eq!(other, b"56789");
+    }
+
+    #[test]
+    #[should_panic(expected = "range end out of bounds")]
+    fn test_extend_from_within_out_of_bounds() {
+        let mut buf = BytesMut::from(&b"abc"[..]);
+
+        buf.extend_from_within(1..4);
+    }
+
+    #[test]
+    fn test_as_str_into_string() {
+        let buf = BytesMut::from(&b"ascii"[..]);
+
+        assert_eq!(buf.as_str(), Ok("ascii"));
+        assert_eq!(buf.into_string().unwrap(), "ascii");
+
+        let buf = BytesMut::from("grüße, 世界");
+
+        assert_eq!(buf.as_str(), Ok("grüße, 世界"));
+        assert_eq!(buf.into_string().unwrap(), "grüße, 世界");
+
+        // Cut in the middle of the three byte '世'
+        let buf = BytesMut::from(&"世界".as_bytes()[..4]);
+        let err = buf.as_str().unwrap_err();
+
+        assert_eq!(err.valid_up_to(), 3);
+        assert_eq!(err.error_len(), None);
+
+        let (err, buf) = buf.into_string().unwrap_err();
+
+        assert_eq!(err.valid_up_to(), 3);
+        assert_eq!(buf, &"世界".as_bytes()[..4]);
+    }
+
+    #[test]
+    fn test_eq_same_view_and_copy() {
+        let buf = BytesMut::from(&[7u8; 64][..]);
+
+        #[allow(clippy::eq_op)]
+        {
+            assert!(buf == buf);
+        }
+
+        let mut other = buf.clone();
+
+        assert_ne!(other.as_ptr(), buf.as_ptr());
+        assert_eq!(other, buf);
+
+        other[63] = 0;
+
+        assert_ne!(other, buf);
+    }
+
+    #[test]
+    fn test_reserve_exact() {
+        let mut buf = BytesMut::new();
+
+        buf.reserve_exact(100);
+
+        assert_eq!(buf.capacity(), 100);
+
+        buf.extend_from_slice(&[1; 100]);
+        buf.reserve_exact(28);
+
+        assert_eq!(buf.capacity(), 128);
+
+        // A shared buffer is copied out into an exactly sized one
+        let mut buf = BytesMut::with_capacity(4096);
+
+        buf.extend_from_slice(b"abc");
+
+        let other = buf.split_off(3);
+
+        buf.reserve_exact(10);
+
+        assert_eq!(buf.capacity(), 13);
+        assert_eq!(buf, b"abc");
+
+        drop(other);
+
+        // Unique again, grows in place without doubling
+        let mut buf = BytesMut::from(&b"xyz"[..]);
+        let _ = buf.split_off(3);
+
+        buf.reserve_exact(5);
+
+        assert_eq!(buf.capacity(), 8);
+    }
 }
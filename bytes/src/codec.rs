@@ -0,0 +1,207 @@
+use crate::{Buf, BufMut, Bytes, BytesMut};
+use core::fmt;
+
+/// Turns a stream of bytes into frames and back
+///
+/// `decode` is called every time more bytes are appended to `src`, and should consume nothing
+/// until a whole frame is available
+pub trait BytesCodec {
+    /// The frame type produced by `decode` and consumed by `encode`
+    type Item;
+    /// The error returned when the stream or an item is malformed
+    type Error;
+
+    /// Attempts to split one frame off the front of `src`, returning `Ok(None)` if more bytes
+    /// are needed
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error>;
+
+    /// Appends the encoded form of `item` to `dst`
+    fn encode(&mut self, item: Self::Item, dst: &mut BytesMut) -> Result<(), Self::Error>;
+}
+
+/// Size of the length prefix written by `LengthDelimitedCodec`
+const HEADER_LEN: usize = 4;
+
+/// Frames prefixed by their length as a big-endian `u32`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthDelimitedCodec {
+    max_frame_len: usize,
+}
+
+impl LengthDelimitedCodec {
+    /// Default limit on the length of a frame, 8 MiB
+    pub const DEFAULT_MAX_FRAME_LEN: usize = 8 * 1024 * 1024;
+
+    /// Creates a codec accepting frames up to `DEFAULT_MAX_FRAME_LEN` bytes
+    pub fn new() -> LengthDelimitedCodec {
+        LengthDelimitedCodec::with_max_frame_len(Self::DEFAULT_MAX_FRAME_LEN)
+    }
+
+    /// Creates a codec accepting frames up to `max_frame_len` bytes
+    pub fn with_max_frame_len(max_frame_len: usize) -> LengthDelimitedCodec {
+        LengthDelimitedCodec {
+            max_frame_len: usize::min(max_frame_len, u32::MAX as usize),
+        }
+    }
+
+    /// Returns the largest frame this codec accepts
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+
+    fn check_len(&self, len: usize) -> Result<(), FrameTooLarge> {
+        if len > self.max_frame_len {
+            return Err(FrameTooLarge {
+                len,
+                max: self.max_frame_len,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for LengthDelimitedCodec {
+    fn default() -> LengthDelimitedCodec {
+        LengthDelimitedCodec::new()
+    }
+}
+
+impl BytesCodec for LengthDelimitedCodec {
+    type Item = Bytes;
+    type Error = FrameTooLarge;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Bytes>, FrameTooLarge> {
+        let Some(header) = src.first_chunk::<HEADER_LEN>() else {
+            return Ok(None);
+        };
+        let len = u32::from_be_bytes(*header) as usize;
+
+        self.check_len(len)?;
+
+        if src.len() < HEADER_LEN + len {
+            // Make room for the rest of the frame so it arrives without reallocating
+            src.reserve(HEADER_LEN + len - src.len());
+
+            return Ok(None);
+        }
+
+        src.advance(HEADER_LEN);
+
+        Ok(Some(src.split_to(len).freeze()))
+    }
+
+    fn encode(&mut self, item: Bytes, dst: &mut BytesMut) -> Result<(), FrameTooLarge> {
+        self.check_len(item.len())?;
+
+        dst.reserve(HEADER_LEN + item.len());
+        dst.put_u32(item.len() as u32);
+        dst.extend_from_slice(&item[..]);
+
+        Ok(())
+    }
+}
+
+/// Error returned by `LengthDelimitedCodec` for a frame longer than its limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameTooLarge {
+    /// Length of the offending frame
+    pub len: usize,
+    /// Largest length the codec accepts
+    pub max: usize,
+}
+
+impl fmt::Display for FrameTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "frame of {} bytes exceeds the limit of {} bytes",
+            self.len, self.max
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrameTooLarge {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn frames() -> Vec<Bytes> {
+        [&b"hello"[..], b"", b", ", &[7; 300], b"world"]
+            .iter()
+            .map(|frame| Bytes::copy_from_slice(frame))
+            .collect()
+    }
+
+    #[test]
+    fn test_length_delimited_round_trip_in_splits() {
+        let mut codec = LengthDelimitedCodec::new();
+        let mut stream = BytesMut::new();
+
+        for frame in frames() {
+            codec.encode(frame, &mut stream).unwrap();
+        }
+
+        for split in [1, 2, 3, 5, 64, stream.len()] {
+            let mut src = BytesMut::new();
+            let mut decoded = Vec::new();
+
+            for piece in stream[..].chunks(split) {
+                src.extend_from_slice(piece);
+
+                while let Some(frame) = codec.decode(&mut src).unwrap() {
+                    decoded.push(frame);
+                }
+            }
+
+            assert!(src.is_empty());
+            assert_eq!(decoded, frames());
+
+            let mut encoded = BytesMut::new();
+
+            for frame in decoded {
+                codec.encode(frame, &mut encoded).unwrap();
+            }
+
+            assert_eq!(encoded, stream);
+        }
+    }
+
+    #[test]
+    fn test_length_delimited_partial_header() {
+        let mut codec = LengthDelimitedCodec::new();
+        let mut src = BytesMut::from(&[0, 0, 0][..]);
+
+        assert_eq!(codec.decode(&mut src), Ok(None));
+        assert_eq!(src.len(), 3);
+
+        src.extend_from_slice(&[2, b'h']);
+
+        assert_eq!(codec.decode(&mut src), Ok(None));
+        assert!(src.capacity() >= 6);
+
+        src.extend_from_slice(b"i");
+
+        assert_eq!(codec.decode(&mut src).unwrap().unwrap(), &b"hi"[..]);
+    }
+
+    #[test]
+    fn test_length_delimited_too_large() {
+        let mut codec = LengthDelimitedCodec::with_max_frame_len(4);
+        let mut dst = BytesMut::new();
+        let err = FrameTooLarge { len: 5, max: 4 };
+
+        assert_eq!(
+            codec.encode(Bytes::from_static(b"hello"), &mut dst),
+            Err(err)
+        );
+        assert!(dst.is_empty());
+
+        let mut src = BytesMut::from(&[0, 0, 0, 5, 1][..]);
+
+        assert_eq!(codec.decode(&mut src), Err(err));
+    }
+}
@@ -1,6 +1,6 @@
 use super::BytesRef;
-use crate::{Bytes, BytesMut};
-use core::fmt::{write, Formatter, LowerHex, Result, UpperHex};
+use crate::{BufMut, Bytes, BytesMut};
+use core::fmt::{self, write, Formatter, LowerHex, Result, UpperHex};
 
 impl LowerHex for BytesRef<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
@@ -26,3 +26,126 @@ fmt_impl!(LowerHex, Bytes);
 fmt_impl!(LowerHex, BytesMut);
 fmt_impl!(UpperHex, Bytes);
 fmt_impl!(UpperHex, BytesMut);
+
+/// Error returned when decoding a hex string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexError {
+    /// The number of hex digits is odd
+    OddLength,
+    /// The character at the given byte index is not a hex digit
+    InvalidDigit(usize),
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            HexError::OddLength => f.write_str("odd number of hex digits"),
+            HexError::InvalidDigit(index) => write!(f, "invalid hex digit at index {}", index),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HexError {}
+
+#[inline]
+fn hex_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+impl Bytes {
+    /// Decodes a string of hex digits, with an optional `0x` prefix, into a new `Bytes`
+    pub fn from_hex(s: &str) -> core::result::Result<Bytes, HexError> {
+        let mut buf = BytesMut::new();
+
+        buf.decode_hex_into(s)?;
+
+        Ok(buf.freeze())
+    }
+}
+
+impl BytesMut {
+    /// Decodes a string of hex digits, with an optional `0x` prefix, and appends the bytes to
+    /// `self`. On error `self` is left unchanged
+    pub fn decode_hex_into(&mut self, s: &str) -> core::result::Result<(), HexError> {
+        let (offset, digits) = match s.strip_prefix("0x") {
+            Some(digits) => (2, digits.as_bytes()),
+            None => (0, s.as_bytes()),
+        };
+
+        if digits.len() % 2 != 0 {
+            return Err(HexError::OddLength);
+        }
+
+        let len = self.len();
+
+        self.reserve(digits.len() / 2);
+
+        for (i, pair) in digits.chunks_exact(2).enumerate() {
+            let index = offset + i * 2;
+
+            let (hi, lo) = match (hex_value(pair[0]), hex_value(pair[1])) {
+                (Some(hi), Some(lo)) => (hi, lo),
+                (None, _) => {
+                    self.truncate(len);
+
+                    return Err(HexError::InvalidDigit(index));
+                }
+                (_, None) => {
+                    self.truncate(len);
+
+                    return Err(HexError::InvalidDigit(index + 1));
+                }
+            };
+
+            self.put_u8((hi << 4) | lo);
+        }
+
+        Ok(())
+    }
+}
+
+// ---- Tests ----
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    #[test]
+    fn test_from_hex() {
+        assert_eq!(Bytes::from_hex("00ff7F").unwrap(), [0x00, 0xff, 0x7f]);
+        assert_eq!(
+            Bytes::from_hex("0xDeadBEEF").unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+        assert!(Bytes::from_hex("").unwrap().is_empty());
+
+        let bytes = Bytes::from_static(b"\x01\xab");
+
+        assert_eq!(Bytes::from_hex(&format!("{:x}", bytes)).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_from_hex_malformed() {
+        assert_eq!(Bytes::from_hex("abc"), Err(HexError::OddLength));
+        assert_eq!(Bytes::from_hex("0x0g"), Err(HexError::InvalidDigit(3)));
+        assert_eq!(Bytes::from_hex("zz"), Err(HexError::InvalidDigit(0)));
+        assert_eq!(Bytes::from_hex("0X00"), Err(HexError::InvalidDigit(1)));
+    }
+
+    #[test]
+    fn test_decode_hex_into_appends() {
+        let mut buf = BytesMut::from(&b"ab"[..]);
+
+        buf.decode_hex_into("6364").unwrap();
+        assert_eq!(buf, b"abcd");
+
+        assert!(buf.decode_hex_into("65xx").is_err());
+        assert_eq!(buf, b"abcd");
+    }
+}
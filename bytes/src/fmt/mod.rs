@@ -11,5 +11,7 @@ macro_rules! fmt_impl {
 mod debug;
 mod hex;
 
+pub use self::hex::HexError;
+
 /// `BytesRef` is not a part of the public API
 struct BytesRef<'a>(&'a [u8]);
@@ -24,12 +24,21 @@ pub use buf::{Buf, BufMut};
 /// Importing and using the `fmt` module and it's adapters
 pub mod fmt;
 
+/// Framing of byte streams on top of `BytesMut`
+pub mod codec;
+
 mod bytes;
 mod bytes_mut;
+#[cfg(feature = "local")]
+mod local;
 mod quick;
+#[cfg(feature = "serde")]
+mod serde;
 
 pub use bytes::Bytes;
 pub use bytes_mut::BytesMut;
+#[cfg(feature = "local")]
+pub use local::LocalBytes;
 
 /// Panic with an understandable message
 #[cold]
@@ -0,0 +1,163 @@
+use crate::Bytes;
+use core::{borrow::Borrow, fmt, hash, marker::PhantomData, ops::Deref, ops::RangeBounds};
+
+/// A cheaply cloneable and sliceable chunk of memory which stays on the thread that created it
+///
+/// Created by [`Bytes::from_owner_local`] over owners that aren't `Send`. The inner `Bytes` is
+/// never handed out, which is what keeps the owner from being reached from another thread
+pub struct LocalBytes {
+    inner: Bytes,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl LocalBytes {
+    /// The caller guarantees that `inner` and its clones are confined to the current thread
+    pub(crate) fn new(inner: Bytes) -> LocalBytes {
+        LocalBytes {
+            inner,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the number of bytes contained in this `LocalBytes`
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the `LocalBytes` has a length of 0
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns a slice of self for the provided range, sharing the same owner
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> LocalBytes {
+        LocalBytes::new(self.inner.slice(range))
+    }
+
+    /// Copies the data into a `Bytes` which can be sent to other threads
+    pub fn to_bytes(&self) -> Bytes {
+        Bytes::copy_from_slice(&self.inner)
+    }
+}
+
+impl Clone for LocalBytes {
+    fn clone(&self) -> LocalBytes {
+        LocalBytes::new(self.inner.clone())
+    }
+}
+
+impl Deref for LocalBytes {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+impl AsRef<[u8]> for LocalBytes {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+impl Borrow<[u8]> for LocalBytes {
+    fn borrow(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+impl hash::Hash for LocalBytes {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.inner.hash(state)
+    }
+}
+
+impl PartialEq for LocalBytes {
+    fn eq(&self, other: &LocalBytes) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl Eq for LocalBytes {}
+
+impl PartialEq<[u8]> for LocalBytes {
+    fn eq(&self, other: &[u8]) -> bool {
+        **self == *other
+    }
+}
+
+impl fmt::Debug for LocalBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use core::cell::Cell;
+
+    struct Counted {
+        data: [u8; 4],
+        drops: Rc<Cell<usize>>,
+    }
+
+    impl AsRef<[u8]> for Counted {
+        fn as_ref(&self) -> &[u8] {
+            &self.data
+        }
+    }
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    #[test]
+    fn test_from_owner_local_rc() {
+        let rc: Rc<[u8]> = Rc::from(&b"shared"[..]);
+        let bytes = Bytes::from_owner_local(rc.clone());
+
+        assert_eq!(Rc::strong_count(&rc), 2);
+        assert_eq!(bytes, b"shared"[..]);
+        assert_eq!(bytes.as_ptr(), rc.as_ptr());
+
+        let tail = bytes.slice(3..);
+
+        drop(bytes);
+
+        assert_eq!(tail, b"red"[..]);
+        assert_eq!(Rc::strong_count(&rc), 2);
+
+        drop(tail);
+
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn test_owner_dropped_once() {
+        let drops = Rc::new(Cell::new(0));
+        let bytes = Bytes::from_owner_local(Counted {
+            data: *b"abcd",
+            drops: drops.clone(),
+        });
+        let clones = [bytes.clone(), bytes.slice(1..), bytes.clone()];
+
+        drop(bytes);
+
+        assert_eq!(drops.get(), 0);
+
+        let copy = clones[1].to_bytes();
+
+        drop(clones);
+
+        assert_eq!(drops.get(), 1);
+        assert_eq!(copy, b"bcd"[..]);
+    }
+}
@@ -0,0 +1,147 @@
+use super::{BufMut, Bytes, BytesMut};
+use alloc::{string::String, vec::Vec};
+use core::{cmp, fmt};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+// ---- Serialize ----
+impl Serialize for Bytes {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.as_slice())
+    }
+}
+
+impl Serialize for BytesMut {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.as_slice())
+    }
+}
+
+// ---- Deserialize ----
+/// Accepts byte buffers from binary formats and sequences of `u8` from human-readable ones
+struct BytesMutVisitor;
+
+impl<'de> de::Visitor<'de> for BytesMutVisitor {
+    type Value = BytesMut;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("byte array")
+    }
+
+    #[inline]
+    fn visit_seq<V>(self, mut seq: V) -> Result<BytesMut, V::Error>
+    where
+        V: de::SeqAccess<'de>,
+    {
+        // Don't trust the size hint of untrusted input for the initial allocation
+        let len = cmp::min(seq.size_hint().unwrap_or(0), 4096);
+        let mut buf = BytesMut::with_capacity(len);
+
+        while let Some(byte) = seq.next_element()? {
+            buf.put_u8(byte);
+        }
+
+        Ok(buf)
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<BytesMut, E>
+    where
+        E: de::Error,
+    {
+        Ok(BytesMut::from(v))
+    }
+
+    #[inline]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<BytesMut, E>
+    where
+        E: de::Error,
+    {
+        Ok(BytesMut::from_vec(v))
+    }
+
+    #[inline]
+    fn visit_str<E>(self, v: &str) -> Result<BytesMut, E>
+    where
+        E: de::Error,
+    {
+        Ok(BytesMut::from(v))
+    }
+
+    #[inline]
+    fn visit_string<E>(self, v: String) -> Result<BytesMut, E>
+    where
+        E: de::Error,
+    {
+        Ok(BytesMut::from_vec(v.into_bytes()))
+    }
+}
+
+impl<'de> Deserialize<'de> for BytesMut {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<BytesMut, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_byte_buf(BytesMutVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Bytes, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        BytesMut::deserialize(deserializer).map(BytesMut::freeze)
+    }
+}
+
+// ---- Tests ----
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_test::{assert_de_tokens, assert_tokens, Configure, Token};
+
+    #[test]
+    fn test_json_round_trip() {
+        let bytes = Bytes::from_static(b"\x00\x01\xff");
+        let json = serde_json::to_string(&bytes).unwrap();
+
+        assert_eq!(json, "[0,1,255]");
+        assert_eq!(serde_json::from_str::<Bytes>(&json).unwrap(), bytes);
+
+        let buf = BytesMut::from(&b"abc"[..]);
+        let json = serde_json::to_vec(&buf).unwrap();
+
+        assert_eq!(serde_json::from_slice::<BytesMut>(&json).unwrap(), buf);
+        assert_eq!(serde_json::from_str::<Bytes>("\"abc\"").unwrap(), b"abc");
+    }
+
+    #[test]
+    fn test_compact_round_trip() {
+        let bytes = Bytes::from_static(b"abc");
+        let buf = BytesMut::from(&b"abc"[..]);
+
+        assert_tokens(&bytes.clone().compact(), &[Token::Bytes(b"abc")]);
+        assert_tokens(&buf.clone().compact(), &[Token::Bytes(b"abc")]);
+        assert_de_tokens(&bytes.clone().compact(), &[Token::ByteBuf(b"abc")]);
+        assert_de_tokens(
+            &buf.compact(),
+            &[
+                Token::Seq { len: Some(3) },
+                Token::U8(b'a'),
+                Token::U8(b'b'),
+                Token::U8(b'c'),
+                Token::SeqEnd,
+            ],
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Blocking
+//! Offload blocking calls to a dedicated pool of OS threads so they don't stall the workers
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, LazyLock, Mutex, mpsc},
+    thread,
+};
+
+use crate::{
+    Coroutine, CoroutineId,
+    config::config,
+    join::Join,
+    join_handle::{JoinHandle, make_join_handle},
+    sync::AtomicOption,
+};
+
+type Task = Box<dyn FnOnce() + Send + 'static>;
+
+static POOL: LazyLock<BlockingPool> =
+    LazyLock::new(|| BlockingPool::new(config().get_blocking_workers()));
+
+/// A fixed size pool of OS threads pulling tasks from a shared queue
+struct BlockingPool {
+    sender: Mutex<mpsc::Sender<Task>>,
+}
+
+impl BlockingPool {
+    fn new(workers: usize) -> BlockingPool {
+        let (sender, receiver) = mpsc::channel::<Task>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for i in 0..workers {
+            let receiver = receiver.clone();
+
+            thread::Builder::new()
+                .name(format!("blocking-{i}"))
+                .spawn(move || {
+                    loop {
+                        // Release the lock before running the task
+                        let task = receiver.lock().unwrap().recv();
+
+                        match task {
+                            Ok(task) => task(),
+                            Err(_) => break,
+                        }
+                    }
+                })
+                .expect("Failed to spawn blocking worker");
+        }
+
+        BlockingPool {
+            sender: Mutex::new(sender),
+        }
+    }
+
+    fn execute(&self, task: Task) {
+        self.sender
+            .lock()
+            .unwrap()
+            .send(task)
+            .expect("Blocking pool is gone");
+    }
+}
+
+/// Runs `f` on the blocking thread pool
+/// Joining the returned handle from a coroutine parks only that coroutine, the worker thread
+/// keeps running other coroutines until the result is ready
+pub fn spawn_blocking<F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let panic = Arc::new(AtomicOption::none());
+    let join = Arc::new(Join::new(panic.clone()));
+    let packet = Arc::new(AtomicOption::none());
+    let their_panic = panic.clone();
+    let their_join = join.clone();
+    let their_packet = packet.clone();
+
+    POOL.execute(Box::new(move || {
+        match panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(ret) => their_packet.store(ret),
+            Err(err) => their_panic.store(err),
+        }
+
+        their_join.trigger();
+    }));
+
+    make_join_handle(
+        Coroutine::new(CoroutineId::next(), "blocking", 0, 0),
+        join,
+        packet,
+        panic,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::{spawn::spawn, yield_now::yield_now};
+
+    #[test]
+    fn test_spawn_blocking_does_not_stall_worker() {
+        let sleeping = Arc::new(AtomicBool::new(true));
+        let their_sleeping = sleeping.clone();
+
+        let blocked = spawn(move || {
+            spawn_blocking(|| thread::sleep(Duration::from_millis(200)))
+                .join()
+                .unwrap();
+
+            their_sleeping.store(false, Ordering::Release);
+        });
+
+        // This coroutine must be able to finish while the other one waits on the sleep
+        let ticker = spawn(|| {
+            for _ in 0..10 {
+                yield_now();
+            }
+        });
+
+        ticker.join().unwrap();
+
+        assert!(sleeping.load(Ordering::Acquire));
+
+        blocked.join().unwrap();
+
+        assert!(!sleeping.load(Ordering::Acquire));
+    }
+}
@@ -0,0 +1,95 @@
+//! Cooperative preemption points, so a busy coroutine can't starve its peers
+
+use crate::{
+    coroutine_local::get_coroutine_local_data, runtime::ContextStack, yield_now::yield_now,
+};
+
+/// Number of `consume_budget` calls a coroutine makes before it yields, unless set by the builder
+pub(crate) const DEFAULT_BUDGET: usize = 128;
+
+/// Accounts for a unit of work done by the current coroutine, yielding to its peers once the
+/// budget set by `CoroutineBuilder::budget` is used up
+///
+/// Does nothing outside of a coroutine, or if its budget is 0
+pub fn consume_budget() {
+    let Some(local) = get_coroutine_local_data() else {
+        return;
+    };
+    let limit = unsafe { local.as_ref() }.get_coroutine().budget();
+
+    if limit == 0 {
+        return;
+    }
+
+    let Some(ctx) = ContextStack::current().coroutine_ctx() else {
+        return;
+    };
+
+    if ctx.budget == 0 {
+        ctx.budget = limit;
+    }
+
+    ctx.budget -= 1;
+
+    if ctx.budget == 0 {
+        yield_now();
+
+        // Resumed, start over with a full budget
+        ctx.budget = limit;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::*;
+    use crate::builder::CoroutineBuilder;
+
+    #[test]
+    fn test_busy_coroutines_interleave() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let started = Arc::new(AtomicUsize::new(0));
+
+        let handles = (0..2)
+            .map(|id| {
+                let log = log.clone();
+                let started = started.clone();
+
+                CoroutineBuilder::new()
+                    .budget(4)
+                    .spawn(move || {
+                        // Both must be ready before the budget is spent, or the first one may
+                        // be done before the second is even spawned
+                        started.fetch_add(1, Ordering::AcqRel);
+
+                        while started.load(Ordering::Acquire) < 2 {
+                            yield_now();
+                        }
+
+                        for _ in 0..100 {
+                            log.lock().unwrap().push(id);
+
+                            consume_budget();
+                        }
+                    })
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let log = log.lock().unwrap();
+        let switches = log.windows(2).filter(|pair| pair[0] != pair[1]).count();
+
+        assert_eq!(log.len(), 200);
+
+        // Running one to completion first would switch only once
+        assert!(switches > 1, "coroutines did not interleave: {log:?}");
+    }
+}
@@ -1,10 +1,17 @@
-use std::{borrow::Cow, io, sync::Arc, thread::JoinHandle};
+use std::{borrow::Cow, io, mem, sync::Arc};
 
 use crate::{
-    Coroutine,
+    Coroutine, CoroutineId, CoroutineImpl,
+    budget::DEFAULT_BUDGET,
+    config::config,
+    coroutine_local::{CoroutineLocal, get_coroutine_local_data},
     done::Done,
     event::{EventSource, EventSubscriber},
+    gen_impl::GeneratorImpl,
     join::Join,
+    join_handle::{JoinHandle, make_join_handle},
+    metrics::METRICS,
+    scheduler::get_scheduler,
     sync::AtomicOption,
 };
 
@@ -16,6 +23,18 @@ pub struct CoroutineBuilder {
     stack_size: Option<usize>,
     /// Identifier for the coroutine
     id: Option<usize>,
+    /// Whether to prefix the name with the name of the spawning coroutine
+    name_from_parent: bool,
+    /// Number of `consume_budget` calls between forced yields
+    budget: Option<usize>,
+    /// Whether to measure the stack used, see `JoinHandle::peak_stack_usage`
+    track_stack_usage: bool,
+}
+
+impl Default for CoroutineBuilder {
+    fn default() -> Self {
+        CoroutineBuilder::new()
+    }
 }
 
 impl CoroutineBuilder {
@@ -25,6 +44,9 @@ impl CoroutineBuilder {
             name: None,
             stack_size: None,
             id: None,
+            name_from_parent: false,
+            budget: None,
+            track_stack_usage: false,
         }
     }
 
@@ -49,42 +71,118 @@ impl CoroutineBuilder {
         self
     }
 
+    /// Prefix the name with the name of the spawning coroutine, e.g. `parent/child`
+    ///
+    /// Has no effect when spawned outside of a named coroutine
+    pub fn name_from_parent(mut self) -> Self {
+        self.name_from_parent = true;
+
+        self
+    }
+
+    /// Set how many times the coroutine may call `consume_budget` before it yields to its peers
+    ///
+    /// 0 disables the budget, the default is `DEFAULT_BUDGET`
+    pub fn budget(mut self, budget: usize) -> Self {
+        self.budget = Some(budget);
+
+        self
+    }
+
+    /// Measure how much of its stack the coroutine uses, reported by
+    /// `JoinHandle::peak_stack_usage` once it is done
+    ///
+    /// The whole stack is filled with a footprint pattern up front, so spawning is slower and
+    /// the stack is never taken from the pool.
+    pub fn track_stack_usage(mut self, track: bool) -> Self {
+        self.track_stack_usage = track;
+
+        self
+    }
+
+    /// Resolves the id, allocating a fresh one when none was set
+    fn resolve_id(&self) -> CoroutineId {
+        self.id.map_or_else(CoroutineId::next, CoroutineId::from)
+    }
+
+    /// Resolves the final name, falling back to `coroutine-{id}` when no name was set
+    fn resolve_name(&self, id: CoroutineId, parent: Option<&str>) -> Cow<'static, str> {
+        let name = match &self.name {
+            Some(name) => name.clone(),
+            None => Cow::Owned(format!("coroutine-{id}")),
+        };
+
+        match parent {
+            Some(parent) if self.name_from_parent => Cow::Owned(format!("{parent}/{name}")),
+            _ => name,
+        }
+    }
+
     /// Spawns a new coroutine by taking ownership of the `CoroutineBuilder`, and returns an
     /// `io::Result` to it's `JoinHandle`
     /// Spawned coroutine may outlive the caller. The join handle method can be used to block on
     /// termination of the child thread, including recovering it's panics.
-    pub unsafe fn spawn<F, T>(self, f: F) -> io::Result<JoinHandle<T>>
+    ///
+    /// The coroutine runs on one of the worker threads and may move between them whenever it is
+    /// suspended, so it doesn't see the thread locals of the spawner. Thread blocking calls stall
+    /// its worker, see `spawn_blocking` for those.
+    pub fn spawn<F, T>(self, f: F) -> io::Result<JoinHandle<T>>
     where
         T: Send + 'static,
         F: FnOnce() -> T + Send + 'static,
     {
-        unsafe {
-            let name = self.name;
-            let id = self.id;
-            let (coroutine, handle) = self.spawn_impl(f)?;
-            let scheduler = get_scheduler();
-
-            match id {
-                None => scheduler.schedule_global(coroutine),
-                Some(id) => scheduler.schedule_global_with_id(coroutine, id),
-            }
-
-            Ok(handle)
+        // SAFETY: `f` and its result are `'static`, nothing they hold can go away while the
+        // coroutine runs
+        unsafe { self.spawn_unchecked(f) }
+    }
+
+    /// Same as `spawn`, except that `f` and its result only need to outlive `'a`
+    ///
+    /// # Safety
+    ///
+    /// The caller must make sure the coroutine is done before `'a` ends, e.g. by joining it.
+    /// Leaking the `JoinHandle` is not enough, `f` could still use what it borrows afterwards
+    pub unsafe fn spawn_unchecked<'a, F, T>(self, f: F) -> io::Result<JoinHandle<T>>
+    where
+        T: Send + 'a,
+        F: FnOnce() -> T + Send + 'a,
+    {
+        let id = self.id;
+        let (coroutine, handle) = unsafe { self.spawn_impl(f)? };
+        let scheduler = get_scheduler();
+
+        match id {
+            None => scheduler.schedule_global(coroutine),
+            Some(id) => scheduler.schedule_global_with_id(coroutine, id),
         }
+
+        Ok(handle)
     }
 
-    fn spawn_impl<F, T>(self, f: F) -> io::Result<(CoroutineImpl, JoinHandle<T>)> {
+    // The caller keeps what `f` borrows alive until the coroutine is done
+    unsafe fn spawn_impl<'a, F, T>(self, f: F) -> io::Result<(CoroutineImpl, JoinHandle<T>)>
+    where
+        T: Send + 'a,
+        F: FnOnce() -> T + Send + 'a,
+    {
         static DONE: Done = Done {};
 
         let scheduler = get_scheduler();
-        let name = self.name;
-        let stack_size = self.stack_size.unwrap_or_else(|| config().get_stack_size());
+        let parent = get_coroutine_local_data()
+            .and_then(|local| unsafe { local.as_ref() }.get_coroutine().name());
+        let id = self.resolve_id();
+        let name = self.resolve_name(id, parent);
+        let mut stack_size = self.stack_size.unwrap_or_else(|| config().get_stack_size());
+
+        // An odd size makes the stack paint its full footprint
+        if self.track_stack_usage {
+            stack_size |= 1;
+        }
 
         // Create a join resource, shared by waited coroutine and *this* coroutine
         let panic = Arc::new(AtomicOption::none());
         let join = Arc::new(Join::new(panic.clone()));
         let packet = Arc::new(AtomicOption::none());
-        let their_join = join.clone();
         let their_packet = packet.clone();
 
         let subscriber = EventSubscriber {
@@ -93,20 +191,31 @@ impl CoroutineBuilder {
 
         let closure = move || {
             their_packet.store(f());
-            their_join.trigger();
 
+            METRICS.completed(false);
+
+            // The join is triggered by `Done` once the stack usage is known
             subscriber
         };
 
+        let closure: Box<dyn FnOnce() -> EventSubscriber + Send + 'a> = Box::new(closure);
+
+        // SAFETY: the caller keeps `'a` alive until the coroutine is done
+        let closure: Box<dyn FnOnce() -> EventSubscriber + Send + 'static> =
+            unsafe { mem::transmute(closure) };
+
         let mut coroutine = if stack_size == config().get_stack_size() {
+            // Reuse a pooled coroutine with the default stack
             let mut coroutine = scheduler.pool.get();
 
             coroutine.init_code(closure);
 
             coroutine
+        } else {
+            GeneratorImpl::new_opt(stack_size, closure)
         };
 
-        let handle = Coroutine::new(name.into(), stack_size);
+        let handle = Coroutine::new(id, name, stack_size, self.budget.unwrap_or(DEFAULT_BUDGET));
 
         // Create the local storage
         let local = CoroutineLocal::new(handle.clone(), join.clone());
@@ -114,8 +223,63 @@ impl CoroutineBuilder {
         // Attach the local storage to the coroutine
         coroutine.set_local_data(Box::into_raw(local) as *mut u8);
 
-        scheduler.schedule(coroutine, id);
+        METRICS.spawned();
+        scheduler.coroutine_spawned();
 
         Ok((coroutine, make_join_handle(handle, join, packet, panic)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_name_uses_id() {
+        let builder = CoroutineBuilder::new().id(7);
+
+        assert_eq!(builder.resolve_id(), CoroutineId(7));
+        assert_eq!(
+            builder.resolve_name(builder.resolve_id(), None),
+            "coroutine-7"
+        );
+    }
+
+    #[test]
+    fn test_default_name_uses_counter() {
+        let builder = CoroutineBuilder::new();
+        let first = builder.resolve_name(builder.resolve_id(), None);
+        let second = builder.resolve_name(builder.resolve_id(), None);
+
+        assert!(first.starts_with("coroutine-"));
+        assert!(second.starts_with("coroutine-"));
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_explicit_name_is_kept() {
+        let builder = CoroutineBuilder::new().name("worker").id(3);
+
+        assert_eq!(
+            builder.resolve_name(builder.resolve_id(), Some("main")),
+            "worker"
+        );
+    }
+
+    #[test]
+    fn test_name_from_parent() {
+        let builder = CoroutineBuilder::new().name("child").name_from_parent();
+
+        let id = builder.resolve_id();
+
+        assert_eq!(builder.resolve_name(id, Some("parent")), "parent/child");
+        assert_eq!(builder.resolve_name(id, None), "child");
+
+        let builder = CoroutineBuilder::new().id(2).name_from_parent();
+
+        assert_eq!(
+            builder.resolve_name(builder.resolve_id(), Some("parent")),
+            "parent/coroutine-2"
+        );
+    }
+}
@@ -6,7 +6,10 @@ use std::{
     },
 };
 
-use crate::{sync::AtomicOption, unlikely::unlikely, yield_now::get_coroutine_para};
+use crate::{
+    CoroutineImpl, error::Error, scheduler::get_scheduler, set_coroutine_parameter,
+    sync::AtomicOption, unlikely::unlikely, yield_now::get_coroutine_para,
+};
 
 pub trait CancelIo {
     type Data;
@@ -16,6 +19,7 @@ pub trait CancelIo {
     #[allow(dead_code)]
     fn set(&self, io_data: Self::Data);
 
+    #[allow(dead_code)]
     fn clear(&self);
 
     unsafe fn cancel(&self) -> Option<io::Result<()>>;
@@ -75,6 +79,7 @@ impl<T: CancelIo> CancelImpl<T> {
     }
 
     // Check if the coroutine cancel is disabled
+    #[allow(dead_code)]
     pub fn is_disabled(&self) -> bool {
         self.state.load(Ordering::Acquire) >= 2
     }
@@ -95,9 +100,21 @@ impl<T: CancelIo> CancelImpl<T> {
             // Before panic clear the last coroutine error
             // This would affect future new coroutine that reuse the instance
             get_coroutine_para();
+
+            std::panic::panic_any(Error::Cancel);
         }
     }
 
+    // Register a suspended coroutine, so that cancel can take it back and reschedule it
+    pub fn set_coroutine(&self, coroutine: Arc<AtomicOption<CoroutineImpl>>) {
+        self.coroutine.store(coroutine);
+    }
+
+    // Forget the registered coroutine once it has been resumed
+    pub fn clear_coroutine(&self) {
+        self.coroutine.take();
+    }
+
     // Cancel for coroutine
     #[cold]
     pub unsafe fn cancel(&self) {
@@ -113,10 +130,7 @@ impl<T: CancelIo> CancelImpl<T> {
                 if let Some(mut coroutine) = coroutine.take() {
                     // This is not safe. Kernel may still need to use the overlapped
                     // Set the Cancel result for the coroutine
-                    set_coroutine_parameter(
-                        &mut coroutine,
-                        io::Error::new(io::ErrorKind::Other, "Cancelled"),
-                    );
+                    set_coroutine_parameter(&mut coroutine, io::Error::other("Cancelled"));
                     get_scheduler().schedule(coroutine);
                 }
             }
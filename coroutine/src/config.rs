@@ -0,0 +1,117 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    thread,
+};
+
+/// Default stack size of a coroutine, in words
+const DEFAULT_STACK_SIZE: usize = 0x1000;
+
+/// Default number of OS threads used to run blocking tasks
+const DEFAULT_BLOCKING_WORKERS: usize = 16;
+
+/// Default number of finished coroutines kept around to reuse their stacks
+const DEFAULT_POOL_CAPACITY: usize = 1000;
+
+static STACK_SIZE: AtomicUsize = AtomicUsize::new(DEFAULT_STACK_SIZE);
+static BLOCKING_WORKERS: AtomicUsize = AtomicUsize::new(DEFAULT_BLOCKING_WORKERS);
+static ZERO_STACK_ON_REUSE: AtomicBool = AtomicBool::new(false);
+static POOL_CAPACITY: AtomicUsize = AtomicUsize::new(DEFAULT_POOL_CAPACITY);
+static EAGER_STACK_ALLOC: AtomicBool = AtomicBool::new(false);
+static WORKERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Runtime configuration, changes should be made before spawning any coroutine
+#[derive(Debug)]
+pub struct Config;
+
+impl Config {
+    /// Set the default stack size of the coroutines, in words
+    pub fn set_stack_size(&self, size: usize) -> &Self {
+        STACK_SIZE.store(size, Ordering::Release);
+
+        self
+    }
+
+    /// Get the default stack size of the coroutines, in words
+    pub fn get_stack_size(&self) -> usize {
+        STACK_SIZE.load(Ordering::Acquire)
+    }
+
+    /// Set the number of OS threads used to run `spawn_blocking` tasks
+    /// Has no effect once the blocking pool is started
+    pub fn set_blocking_workers(&self, workers: usize) -> &Self {
+        assert!(workers > 0, "blocking pool needs at least one worker");
+
+        BLOCKING_WORKERS.store(workers, Ordering::Release);
+
+        self
+    }
+
+    /// Get the number of OS threads used to run `spawn_blocking` tasks
+    pub fn get_blocking_workers(&self) -> usize {
+        BLOCKING_WORKERS.load(Ordering::Acquire)
+    }
+
+    /// Set whether the used part of a stack is cleared before it goes back to the pool, so a
+    /// coroutine reusing it can't read what the previous one left behind
+    /// The used part is measured with the footprint pattern, stacks allocated before this is set
+    /// only carry a few words of it and are cleared in full
+    pub fn set_zero_stack_on_reuse(&self, zero: bool) -> &Self {
+        ZERO_STACK_ON_REUSE.store(zero, Ordering::Release);
+
+        self
+    }
+
+    /// Get whether stacks are cleared before being reused
+    pub fn get_zero_stack_on_reuse(&self) -> bool {
+        ZERO_STACK_ON_REUSE.load(Ordering::Acquire)
+    }
+
+    /// Set how many coroutines with the default stack size are kept for reuse once finished
+    /// Extra ones are dropped along with their stacks, 0 disables the pool
+    pub fn set_pool_capacity(&self, capacity: usize) -> &Self {
+        POOL_CAPACITY.store(capacity, Ordering::Release);
+
+        self
+    }
+
+    /// Get how many finished coroutines are kept for reuse
+    pub fn get_pool_capacity(&self) -> usize {
+        POOL_CAPACITY.load(Ordering::Acquire)
+    }
+
+    /// Set the number of worker threads running coroutines
+    /// Has no effect once the scheduler is started
+    pub fn set_workers(&self, workers: usize) -> &Self {
+        assert!(workers > 0, "scheduler needs at least one worker");
+
+        WORKERS.store(workers, Ordering::Release);
+
+        self
+    }
+
+    /// Get the number of worker threads running coroutines, one per CPU unless set
+    pub fn get_workers(&self) -> usize {
+        match WORKERS.load(Ordering::Acquire) {
+            0 => thread::available_parallelism().map_or(1, usize::from),
+            workers => workers,
+        }
+    }
+
+    /// Set whether new stacks are touched from end to end when allocated, committing all of
+    /// their memory up front instead of on first use
+    pub fn set_eager_stack_alloc(&self, eager: bool) -> &Self {
+        EAGER_STACK_ALLOC.store(eager, Ordering::Release);
+
+        self
+    }
+
+    /// Get whether new stacks are committed when allocated
+    pub fn get_eager_stack_alloc(&self) -> bool {
+        EAGER_STACK_ALLOC.load(Ordering::Acquire)
+    }
+}
+
+/// Get the runtime configuration
+pub fn config() -> Config {
+    Config
+}
@@ -1,5 +1,5 @@
 use std::{
-    any::TypeId, cell::RefCell, collections::HashMap, hash::BuildHasherDefault, ptr::NonNull,
+    any::TypeId, cell::RefCell, collections::HashMap, fmt, hash::BuildHasherDefault, ptr::NonNull,
     sync::Arc,
 };
 
@@ -64,3 +64,129 @@ where
         None => LOCALMAP.with(|data| f(data)),
     }
 }
+
+/// A key for coroutine local storage, declared with `coroutine_local!`
+///
+/// Inside a coroutine every coroutine gets its own lazily initialized value, which is dropped
+/// together with the coroutine. Outside of one the value is shared by the whole thread.
+pub struct LocalKey<T: 'static> {
+    // Produces the initial value on first access
+    #[doc(hidden)]
+    pub __init: fn() -> T,
+
+    // Returns the unique id of the key
+    #[doc(hidden)]
+    pub __key: fn() -> TypeId,
+}
+
+impl<T: 'static> LocalKey<T> {
+    /// Runs `f` with a reference to the value of this key for the current coroutine or thread
+    pub fn with<F, R>(&'static self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let key = (self.__key)();
+        let found = with(|data| {
+            data.borrow()
+                .get(&key)
+                .map(|value| &**value as *const dyn Opaque as *const T)
+        });
+
+        let value = match found {
+            Some(value) => value,
+            None => {
+                // Run the initializer without holding the map, it may use other keys
+                let value = Box::new((self.__init)());
+                let ptr = &*value as *const T;
+
+                with(|data| data.borrow_mut().insert(key, value));
+
+                ptr
+            }
+        };
+
+        // Values are boxed and only removed when the whole map is dropped
+        f(unsafe { &*value })
+    }
+}
+
+impl<T: 'static> fmt::Debug for LocalKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("LocalKey { .. }")
+    }
+}
+
+/// Declares coroutine local storage keys, like `thread_local!` but per coroutine
+///
+/// ```ignore
+/// coroutine_local!(static COUNTER: Cell<u32> = Cell::new(0));
+///
+/// COUNTER.with(|counter| counter.set(counter.get() + 1));
+/// ```
+#[macro_export]
+macro_rules! coroutine_local {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty = $init:expr) => {
+        $(#[$attr])*
+        $vis static $name: $crate::LocalKey<$t> = {
+            fn __init() -> $t {
+                $init
+            }
+
+            fn __key() -> ::std::any::TypeId {
+                struct __Key;
+
+                ::std::any::TypeId::of::<__Key>()
+            }
+
+            $crate::LocalKey { __init, __key }
+        };
+    };
+
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty = $init:expr; $($rest:tt)*) => {
+        $crate::coroutine_local!($(#[$attr])* $vis static $name: $t = $init);
+        $crate::coroutine_local!($($rest)*);
+    };
+
+    () => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use crate::{spawn::spawn, yield_now::yield_now};
+
+    coroutine_local! {
+        static COUNTER: Cell<u32> = Cell::new(0);
+        static NAME: String = String::from("local");
+    }
+
+    #[test]
+    fn test_coroutine_local_isolation() {
+        let handles = (1..=2)
+            .map(|step| {
+                spawn(move || {
+                    for _ in 0..10 {
+                        COUNTER.with(|counter| counter.set(counter.get() + step));
+
+                        // Let the other coroutine run in between
+                        yield_now();
+                    }
+
+                    NAME.with(|name| assert_eq!(name, "local"));
+                    COUNTER.with(Cell::get)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let results = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(results, [10, 20]);
+
+        // The thread has a value of its own
+        COUNTER.with(|counter| assert_eq!(counter.get(), 0));
+    }
+}
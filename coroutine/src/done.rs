@@ -1,12 +1,15 @@
 use log::{debug, error};
 
-use crate::event::EventSource;
+use crate::{
+    CoroutineImpl, config::config, event::EventSource, get_coroutine_local,
+    scheduler::get_scheduler,
+};
 
 pub struct Done;
 
 impl Done {
     pub(crate) fn drop_coroutine(coroutine: CoroutineImpl) {
-        let local = unsafe { Box::from_raw(get_local_coroutine(&coroutine)) };
+        let local = unsafe { Box::from_raw(get_coroutine_local(&coroutine)) };
         let name = local.get_coroutine().name();
 
         // Recycle the coroutine
@@ -23,11 +26,34 @@ impl Done {
                 "Coroutine name = {:?}, stack size = {}, used size = {}",
                 name, size, used
             );
+
+            local.get_coroutine().set_peak_stack_usage(used);
         }
 
+        // Only now the coroutine is done for whoever joins it
+        local.get_join().trigger();
+
         if size == config().get_stack_size() {
+            if config().get_zero_stack_on_reuse() {
+                coroutine.stack().scrub_used();
+            }
+
             get_scheduler().pool.put(coroutine);
         }
+
+        get_scheduler().coroutine_done();
+    }
+
+    /// Drop a coroutine stopped by its stack overflow handler
+    /// The footprint is gone and the stack may have lost its guard, so it is never reused
+    pub(crate) fn drop_overflowed(coroutine: CoroutineImpl) {
+        let local = unsafe { Box::from_raw(get_coroutine_local(&coroutine)) };
+
+        local.get_join().trigger();
+
+        drop(coroutine);
+
+        get_scheduler().coroutine_done();
     }
 }
 
@@ -1,3 +1,4 @@
+/// Payloads the runtime unwinds a coroutine with, `join` reports them as the panic value
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Error {
     /// Done panic
@@ -1,6 +1,6 @@
 use std::io;
 
-use crate::cancel::Cancel;
+use crate::{CoroutineImpl, cancel::Cancel};
 
 pub type EventResult = io::Error;
 
@@ -19,3 +19,16 @@ pub struct EventSubscriber {
 }
 
 unsafe impl Send for EventSubscriber {}
+
+impl EventSubscriber {
+    pub fn new(resource: *mut dyn EventSource) -> Self {
+        Self { resource }
+    }
+
+    /// Hand the suspended coroutine over to the event source
+    pub fn subscribe(self, coroutine_impl: CoroutineImpl) {
+        let resource = unsafe { &mut *self.resource };
+
+        resource.subscribe(coroutine_impl);
+    }
+}
@@ -0,0 +1,225 @@
+//! Generator implementation
+//! Runs a closure on a stack of its own, switching in on `resume` and out on each yield
+
+use std::{
+    any::Any,
+    mem::MaybeUninit,
+    panic::{self, AssertUnwindSafe},
+    ptr, thread,
+};
+
+use crate::{
+    error::Error,
+    generator::Yielder,
+    register_context::RegisterContext,
+    runtime::{Context, ContextStack},
+    stack::Stack,
+};
+
+/// The generator, boxed so the context linked into the context stack never moves
+pub struct GeneratorImpl<'a, A, T> {
+    /// Registers and links of the generator
+    context: Context,
+
+    /// The stack the code runs on
+    stack: Stack,
+
+    /// Parameter for the code, read back once resumed
+    para: Option<A>,
+
+    /// Value handed out by the last yield or by the return of the code
+    ret: Option<T>,
+
+    /// The code to run, taken once it starts
+    f: Option<Box<dyn FnOnce() + 'a>>,
+}
+
+unsafe impl<A: Send, T: Send> Send for GeneratorImpl<'static, A, T> {}
+
+impl<'a, A: Any, T: Any> GeneratorImpl<'a, A, T> {
+    /// Create a generator with a stack of `size` words, the code is set by `init_code`
+    pub fn new(size: usize) -> Box<Self> {
+        let mut generator = Box::new(GeneratorImpl {
+            context: Context::new(),
+            stack: Stack::new(size),
+            para: None,
+            ret: None,
+            f: None,
+        });
+
+        generator.context.para = MaybeUninit::new(&mut generator.para as &mut dyn Any);
+        generator.context.ret = MaybeUninit::new(&mut generator.ret as &mut dyn Any);
+
+        generator
+    }
+
+    /// Create a generator running `f` on a stack of `size` words
+    pub fn new_opt<F>(size: usize, f: F) -> Box<Self>
+    where
+        F: FnOnce() -> T + 'a,
+    {
+        let mut generator = GeneratorImpl::new(size);
+
+        generator.init_code(f);
+
+        generator
+    }
+
+    /// Set the code to run, replacing whatever the generator ran before
+    /// Panics if the generator is started and not done yet
+    pub fn init_code<F>(&mut self, f: F)
+    where
+        F: FnOnce() -> T + 'a,
+    {
+        assert!(
+            !self.is_started() || self.is_done(),
+            "init_code on a running generator"
+        );
+
+        let ret = &mut self.ret as *mut Option<T>;
+
+        self.f = Some(Box::new(move || unsafe { *ret = Some(f()) }));
+        self.para = None;
+        self.ret = None;
+
+        let context = &mut self.context;
+
+        // Not started, the context is the top of its own stack
+        context.parent = context;
+        context.child = ptr::null_mut();
+        context._ref = 0;
+        context.err = None;
+        context.local_data = ptr::null_mut();
+        context.budget = 0;
+        context.stack_guard = (self.stack.begin() as usize, self.stack.end() as usize);
+
+        let arg = self as *mut Self as usize;
+
+        self.context
+            .regs
+            .init_with(gen_wrapper::<A, T>, arg, ptr::null_mut(), &self.stack);
+    }
+
+    /// Run the generator until it yields or is done, returning the value it handed out
+    /// Returns `None` once it is done
+    pub fn resume(&mut self) -> Option<T> {
+        if self.is_done() {
+            return None;
+        }
+
+        // A yield takes one off, so a resumed yield can tell it wasn't cancelled instead
+        self.context._ref += 1;
+        self.resume_gen();
+
+        self.ret.take()
+    }
+
+    /// Set the parameter read back by the code once resumed
+    pub fn set_para(&mut self, para: A) {
+        self.para = Some(para);
+    }
+
+    /// Take the panic the code ended with, only kept for coroutines
+    pub fn get_panic_data(&mut self) -> Option<Box<dyn Any + Send>> {
+        self.context.err.take()
+    }
+
+    /// Attach the local storage, which makes the generator a coroutine
+    pub fn set_local_data(&mut self, data: *mut u8) {
+        self.context.local_data = data;
+    }
+
+    /// Get the local storage
+    pub fn get_local_data(&self) -> *mut u8 {
+        self.context.local_data
+    }
+
+    /// Get the stack capacity and how much of it was used, in words
+    pub fn stack_usage(&self) -> (usize, usize) {
+        (self.stack.size(), self.stack.get_used_size())
+    }
+
+    /// Get the stack
+    pub fn stack(&self) -> &Stack {
+        &self.stack
+    }
+
+    /// Warm up the cache before resuming
+    pub fn prefetch(&self) {
+        self.context.regs.prefetch();
+    }
+
+    /// Get the handle the code uses to yield values out of this generator
+    pub fn yielder(&mut self) -> Yielder<T> {
+        Yielder::new(&mut self.context, &mut self.ret)
+    }
+}
+
+impl<A, T> GeneratorImpl<'_, A, T> {
+    /// Return true if the code has run to its end
+    pub fn is_done(&self) -> bool {
+        self.is_started() && self.context._ref != 0
+    }
+
+    fn is_started(&self) -> bool {
+        self.f.is_none()
+    }
+
+    fn resume_gen(&mut self) {
+        let env = ContextStack::current();
+        let cur = env.top();
+
+        env.push_context(&mut self.context);
+        RegisterContext::swap(&mut cur.regs, &self.context.regs);
+
+        // A coroutine's panic is reported by the scheduler, a plain generator's goes on in the
+        // caller
+        if self.context.local_data.is_null() {
+            if let Some(err) = self.context.err.take() {
+                panic::resume_unwind(err);
+            }
+        }
+    }
+
+    // Resume the code so that its pending yield unwinds with `Error::Cancel`
+    #[cold]
+    fn raw_cancel(&mut self) {
+        self.context._ref = 2;
+        self.resume_gen();
+    }
+}
+
+impl<A, T> Drop for GeneratorImpl<'_, A, T> {
+    fn drop(&mut self) {
+        // Unwinding the code from a thread that is already panicking would abort
+        if self.is_started() && !self.is_done() && !thread::panicking() {
+            // Unwind the code so what lives on its stack is dropped
+            self.raw_cancel();
+        }
+
+        self.stack.drop_stack();
+    }
+}
+
+/// Entry of a generator stack, runs the code and switches back to the parent for good
+extern "sysv64" fn gen_wrapper<A, T>(arg: usize, _: *mut usize) -> ! {
+    let generator = unsafe { &mut *(arg as *mut GeneratorImpl<'_, A, T>) };
+    let f = generator.f.take().expect("generator started twice");
+
+    if let Err(err) = panic::catch_unwind(AssertUnwindSafe(f)) {
+        // Done and Cancel stop the code early, they are no failure
+        if !matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::Done | Error::Cancel)
+        ) {
+            generator.context.err = Some(err);
+        }
+    }
+
+    let env = ContextStack::current();
+    let parent = env.pop_context(&mut generator.context);
+
+    RegisterContext::load(&parent.regs);
+
+    unreachable!("resumed a finished generator");
+}
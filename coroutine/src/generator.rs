@@ -0,0 +1,160 @@
+//! Generators, coroutines resumed by hand that hand out a value on each suspension
+
+use std::{any::Any, ptr};
+
+use crate::{
+    config::config,
+    error::Error,
+    gen_impl::GeneratorImpl,
+    runtime::{Context, ContextStack},
+    yield_now::yield_context,
+};
+
+/// A generator running `f` on its own stack, each `Yielder::yield_value(v)` inside it suspends
+/// it and makes `resume` return `Some(v)`
+///
+/// The value returned by `f` is handed out by the last `resume`, use `done!()` to finish
+/// without one.
+pub struct Generator<T: Any> {
+    inner: Box<GeneratorImpl<'static, (), T>>,
+}
+
+impl<T: Any> Generator<T> {
+    /// Create a generator with the default stack size
+    pub fn new<F>(f: F) -> Generator<T>
+    where
+        F: FnOnce(&Yielder<T>) -> T + Send + 'static,
+    {
+        Generator::with_stack_size(config().get_stack_size(), f)
+    }
+
+    /// Create a generator with a stack of `stack_size` words
+    pub fn with_stack_size<F>(stack_size: usize, f: F) -> Generator<T>
+    where
+        F: FnOnce(&Yielder<T>) -> T + Send + 'static,
+    {
+        let mut inner = GeneratorImpl::new(stack_size);
+        let yielder = inner.yielder();
+
+        inner.init_code(move || f(&yielder));
+
+        Generator { inner }
+    }
+
+    /// Run the generator until it yields or finishes, returning the value it produced
+    /// Returns `None` once it is done
+    pub fn resume(&mut self) -> Option<T> {
+        self.inner.resume()
+    }
+
+    /// Return true if the generator has finished
+    pub fn is_done(&self) -> bool {
+        self.inner.is_done()
+    }
+}
+
+impl<T: Any> Iterator for Generator<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.resume()
+    }
+}
+
+/// Handed to the code of a `Generator` to suspend it with a value
+///
+/// It only works on the stack of its own generator, not from one nested in it.
+pub struct Yielder<T> {
+    context: *mut Context,
+    ret: *mut Option<T>,
+}
+
+impl<T> Yielder<T> {
+    pub(crate) fn new(context: *mut Context, ret: *mut Option<T>) -> Yielder<T> {
+        Yielder { context, ret }
+    }
+
+    /// Suspend the generator, making `resume` return `Some(v)`, until it is resumed again
+    ///
+    /// Panics with `Error::ContextErr` if not called from the generator's own code
+    pub fn yield_value(&self, v: T) {
+        let env = ContextStack::current();
+        let context = unsafe { &mut *self.context };
+
+        if !ptr::eq(env.top(), context) {
+            std::panic::panic_any(Error::ContextErr);
+        }
+
+        unsafe { *self.ret = Some(v) };
+
+        yield_context(&env, context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    use super::*;
+    use crate::done;
+
+    struct Guard(Arc<AtomicBool>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_collect_yielded_values() {
+        let generator = Generator::new(|yielder| {
+            for i in 0..5 {
+                yielder.yield_value(i);
+            }
+
+            done!()
+        });
+
+        assert_eq!(generator.collect::<Vec<u32>>(), [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_return_value_is_resumed_last() {
+        let mut generator = Generator::new(|yielder| {
+            yielder.yield_value("first");
+
+            "last"
+        });
+
+        assert_eq!(generator.resume(), Some("first"));
+        assert!(!generator.is_done());
+        assert_eq!(generator.resume(), Some("last"));
+        assert!(generator.is_done());
+        assert_eq!(generator.resume(), None);
+    }
+
+    #[test]
+    fn test_drop_unwinds_suspended_generator() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let their_dropped = dropped.clone();
+
+        let mut generator = Generator::new(move |yielder| {
+            // Only dropped if the suspended code is unwound
+            let _guard = Guard(their_dropped);
+
+            loop {
+                yielder.yield_value(());
+            }
+        });
+
+        assert_eq!(generator.resume(), Some(()));
+
+        drop(generator);
+
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+}
@@ -2,7 +2,7 @@ use std::ops::Range;
 
 use crate::{
     runtime::{ContextStack, is_generator},
-    stack::page_size,
+    stack::guard_size,
 };
 
 pub type Guard = Range<usize>;
@@ -12,5 +12,5 @@ pub fn current() -> Guard {
 
     let guard = unsafe { (*(*ContextStack::current().root).child).stack_guard };
 
-    guard.0 - page_size()..guard.1
+    guard.0 - guard_size()..guard.1
 }
@@ -6,7 +6,7 @@ use std::{
     },
 };
 
-use crate::sync::AtomicOption;
+use crate::sync::{AtomicOption, blocker::Blocker};
 
 pub struct Join {
     /// The coroutine thats waiting for this join handler
@@ -32,7 +32,7 @@ impl Join {
     }
 
     /// Sets the panic information for the coroutine
-    pub fn set_panic_data(&self, panic: Arc<AtomicOption<Box<dyn Any + Send>>>) {
+    pub fn set_panic_data(&self, panic: Box<dyn Any + Send>) {
         self.panic.store(panic);
     }
 
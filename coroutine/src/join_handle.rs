@@ -1,10 +1,59 @@
 use std::{
     any::Any,
+    error, fmt,
     sync::{Arc, atomic::Ordering},
-    thread::Result,
 };
 
-use crate::{Coroutine, error::Error, join::Join, sync::AtomicOption};
+use crate::{Coroutine, CoroutineId, join::Join, sync::AtomicOption};
+
+/// Why `JoinHandle::join` has no value to return
+pub enum JoinError {
+    /// The coroutine was cancelled before it finished
+    Cancelled,
+    /// The coroutine panicked, holding the payload it panicked with
+    Panicked(Box<dyn Any + Send>),
+}
+
+impl JoinError {
+    /// Return true if the coroutine was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, JoinError::Cancelled)
+    }
+
+    /// Return true if the coroutine panicked
+    pub fn is_panic(&self) -> bool {
+        matches!(self, JoinError::Panicked(_))
+    }
+
+    /// Take the payload the coroutine panicked with
+    /// Panics if the coroutine was cancelled instead
+    pub fn into_panic(self) -> Box<dyn Any + Send> {
+        match self {
+            JoinError::Panicked(panic) => panic,
+            JoinError::Cancelled => panic!("the coroutine was cancelled, it didn't panic"),
+        }
+    }
+}
+
+impl fmt::Debug for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JoinError::Cancelled => f.write_str("Cancelled"),
+            JoinError::Panicked(_) => f.write_str("Panicked(..)"),
+        }
+    }
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JoinError::Cancelled => f.write_str("coroutine was cancelled"),
+            JoinError::Panicked(_) => f.write_str("coroutine panicked"),
+        }
+    }
+}
+
+impl error::Error for JoinError {}
 
 /// JoinHandle for Coroutine
 pub struct JoinHandle<T> {
@@ -38,23 +87,232 @@ impl<T> JoinHandle<T> {
         &self.coroutine
     }
 
+    /// Gets the id of the coroutine, the same `current().id()` gives inside it
+    pub fn id(&self) -> CoroutineId {
+        self.coroutine.id()
+    }
+
     /// Return true if the coroutine is finished
     pub fn is_done(&self) -> bool {
         !self.join.state.load(Ordering::Acquire)
     }
 
+    /// Same as `is_done`, named after `std::thread::JoinHandle::is_finished`
+    pub fn is_finished(&self) -> bool {
+        self.is_done()
+    }
+
+    /// Words of stack used by the coroutine, for one spawned with
+    /// `CoroutineBuilder::track_stack_usage`
+    ///
+    /// `None` until the coroutine is done, or if its stack usage wasn't tracked
+    pub fn peak_stack_usage(&self) -> Option<usize> {
+        self.coroutine.peak_stack_usage()
+    }
+
+    /// Joins the coroutine if it is finished, or hands the handle back without blocking
+    pub fn try_join(self) -> Result<Result<T, JoinError>, Self> {
+        if self.is_finished() {
+            Ok(self.join())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Cancels the coroutine, making `join` return `JoinError::Cancelled`
+    ///
+    /// The coroutine unwinds at its next suspend point, or once it leaves a region where
+    /// cancellation is disabled. Does nothing if it has already finished.
+    pub fn cancel(&self) {
+        if !self.is_done() {
+            // The coroutine is kept alive by the handle and only unwinds at a suspend point
+            unsafe { self.coroutine.cancel() };
+        }
+    }
+
     /// Block until the coroutine is done
     pub fn wait(&self) {
         self.join.wait();
     }
 
     /// Join the coroutine, returning the result produced
-    pub fn join(self) -> Result<T> {
+    ///
+    /// If the coroutine panicked `JoinError::Panicked` holds the payload it panicked with,
+    /// untouched, so it can be downcast or passed to `std::panic::resume_unwind`. A coroutine
+    /// cancelled before finishing gives `JoinError::Cancelled` instead.
+    pub fn join(self) -> Result<T, JoinError> {
         self.join.wait();
 
         // Take the result
-        self.packet
-            .take()
-            .ok_or_else(|| self.panic.take().unwrap_or_else(|| Box::new(Error::Cancel)))
+        self.packet.take().ok_or_else(|| match self.panic.take() {
+            Some(panic) => JoinError::Panicked(panic),
+            None => JoinError::Cancelled,
+        })
+    }
+}
+
+/// Joins all the handles in order, collecting each result
+/// A panicking coroutine gives an `Err` in its slot instead of stopping the rest of the batch
+pub fn join_all<T>(handles: Vec<JoinHandle<T>>) -> Vec<Result<T, JoinError>> {
+    handles.into_iter().map(JoinHandle::join).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{panic::AssertUnwindSafe, sync::atomic::AtomicBool};
+
+    use super::*;
+    use crate::{
+        builder::CoroutineBuilder, error::Error, spawn::spawn, sync::blocker::Blocker,
+        yield_now::yield_now,
+    };
+
+    #[test]
+    fn test_join_all_in_spawn_order() {
+        let handles = (0..4)
+            .map(|i| {
+                spawn(move || {
+                    // Finish out of order
+                    for _ in 0..(4 - i) {
+                        yield_now();
+                    }
+
+                    if i == 2 {
+                        panic!("coroutine {i} failed");
+                    }
+
+                    i * 10
+                })
+            })
+            .collect();
+
+        let results = join_all(handles);
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(*results[0].as_ref().unwrap(), 0);
+        assert_eq!(*results[1].as_ref().unwrap(), 10);
+        assert!(results[2].is_err());
+        assert_eq!(*results[3].as_ref().unwrap(), 30);
+    }
+
+    #[test]
+    fn test_join_returns_the_panic_payload() {
+        #[derive(Debug, PartialEq)]
+        struct Failure {
+            code: u32,
+        }
+
+        let handle = spawn(|| -> u32 { std::panic::panic_any(Failure { code: 7 }) });
+        let err = handle.join().err().unwrap().into_panic();
+
+        assert_eq!(err.downcast_ref::<Failure>(), Some(&Failure { code: 7 }));
+        assert!(err.downcast_ref::<Error>().is_none());
+
+        let handle = spawn(|| -> u32 { panic!("static message") });
+        let err = handle.join().err().unwrap().into_panic();
+
+        assert_eq!(err.downcast_ref::<&str>(), Some(&"static message"));
+
+        // The payload can be raised again on the joining side
+        let handle = spawn(|| -> u32 { std::panic::panic_any(Failure { code: 9 }) });
+        let err = handle.join().err().unwrap().into_panic();
+        let reraised =
+            std::panic::catch_unwind(AssertUnwindSafe(|| std::panic::resume_unwind(err)))
+                .err()
+                .unwrap();
+
+        assert_eq!(
+            reraised.downcast_ref::<Failure>(),
+            Some(&Failure { code: 9 })
+        );
+    }
+
+    #[test]
+    fn test_cancel_parked_coroutine() {
+        let handle = spawn(|| {
+            loop {
+                Blocker::current().park(None).ok();
+            }
+        });
+
+        // Let the coroutine park first
+        yield_now();
+        handle.cancel();
+
+        let err = handle.join().err().unwrap();
+
+        assert!(err.is_cancelled());
+        assert!(!err.is_panic());
+    }
+
+    #[test]
+    fn test_cancel_finished_coroutine() {
+        let handle = spawn(|| 3);
+
+        handle.wait();
+        handle.cancel();
+
+        assert_eq!(handle.join().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_is_finished_and_try_join() {
+        let release = Arc::new(AtomicBool::new(false));
+        let their_release = release.clone();
+
+        let handle = spawn(move || {
+            // Stay suspended until released
+            while !their_release.load(Ordering::Acquire) {
+                yield_now();
+            }
+
+            7
+        });
+
+        assert!(!handle.is_finished());
+
+        let handle = handle.try_join().err().unwrap();
+
+        release.store(true, Ordering::Release);
+        handle.wait();
+
+        assert!(handle.is_finished());
+        assert_eq!(handle.try_join().ok().unwrap().unwrap(), 7);
+    }
+
+    #[test]
+    fn test_peak_stack_usage() {
+        const STACK_SIZE: usize = 0x4000;
+
+        // Each frame keeps a buffer alive on the stack
+        fn recurse(depth: usize) -> usize {
+            let frame = std::hint::black_box([depth; 16]);
+
+            match depth {
+                0 => frame[0],
+                _ => recurse(depth - 1) + frame[1],
+            }
+        }
+
+        let handle = CoroutineBuilder::new()
+            .stack_size(STACK_SIZE)
+            .track_stack_usage(true)
+            .spawn(|| recurse(64))
+            .unwrap();
+
+        handle.wait();
+
+        let used = handle.peak_stack_usage().unwrap();
+
+        assert!(used > 0);
+        assert!(used < STACK_SIZE);
+        assert_eq!(handle.join().unwrap(), (1..=64).sum::<usize>());
+
+        // Not tracked by default
+        let handle = spawn(|| recurse(64));
+
+        handle.wait();
+
+        assert_eq!(handle.peak_stack_usage(), None);
     }
 }
@@ -1,78 +1,204 @@
-use std::{borrow::Cow, fmt, ptr, sync::Arc};
+use std::{
+    borrow::Cow,
+    fmt,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
 
 use cancel::Cancel;
+use coroutine_local::{CoroutineLocal, get_coroutine_local_data};
 use done::Done;
+use event::{EventResult, EventSubscriber};
+use gen_impl::GeneratorImpl;
+use metrics::METRICS;
 use park::Park;
 
+mod blocking;
+mod budget;
 mod builder;
 mod cancel;
 mod cold;
+mod config;
 mod coroutine_local;
 mod done;
 mod error;
 mod event;
+mod gen_impl;
+mod generator;
 mod guard;
 mod id_hasher;
 mod join;
 mod join_handle;
 mod likely;
+mod metrics;
+mod panic;
 mod park;
+mod pool;
 mod register_context;
-mod runtime;
+pub mod runtime;
+mod scheduler;
+mod scope;
+mod sleep;
 mod spawn;
 mod stack;
-mod sync;
+pub mod sync;
+mod timer;
 mod unlikely;
 mod yield_now;
 
+pub use blocking::spawn_blocking;
+pub use budget::consume_budget;
+pub use builder::CoroutineBuilder;
+pub use config::{Config, config};
+pub use coroutine_local::LocalKey;
+pub use error::Error;
+pub use generator::{Generator, Yielder};
+pub use join_handle::{JoinError, JoinHandle, join_all};
+pub use metrics::{MetricsSnapshot, metrics};
+pub use panic::{CoroutinePanicInfo, set_panic_hook, take_panic_hook};
+pub use park::ParkError;
+pub use scope::{Scope, ScopedJoinHandle, scope};
+pub use sleep::sleep;
+pub use spawn::{spawn, spawn_unchecked};
+#[doc(hidden)]
+pub use yield_now::done;
+pub use yield_now::yield_now;
+
+/// The generator running a coroutine, it hands out the event the coroutine waits on
+pub(crate) type CoroutineImpl = Box<GeneratorImpl<'static, EventResult, EventSubscriber>>;
+
+/// Get the local storage of a coroutine
+#[inline]
+pub(crate) fn get_coroutine_local(coroutine: &CoroutineImpl) -> *mut CoroutineLocal {
+    coroutine.get_local_data() as *mut CoroutineLocal
+}
+
+/// Set the parameter read back by the coroutine once resumed, see `get_coroutine_para`
+#[inline]
+pub(crate) fn set_coroutine_parameter(coroutine: &mut CoroutineImpl, para: EventResult) {
+    coroutine.set_para(para);
+}
+
+/// Counter used to number coroutines spawned without an explicit id
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Identifier of a coroutine, unique within the process unless set by `CoroutineBuilder::id`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CoroutineId(usize);
+
+impl CoroutineId {
+    // Allocates a fresh id
+    pub(crate) fn next() -> CoroutineId {
+        CoroutineId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Get the id as a plain number
+    pub fn as_usize(self) -> usize {
+        self.0
+    }
+}
+
+impl From<usize> for CoroutineId {
+    fn from(id: usize) -> CoroutineId {
+        CoroutineId(id)
+    }
+}
+
+impl fmt::Display for CoroutineId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
 pub(crate) struct Inner {
+    id: CoroutineId,
     name: Option<Cow<'static, str>>,
     stack_size: usize,
+    budget: usize,
+    /// Words of stack used at completion when tracked, 0 until known
+    peak_stack_usage: AtomicUsize,
     park: Park,
     cancel: Cancel,
 }
 
+/// A handle to a coroutine, see `current` and `JoinHandle::coroutine`
 #[derive(Clone)]
-pub(crate) struct Coroutine {
+pub struct Coroutine {
     inner: Arc<Inner>,
 }
 
 impl Coroutine {
-    fn new(name: impl Into<Cow<'static, str>>, stack_size: usize) -> Coroutine {
+    fn new(
+        id: CoroutineId,
+        name: impl Into<Cow<'static, str>>,
+        stack_size: usize,
+        budget: usize,
+    ) -> Coroutine {
         Coroutine {
             inner: Arc::new(Inner {
+                id,
                 name: Some(name.into()),
                 stack_size,
+                budget,
+                peak_stack_usage: AtomicUsize::new(0),
                 park: Park::new(),
                 cancel: Cancel::new(),
             }),
         }
     }
 
-    // Gets the coroutine stack size
+    /// Gets the coroutine id
+    pub fn id(&self) -> CoroutineId {
+        self.inner.id
+    }
+
+    /// Gets the coroutine stack size
     pub fn stack_size(&self) -> usize {
         self.inner.stack_size
     }
 
-    // Atomically makes the handle's token available if it is not already
+    /// Gets the number of `consume_budget` calls between forced yields, 0 is unlimited
+    pub fn budget(&self) -> usize {
+        self.inner.budget
+    }
+
+    /// Gets the words of stack used by the coroutine, only known once a tracked one is done
+    pub fn peak_stack_usage(&self) -> Option<usize> {
+        match self.inner.peak_stack_usage.load(Ordering::Acquire) {
+            0 => None,
+            used => Some(used),
+        }
+    }
+
+    // Records the stack usage, must happen before the join is triggered
+    pub(crate) fn set_peak_stack_usage(&self, used: usize) {
+        self.inner.peak_stack_usage.store(used, Ordering::Release);
+    }
+
+    /// Atomically makes the handle's token available if it is not already
     pub fn unpark(&self) {
         self.inner.park.unpark();
     }
 
-    // Cancel a coroutine
+    /// Cancel a coroutine
+    ///
+    /// # Safety
+    ///
+    /// The coroutine must still be alive, `JoinHandle::cancel` checks that for you
     pub unsafe fn cancel(&self) {
         unsafe {
             self.inner.cancel.cancel();
         }
     }
 
-    // Gets the name of the coroutine
+    /// Gets the name of the coroutine
     pub fn name(&self) -> Option<&str> {
         self.inner.name.as_deref()
     }
 
     // Get the internal cancel
-    #[cfg(unix)]
     pub(crate) fn get_cancel(&self) -> &Cancel {
         &self.inner.cancel
     }
@@ -93,19 +219,44 @@ pub(crate) fn run_coroutine(mut coroutine: CoroutineImpl) {
             let local = unsafe { &mut *get_coroutine_local(&coroutine) };
             let join = local.get_join();
 
+            let mut overflowed = false;
+
             // Set the panic data
             if let Some(panic) = coroutine.get_panic_data() {
+                panic::report(local.get_coroutine().name(), &*panic);
+
+                overflowed = matches!(panic.downcast_ref::<Error>(), Some(Error::StackErr));
+
                 join.set_panic_data(panic);
             }
 
-            // Trigger the join here
-            join.trigger();
+            METRICS.completed(true);
 
-            Done::drop_coroutine(coroutine);
+            // The join is triggered once the coroutine is dropped
+            if overflowed {
+                Done::drop_overflowed(coroutine);
+            } else {
+                Done::drop_coroutine(coroutine);
+            }
         }
     }
 }
 
+/// Gets a handle to the current coroutine
+///
+/// Panics if not called from a coroutine, see `current_id` for a check that doesn't
+pub fn current() -> Coroutine {
+    match get_coroutine_local_data() {
+        Some(local) => unsafe { local.as_ref() }.get_coroutine().clone(),
+        None => panic!("no current coroutine, not called from a coroutine"),
+    }
+}
+
+/// Returns the id of the current coroutine, or `None` outside of a coroutine
+pub fn current_id() -> Option<CoroutineId> {
+    get_coroutine_local_data().map(|local| unsafe { local.as_ref() }.get_coroutine().id())
+}
+
 /// Returns true if current context is coroutine
 pub(crate) fn is_coroutine() -> bool {
     // We will never call this function in a pure generator context
@@ -1,5 +1,6 @@
 use crate::cold::cold;
 
+#[allow(dead_code)]
 #[inline]
 pub const fn likely(b: bool) -> bool {
     if !b {
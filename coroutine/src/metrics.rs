@@ -0,0 +1,119 @@
+//! Runtime counters for observability
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counters updated by the runtime as coroutines are spawned, parked and finished
+#[derive(Debug)]
+pub(crate) struct Metrics {
+    spawned: AtomicUsize,
+    completed: AtomicUsize,
+    panicked: AtomicUsize,
+    parked: AtomicUsize,
+    steals: AtomicUsize,
+}
+
+pub(crate) static METRICS: Metrics = Metrics::new();
+
+impl Metrics {
+    const fn new() -> Metrics {
+        Metrics {
+            spawned: AtomicUsize::new(0),
+            completed: AtomicUsize::new(0),
+            panicked: AtomicUsize::new(0),
+            parked: AtomicUsize::new(0),
+            steals: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn spawned(&self) {
+        self.spawned.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Must be called before the join is triggered, so a joined coroutine is always counted
+    pub(crate) fn completed(&self, panicked: bool) {
+        if panicked {
+            self.panicked.fetch_add(1, Ordering::Relaxed);
+        }
+
+        self.completed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Counts the coroutine as parked until the guard is dropped, even if it unwinds meanwhile
+    pub(crate) fn parked(&self) -> ParkedGuard<'_> {
+        self.parked.fetch_add(1, Ordering::Relaxed);
+
+        ParkedGuard { metrics: self }
+    }
+
+    // Called by the workers whenever they take coroutines from another worker's queue
+    pub(crate) fn stolen(&self, count: usize) {
+        self.steals.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Read all the counters at once
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            spawned: self.spawned.load(Ordering::Relaxed),
+            completed: self.completed.load(Ordering::Relaxed),
+            panicked: self.panicked.load(Ordering::Relaxed),
+            parked: self.parked.load(Ordering::Relaxed),
+            steals: self.steals.load(Ordering::Relaxed),
+        }
+    }
+}
+
+pub(crate) struct ParkedGuard<'a> {
+    metrics: &'a Metrics,
+}
+
+impl Drop for ParkedGuard<'_> {
+    fn drop(&mut self) {
+        self.metrics.parked.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// The value of the runtime counters at one point in time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// Coroutines spawned so far
+    pub spawned: usize,
+    /// Coroutines finished so far, including the ones that panicked
+    pub completed: usize,
+    /// Coroutines that finished with a panic
+    pub panicked: usize,
+    /// Coroutines currently parked
+    pub parked: usize,
+    /// Coroutines taken by a worker from another worker's queue
+    pub steals: usize,
+}
+
+/// Get a snapshot of the runtime counters
+pub fn metrics() -> MetricsSnapshot {
+    METRICS.snapshot()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spawn::spawn;
+
+    #[test]
+    fn test_spawned_and_completed() {
+        const N: usize = 20;
+
+        let before = metrics();
+
+        let handles = (0..N).map(|i| spawn(move || i)).collect::<Vec<_>>();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let after = metrics();
+
+        // Other tests spawn coroutines concurrently, so only a lower bound holds
+        assert!(after.spawned - before.spawned >= N);
+        assert!(after.completed - before.completed >= N);
+        assert!(after.completed <= after.spawned);
+    }
+}
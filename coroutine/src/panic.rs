@@ -0,0 +1,126 @@
+use std::{
+    any::Any,
+    panic::{self, PanicHookInfo},
+    sync::{
+        Once, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use crate::{error::Error, is_coroutine};
+
+type Hook = Box<dyn Fn(&CoroutinePanicInfo<'_>) + Send + Sync>;
+
+static HOOK: RwLock<Option<Hook>> = RwLock::new(None);
+
+/// Set while a hook is installed, checked by the process-wide hook without taking the lock
+static HOOK_SET: AtomicBool = AtomicBool::new(false);
+
+/// Information about a panicked coroutine, passed to the hook set with `set_panic_hook`
+#[derive(Debug)]
+pub struct CoroutinePanicInfo<'a> {
+    name: Option<&'a str>,
+    payload: &'a (dyn Any + Send),
+}
+
+impl CoroutinePanicInfo<'_> {
+    /// The name of the coroutine, if it was given one
+    pub fn name(&self) -> Option<&str> {
+        self.name
+    }
+
+    /// The payload the coroutine panicked with
+    pub fn payload(&self) -> &(dyn Any + Send) {
+        self.payload
+    }
+
+    /// The panic message, when the payload is a string
+    pub fn message(&self) -> Option<&str> {
+        if let Some(s) = self.payload.downcast_ref::<&str>() {
+            Some(s)
+        } else {
+            self.payload.downcast_ref::<String>().map(String::as_str)
+        }
+    }
+}
+
+/// Set the hook called when a coroutine panics, separate from the process-wide panic hook
+/// While it is installed, the process-wide hook no longer prints panics raised inside coroutines
+pub fn set_panic_hook(hook: Hook) {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let default = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info: &PanicHookInfo<'_>| {
+            if !(HOOK_SET.load(Ordering::Acquire) && is_coroutine()) {
+                default(info);
+            }
+        }));
+    });
+
+    *HOOK.write().unwrap() = Some(hook);
+
+    HOOK_SET.store(true, Ordering::Release);
+}
+
+/// Remove the coroutine panic hook, returning it if one was set
+pub fn take_panic_hook() -> Option<Hook> {
+    let hook = HOOK.write().unwrap().take();
+
+    HOOK_SET.store(false, Ordering::Release);
+
+    hook
+}
+
+/// Report a coroutine panic to the hook
+/// The runtime's own unwinding payloads are not panics of the coroutine and are skipped
+pub(crate) fn report(name: Option<&str>, payload: &(dyn Any + Send)) {
+    if let Some(Error::Done | Error::Cancel) = payload.downcast_ref::<Error>() {
+        return;
+    }
+
+    if let Some(hook) = HOOK.read().unwrap().as_ref() {
+        hook(&CoroutinePanicInfo { name, payload });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::builder::CoroutineBuilder;
+
+    #[test]
+    fn test_panic_hook_gets_name_and_message() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let their_seen = seen.clone();
+
+        set_panic_hook(Box::new(move |info| {
+            their_seen.lock().unwrap().push((
+                info.name().map(String::from),
+                info.message().map(String::from),
+            ));
+        }));
+
+        let handle = CoroutineBuilder::new()
+            .name("worker")
+            .spawn(|| panic!("worker failed with {}", 42))
+            .unwrap();
+
+        assert!(handle.join().is_err());
+        assert!(take_panic_hook().is_some());
+
+        // The hook is global, coroutines panicking in other tests may have reached it too
+        let seen: Vec<_> = seen
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(name, _)| name.as_deref() == Some("worker"))
+            .map(|(_, message)| message.clone())
+            .collect();
+
+        assert_eq!(seen, [Some(String::from("worker failed with 42"))]);
+    }
+}
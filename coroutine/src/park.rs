@@ -1,12 +1,22 @@
 use std::{
-    ptr,
+    fmt, io,
     sync::{
         Arc,
-        atomic::{AtomicBool, AtomicPtr, Ordering},
+        atomic::{AtomicBool, Ordering},
     },
+    time::Duration,
 };
 
-use crate::{run_coroutine, sync::AtomicOption};
+use crate::{
+    CoroutineImpl,
+    cancel::Cancel,
+    event::EventSource,
+    get_coroutine_local, run_coroutine,
+    scheduler::get_scheduler,
+    sync::{AtomicDuration, AtomicOption, Backoff},
+    timer::TimeoutHandle,
+    yield_now::{get_coroutine_para, yield_with_event},
+};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum ParkError {
@@ -14,7 +24,6 @@ pub enum ParkError {
     Timeout,
 }
 
-#[derive(Debug)]
 pub struct Park {
     // The coroutine which is waiting for this park instance
     wait_coroutine: Arc<AtomicOption<CoroutineImpl>>,
@@ -25,11 +34,11 @@ pub struct Park {
     // Control how to deal with the cancellation
     check_cancel: AtomicBool,
 
-    // Timeout setting in ms, 0 is none (park forever)
+    // Timeout of the next park, none is park forever
     timeout: AtomicDuration,
 
-    // Timer handle - can be null
-    timeout_handle: AtomicPtr<TimeoutHandle<Arc<AtomicOption<CoroutineImpl>>>>,
+    // Handle of the pending timeout, if any
+    timeout_handle: AtomicOption<Box<TimeoutHandle>>,
 
     // A flag if kernel is entered
     wait_kernel: AtomicBool,
@@ -48,7 +57,7 @@ impl Park {
             state: AtomicBool::new(false),
             check_cancel: AtomicBool::new(true),
             timeout: AtomicDuration::new(None),
-            timeout_handle: AtomicPtr::new(ptr::null_mut()),
+            timeout_handle: AtomicOption::none(),
             wait_kernel: AtomicBool::new(false),
         }
     }
@@ -59,6 +68,35 @@ impl Park {
             .store(!ignore, std::sync::atomic::Ordering::Relaxed);
     }
 
+    // Park the current coroutine until unparked, cancelled or `dur` expires
+    // An unpark that happened before returns immediately, and is consumed either way
+    pub fn park_timeout(&self, dur: Option<Duration>) -> Result<(), ParkError> {
+        // Unparked already, no need to suspend
+        if self.state.swap(false, Ordering::AcqRel) {
+            return Ok(());
+        }
+
+        self.timeout.store(dur);
+
+        yield_with_event(self);
+
+        let unparked = self.state.swap(false, Ordering::AcqRel);
+
+        // The timer and the cancel path leave an error as the coroutine parameter
+        match get_coroutine_para() {
+            None => Ok(()),
+            Some(err) if err.kind() == io::ErrorKind::TimedOut => {
+                // An unpark that raced with the timer still counts
+                if unparked {
+                    Ok(())
+                } else {
+                    Err(ParkError::Timeout)
+                }
+            }
+            Some(_) => Err(ParkError::Cancelled),
+        }
+    }
+
     // Unpark the underlying coroutine if any, push to the ready task queue
     #[inline]
     pub fn unpark(&self) {
@@ -78,3 +116,108 @@ impl Park {
         }
     }
 }
+
+impl EventSource for Park {
+    // Runs on the worker once the coroutine is off its stack
+    fn subscribe(&mut self, coroutine_impl: CoroutineImpl) {
+        let local = unsafe { &*get_coroutine_local(&coroutine_impl) };
+        let coroutine = local.get_coroutine().clone();
+
+        // Keeps `yield_back` from running before subscribing is done
+        self.wait_kernel.store(true, Ordering::Release);
+
+        self.wait_coroutine.store(coroutine_impl);
+
+        if let Some(dur) = self.timeout.take() {
+            let handle = get_scheduler().add_timer(dur, self.wait_coroutine.clone());
+
+            self.timeout_handle.store(Box::new(handle));
+        }
+
+        // An unpark that raced with storing the coroutine found nothing to wake up
+        if self.state.load(Ordering::Acquire) {
+            self.wake_up(false);
+        }
+
+        let cancel = coroutine.get_cancel();
+
+        cancel.set_coroutine(self.wait_coroutine.clone());
+
+        // A cancel that raced with subscribing found nothing to take back, do it again now
+        if cancel.is_cancelled() {
+            unsafe { cancel.cancel() };
+        }
+
+        self.wait_kernel.store(false, Ordering::Release);
+    }
+
+    // Runs on the coroutine once it is resumed
+    fn yield_back(&self, cancel: &'static Cancel) {
+        let backoff = Backoff::new();
+
+        // The coroutine may be resumed before `subscribe` is done with this park
+        while self.wait_kernel.load(Ordering::Acquire) {
+            backoff.snooze();
+        }
+
+        cancel.clear_coroutine();
+
+        if let Some(handle) = self.timeout_handle.take() {
+            get_scheduler().del_timer(*handle);
+        }
+
+        if self.check_cancel.load(Ordering::Relaxed) {
+            cancel.check_cancel();
+        }
+    }
+}
+
+impl fmt::Debug for Park {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Park")
+            .field("state", &self.state.load(Ordering::Relaxed))
+            .field("check_cancel", &self.check_cancel.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, time::Instant};
+
+    use super::*;
+    use crate::{spawn::spawn, sync::blocker::Blocker};
+
+    #[test]
+    fn test_park_timeout_expires() {
+        let handle = spawn(|| {
+            let start = Instant::now();
+            let parked = Blocker::current().park(Some(Duration::from_millis(20)));
+
+            (parked, start.elapsed())
+        });
+
+        let (parked, elapsed) = handle.join().unwrap();
+
+        assert_eq!(parked, Err(ParkError::Timeout));
+        assert!(elapsed >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_unpark_wakes_parked_coroutine() {
+        let (tx, rx) = mpsc::channel();
+
+        let handle = spawn(move || {
+            let blocker = Blocker::current();
+
+            tx.send(blocker.clone()).unwrap();
+
+            blocker.park(None)
+        });
+
+        // Unparking before the coroutine parks works as well
+        rx.recv().unwrap().unpark();
+
+        assert_eq!(handle.join().unwrap(), Ok(()));
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Mutex;
+
+use crate::{CoroutineImpl, config::config, gen_impl::GeneratorImpl};
+
+/// Finished coroutines kept by the scheduler to reuse their default sized stacks
+pub(crate) type CoroutinePool = Pool<CoroutineImpl>;
+
+/// Creates an empty coroutine with the default stack size, its code is set by `init_code`
+pub(crate) fn new_pooled_coroutine() -> CoroutineImpl {
+    GeneratorImpl::new_opt(config().get_stack_size(), || unreachable!())
+}
+
+/// A cache of reusable items, holding at most `Config::get_pool_capacity` of them
+pub(crate) struct Pool<T> {
+    items: Mutex<Vec<T>>,
+
+    // Creates an item when the pool is empty
+    create: fn() -> T,
+}
+
+impl<T> Pool<T> {
+    pub const fn new(create: fn() -> T) -> Pool<T> {
+        Pool {
+            items: Mutex::new(Vec::new()),
+            create,
+        }
+    }
+
+    /// Take an item out of the pool, or create a new one if it is empty
+    pub fn get(&self) -> T {
+        let item = self.items.lock().unwrap().pop();
+
+        item.unwrap_or_else(self.create)
+    }
+
+    /// Give an item back, it is dropped if the pool is already full
+    pub fn put(&self, item: T) {
+        let mut items = self.items.lock().unwrap();
+
+        if items.len() < config().get_pool_capacity() {
+            items.push(item);
+        }
+    }
+
+    /// Number of items ready to be reused
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.items.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+    /// Sets the pool capacity, the previous one is restored when dropped
+    struct CapacityGuard(usize);
+
+    impl CapacityGuard {
+        fn set(capacity: usize) -> CapacityGuard {
+            let previous = config().get_pool_capacity();
+
+            config().set_pool_capacity(capacity);
+
+            CapacityGuard(previous)
+        }
+    }
+
+    impl Drop for CapacityGuard {
+        fn drop(&mut self) {
+            config().set_pool_capacity(self.0);
+        }
+    }
+
+    fn new_stack() -> Box<[usize; 64]> {
+        CREATED.fetch_add(1, Ordering::SeqCst);
+
+        Box::new([0; 64])
+    }
+
+    #[test]
+    fn test_pool_recycles_up_to_capacity() {
+        let pool = Pool::new(new_stack);
+        let _capacity = CapacityGuard::set(2);
+
+        let stacks = (0..4).map(|_| pool.get()).collect::<Vec<_>>();
+        let recycled = stacks[..2]
+            .iter()
+            .map(|stack| stack.as_ptr())
+            .collect::<Vec<_>>();
+
+        assert_eq!(CREATED.load(Ordering::SeqCst), 4);
+
+        // Only two of them are kept, the rest are dropped
+        for stack in stacks {
+            pool.put(stack);
+        }
+
+        assert_eq!(pool.len(), 2);
+
+        let reused = (0..3).map(|_| pool.get()).collect::<Vec<_>>();
+
+        assert_eq!(CREATED.load(Ordering::SeqCst), 5);
+        assert_eq!(pool.len(), 0);
+
+        // Handed back in reverse order
+        let mut reused = reused[..2]
+            .iter()
+            .map(|stack| stack.as_ptr())
+            .collect::<Vec<_>>();
+
+        reused.reverse();
+
+        assert_eq!(reused, recycled);
+    }
+}
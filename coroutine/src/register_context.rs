@@ -1,4 +1,4 @@
-use crate::stack::Register;
+use crate::stack::{InitFn, Register, Stack, initialize_call_frame, swap_registers};
 
 #[derive(Debug)]
 pub struct RegisterContext {
@@ -12,4 +12,31 @@ impl RegisterContext {
             regs: Register::new(),
         }
     }
+
+    /// Prepare the registers so the first switch runs `init(arg, start)` on `stack`
+    #[inline]
+    pub fn init_with(&mut self, init: InitFn, arg: usize, start: *mut usize, stack: &Stack) {
+        initialize_call_frame(&mut self.regs, init, arg, start, stack);
+    }
+
+    /// Switch to `in_context`, saving the current registers in `out_context`
+    /// Returns once something switches back to `out_context`
+    #[inline]
+    pub fn swap(out_context: &mut RegisterContext, in_context: &RegisterContext) {
+        unsafe { swap_registers(&mut out_context.regs, &in_context.regs) }
+    }
+
+    /// Switch to `to_context` for good, the current registers are dropped
+    #[inline]
+    pub fn load(to_context: &RegisterContext) {
+        let mut cur = Register::new();
+
+        unsafe { swap_registers(&mut cur, &to_context.regs) }
+    }
+
+    /// Warm up the cache with the top of the suspended stack
+    #[inline]
+    pub fn prefetch(&self) {
+        self.regs.prefetch();
+    }
 }
@@ -1,3 +1,6 @@
+//! Runtime
+//! The generator context stack of each thread, and `shutdown` to stop the runtime threads
+
 use std::{
     any::{self, Any},
     cell::Cell,
@@ -5,7 +8,7 @@ use std::{
     ptr::{self, null_mut},
 };
 
-use crate::register_context::RegisterContext;
+use crate::{is_coroutine, register_context::RegisterContext, scheduler::get_scheduler};
 
 thread_local! {
     /// Each thread has it's own generator context stack
@@ -15,7 +18,7 @@ thread_local! {
 /// Generator Context
 #[repr(C)]
 #[repr(align(128))]
-pub struct Context {
+pub(crate) struct Context {
     /// Generator regs context
     pub regs: RegisterContext,
 
@@ -42,6 +45,9 @@ pub struct Context {
 
     /// Cached stack guard for fast path
     pub stack_guard: (usize, usize),
+
+    /// Calls left to `consume_budget` before the coroutine yields, 0 until first used
+    pub budget: usize,
 }
 
 impl Context {
@@ -57,16 +63,19 @@ impl Context {
             parent: null_mut(),
             local_data: null_mut(),
             stack_guard: (0, 0),
+            budget: 0,
         }
     }
 
     /// Check if it is generator's context
+    #[allow(dead_code)]
     #[inline]
     pub fn is_generator(&self) -> bool {
-        self.parent != self as *const _ as *mut _
+        !ptr::eq(self.parent, self)
     }
 
     /// Get current generator send parameter
+    #[allow(dead_code)]
     #[inline]
     pub fn get_para<T>(&mut self) -> Option<T>
     where
@@ -113,6 +122,7 @@ impl Context {
     }
 
     /// Set current generator return value
+    #[allow(dead_code)]
     pub fn set_ret<T>(&mut self, v: T)
     where
         T: Any,
@@ -148,7 +158,7 @@ impl Context {
 }
 
 /// Coroutine managing environment
-pub struct ContextStack {
+pub(crate) struct ContextStack {
     pub(crate) root: *mut Context,
 }
 
@@ -170,6 +180,9 @@ impl ContextStack {
     }
 
     /// Get the current context stack
+    // Never inlined, a coroutine may resume on another thread and must not see a thread local
+    // address cached from before it was suspended
+    #[inline(never)]
     pub fn current() -> ContextStack {
         let mut root = ROOT_CONTEXT_P.get();
 
@@ -188,6 +201,41 @@ impl ContextStack {
         unsafe { &mut *root.parent }
     }
 
+    /// Push the context on top of the stack, it becomes the running one
+    #[inline]
+    pub fn push_context(&self, ctx: *mut Context) {
+        let root = unsafe { &mut *self.root };
+        let ctx = unsafe { &mut *ctx };
+        let top = unsafe { &mut *root.parent };
+
+        // A suspended context keeps its own top in `parent`
+        let new_top = ctx.parent;
+
+        // Link the context under the current top
+        top.child = ctx;
+        ctx.parent = top;
+
+        root.parent = new_top;
+    }
+
+    /// Pop the context from the stack, returning its parent which becomes the running one
+    #[inline]
+    pub fn pop_context(&self, ctx: *mut Context) -> &'static mut Context {
+        let root = unsafe { &mut *self.root };
+        let ctx = unsafe { &mut *ctx };
+        let parent = unsafe { &mut *ctx.parent };
+
+        // Keep the current top in the context until it is pushed again
+        ctx.parent = root.parent;
+
+        // Unlink the context from its parent
+        parent.child = null_mut();
+
+        root.parent = parent;
+
+        parent
+    }
+
     /// Get the coroutine context
     #[inline]
     pub fn coroutine_ctx(&self) -> Option<&'static mut Context> {
@@ -196,7 +244,7 @@ impl ContextStack {
         // Search from top
         let mut ctx = unsafe { &mut *root.parent };
 
-        while ctx as *const _ != root as *const _ {
+        while !ptr::eq(ctx, root) {
             if !ctx.local_data.is_null() {
                 return Some(ctx);
             }
@@ -210,12 +258,12 @@ impl ContextStack {
 }
 
 /// Check the current context if it's generator
-#[inline]
-pub fn is_generator() -> bool {
-    let env = ContextStack::current();
-    let root = unsafe { &mut *env.root };
+// Leaves a thread without a root context alone, the fault handlers call it and must not allocate
+#[inline(never)]
+pub(crate) fn is_generator() -> bool {
+    let root = ROOT_CONTEXT_P.get();
 
-    !root.child.is_null()
+    !root.is_null() && !unsafe { (*root).child }.is_null()
 }
 
 #[inline]
@@ -235,7 +283,7 @@ pub(crate) fn get_local_data() -> *mut u8 {
     // Search from top
     let mut ctx = unsafe { &mut *root.parent };
 
-    while ctx as *const _ != root as *const _ {
+    while !ptr::eq(ctx, root) {
         if !ctx.local_data.is_null() {
             return ctx.local_data;
         }
@@ -245,3 +293,54 @@ pub(crate) fn get_local_data() -> *mut u8 {
 
     ptr::null_mut()
 }
+
+/// Waits for every coroutine to be done, then stops the worker and timer threads
+///
+/// Coroutines that stay parked keep it waiting. Spawning a coroutine afterwards starts the
+/// threads again.
+///
+/// # Panics
+///
+/// Panics if called from a coroutine, which would wait for itself
+pub fn shutdown() {
+    assert!(!is_coroutine(), "runtime::shutdown called from a coroutine");
+
+    get_scheduler().shutdown();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::{sleep::sleep, spawn::spawn};
+
+    #[test]
+    fn test_shutdown_waits_for_running_coroutines() {
+        let finished = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..4 {
+            let finished = finished.clone();
+
+            // Not joined, only the shutdown waits for them
+            spawn(move || {
+                sleep(Duration::from_millis(50)).unwrap();
+
+                finished.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+
+        shutdown();
+
+        assert_eq!(finished.load(Ordering::SeqCst), 4);
+
+        // The workers are started again
+        assert_eq!(spawn(|| 6 * 7).join().unwrap(), 42);
+    }
+}
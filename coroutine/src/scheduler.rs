@@ -0,0 +1,330 @@
+//! Scheduler
+//! Worker threads running the coroutines that are ready, see `runtime::shutdown` to stop them
+//!
+//! Ready coroutines run in the order they were queued. Spawned and yielding coroutines go to the
+//! back of the global queue, so a coroutine that keeps yielding waits for every one queued before
+//! it. A worker runs the coroutines woken on it first, but takes from the global queue at least
+//! once every `GLOBAL_POLL_INTERVAL` turns, so those can't starve the global queue either.
+
+use std::{
+    cell::Cell,
+    collections::VecDeque,
+    sync::{
+        Condvar, LazyLock, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    CoroutineImpl,
+    config::config,
+    metrics::METRICS,
+    pool::{CoroutinePool, new_pooled_coroutine},
+    run_coroutine,
+    timer::{TimeoutHandle, Timer, TimerEntry},
+};
+
+/// Number of turns after which a worker takes from the global queue before its own
+const GLOBAL_POLL_INTERVAL: usize = 16;
+
+static SCHEDULER: LazyLock<Scheduler> = LazyLock::new(Scheduler::new);
+
+thread_local! {
+    /// Index of the worker running on this thread, `None` on other threads
+    static WORKER_ID: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Get the scheduler, its workers are started by the first coroutine scheduled
+#[inline]
+pub(crate) fn get_scheduler() -> &'static Scheduler {
+    &SCHEDULER
+}
+
+// Never inlined, a coroutine may resume on another worker and must not see a thread local
+// address cached from before it was suspended
+#[inline(never)]
+fn current_worker() -> Option<usize> {
+    WORKER_ID.get()
+}
+
+struct RunQueue {
+    /// Ready coroutines any worker may take
+    global: VecDeque<CoroutineImpl>,
+
+    /// Ready coroutines of each worker, the others steal from them when they run out
+    local: Vec<VecDeque<CoroutineImpl>>,
+
+    /// Set by `shutdown`, the workers exit once no coroutine is left
+    stopping: bool,
+}
+
+impl RunQueue {
+    fn is_empty(&self) -> bool {
+        self.global.is_empty() && self.local.iter().all(VecDeque::is_empty)
+    }
+}
+
+pub(crate) struct Scheduler {
+    /// Finished coroutines with the default stack size, reused by `spawn`
+    pub pool: CoroutinePool,
+
+    queue: Mutex<RunQueue>,
+
+    /// Signaled when a coroutine is ready, or when the last one is done
+    ready: Condvar,
+
+    /// Coroutines spawned and not done yet
+    live: AtomicUsize,
+
+    /// Set once the workers are started
+    started: AtomicBool,
+
+    /// The worker threads
+    workers: Mutex<Vec<thread::JoinHandle<()>>>,
+
+    timer: Timer,
+}
+
+impl Scheduler {
+    fn new() -> Scheduler {
+        let workers = config().get_workers();
+
+        Scheduler {
+            pool: CoroutinePool::new(new_pooled_coroutine),
+            queue: Mutex::new(RunQueue {
+                global: VecDeque::new(),
+                local: (0..workers).map(|_| VecDeque::new()).collect(),
+                stopping: false,
+            }),
+            ready: Condvar::new(),
+            live: AtomicUsize::new(0),
+            started: AtomicBool::new(false),
+            workers: Mutex::new(Vec::new()),
+            timer: Timer::new(),
+        }
+    }
+
+    /// Queue a ready coroutine on the current worker, or on the global queue from any other
+    /// thread
+    pub fn schedule(&'static self, coroutine: CoroutineImpl) {
+        self.push(current_worker(), coroutine);
+    }
+
+    /// Queue a ready coroutine on the global queue
+    pub fn schedule_global(&'static self, coroutine: CoroutineImpl) {
+        self.push(None, coroutine);
+    }
+
+    /// Queue a ready coroutine on the worker `id`, modulo the number of workers
+    pub fn schedule_global_with_id(&'static self, coroutine: CoroutineImpl, id: usize) {
+        self.push(Some(id), coroutine);
+    }
+
+    /// Count a new coroutine as live until `coroutine_done`
+    pub fn coroutine_spawned(&self) {
+        self.live.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Count a coroutine as done, it won't be scheduled anymore
+    pub fn coroutine_done(&self) {
+        if self.live.fetch_sub(1, Ordering::AcqRel) == 1 {
+            // Taking the lock makes sure a worker about to wait sees the count first
+            let _queue = self.queue.lock().unwrap();
+
+            self.ready.notify_all();
+        }
+    }
+
+    /// Wait for every live coroutine to be done, then stop the workers and the timer
+    /// Coroutines scheduled afterwards start them again
+    pub fn shutdown(&'static self) {
+        // Held until the workers are gone, so `start` can't race with stopping
+        let mut workers = self.workers.lock().unwrap();
+
+        self.queue.lock().unwrap().stopping = true;
+        self.ready.notify_all();
+
+        for worker in workers.drain(..) {
+            worker.join().expect("A coroutine worker panicked");
+        }
+
+        self.timer.stop();
+
+        let mut queue = self.queue.lock().unwrap();
+
+        queue.stopping = false;
+        self.started.store(false, Ordering::Release);
+
+        // Spawned while the workers were exiting, nobody would run them otherwise
+        let pending = !queue.is_empty();
+
+        drop(queue);
+        drop(workers);
+
+        if pending {
+            self.start();
+        }
+    }
+
+    /// Reschedule the coroutine in `entry` with a timeout error once `dur` has elapsed
+    pub fn add_timer(&'static self, dur: Duration, entry: TimerEntry) -> TimeoutHandle {
+        self.timer.add(dur, entry)
+    }
+
+    /// Drop a pending timeout
+    pub fn del_timer(&self, handle: TimeoutHandle) {
+        self.timer.remove(handle);
+    }
+
+    fn push(&'static self, worker: Option<usize>, coroutine: CoroutineImpl) {
+        let mut queue = self.queue.lock().unwrap();
+
+        match worker {
+            Some(id) => {
+                let workers = queue.local.len();
+
+                queue.local[id % workers].push_back(coroutine);
+            }
+            None => queue.global.push_back(coroutine),
+        }
+
+        drop(queue);
+
+        self.ready.notify_one();
+        self.start();
+    }
+
+    fn start(&'static self) {
+        if self.started.load(Ordering::Acquire) {
+            return;
+        }
+
+        let mut workers = self.workers.lock().unwrap();
+
+        if self.started.load(Ordering::Acquire) {
+            return;
+        }
+
+        let count = self.queue.lock().unwrap().local.len();
+
+        for id in 0..count {
+            let worker = thread::Builder::new()
+                .name(format!("coroutine-worker-{id}"))
+                .spawn(move || self.run_worker(id))
+                .expect("Failed to spawn a coroutine worker");
+
+            workers.push(worker);
+        }
+
+        self.started.store(true, Ordering::Release);
+    }
+
+    fn run_worker(&self, id: usize) {
+        crate::stack::overflow::init_once();
+
+        WORKER_ID.set(Some(id));
+
+        let mut turn = 0;
+
+        while let Some(coroutine) = self.next(id, turn) {
+            turn += 1;
+
+            coroutine.prefetch();
+
+            run_coroutine(coroutine);
+        }
+    }
+
+    // Take the next ready coroutine, own queue first, then the global one, then the others'
+    // Every `GLOBAL_POLL_INTERVAL` turns the global queue goes first
+    fn next(&self, id: usize, turn: usize) -> Option<CoroutineImpl> {
+        let mut queue = self.queue.lock().unwrap();
+
+        if turn % GLOBAL_POLL_INTERVAL == 0 {
+            if let Some(coroutine) = queue.global.pop_front() {
+                return Some(coroutine);
+            }
+        }
+
+        loop {
+            if let Some(coroutine) = queue.local[id].pop_front() {
+                return Some(coroutine);
+            }
+
+            if let Some(coroutine) = queue.global.pop_front() {
+                return Some(coroutine);
+            }
+
+            if let Some(coroutine) = queue.steal(id) {
+                return Some(coroutine);
+            }
+
+            if queue.stopping && self.live.load(Ordering::Acquire) == 0 {
+                return None;
+            }
+
+            queue = self.ready.wait(queue).unwrap();
+        }
+    }
+}
+
+impl RunQueue {
+    // Move the newer half of the first busy worker after `id` to the queue of `id`, and return
+    // the oldest of them
+    fn steal(&mut self, id: usize) -> Option<CoroutineImpl> {
+        let workers = self.local.len();
+        let victim = (1..workers)
+            .map(|i| (id + i) % workers)
+            .find(|&victim| !self.local[victim].is_empty())?;
+
+        let len = self.local[victim].len();
+        let mut stolen = self.local[victim].split_off(len / 2);
+
+        METRICS.stolen(stolen.len());
+
+        let coroutine = stolen.pop_front();
+
+        self.local[id].append(&mut stolen);
+
+        coroutine
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use crate::{spawn::spawn, yield_now::yield_now};
+
+    #[test]
+    fn test_spawned_coroutines_all_run() {
+        let steps = Arc::new(AtomicUsize::new(0));
+
+        let handles = (0..64)
+            .map(|i| {
+                let steps = steps.clone();
+
+                spawn(move || {
+                    for _ in 0..4 {
+                        steps.fetch_add(1, Ordering::Relaxed);
+
+                        yield_now();
+                    }
+
+                    i
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for (i, handle) in handles.into_iter().enumerate() {
+            assert_eq!(handle.join().unwrap(), i);
+        }
+
+        assert_eq!(steps.load(Ordering::Relaxed), 64 * 4);
+    }
+}
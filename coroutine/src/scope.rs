@@ -0,0 +1,230 @@
+//! Scoped coroutines, which may borrow from the stack of the spawner
+
+use std::{
+    any::Any,
+    marker::PhantomData,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+    thread::Result,
+};
+
+use crate::{
+    builder::CoroutineBuilder, coroutine_local::get_coroutine_local_data, error::Error,
+    join_handle::JoinHandle, sync::WaitGroup,
+};
+
+/// A scope to spawn coroutines in, see `scope`
+pub struct Scope<'scope, 'env: 'scope> {
+    /// Coroutines of this scope that have not finished yet
+    running: WaitGroup,
+
+    /// Payload of the first coroutine that panicked without being joined
+    first_panic: Mutex<Option<Box<dyn Any + Send>>>,
+
+    scope: PhantomData<&'scope mut &'scope ()>,
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+/// The result of a scoped coroutine, shared by the coroutine and its handle
+struct Packet<'scope, T> {
+    result: Mutex<Option<Result<T>>>,
+    first_panic: &'scope Mutex<Option<Box<dyn Any + Send>>>,
+}
+
+impl<T> Drop for Packet<'_, T> {
+    fn drop(&mut self) {
+        // Nobody joined the coroutine, so its panic goes to the scope. A cancelled coroutine
+        // didn't panic, it has nothing to report
+        if let Some(Err(panic)) = self.result.get_mut().unwrap().take()
+            && !is_cancel(&*panic)
+        {
+            self.first_panic.lock().unwrap().get_or_insert(panic);
+        }
+    }
+}
+
+// Whether the payload is the unwinding of a cancelled coroutine
+fn is_cancel(payload: &(dyn Any + Send)) -> bool {
+    matches!(payload.downcast_ref::<Error>(), Some(Error::Cancel))
+}
+
+/// Creates a scope for spawning coroutines that borrow non `'static` data
+///
+/// All the coroutines spawned in the scope are finished before `scope` returns. If one of them
+/// panicked and was not joined, the panic is raised again here once they are all done.
+///
+/// Cancelling the coroutine calling `scope` takes effect once the spawned coroutines are done,
+/// it is raised again over any of their panics.
+pub fn scope<'env, F, T>(f: F) -> T
+where
+    F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> T,
+{
+    let scope = Scope {
+        running: WaitGroup::new(),
+        first_panic: Mutex::new(None),
+        scope: PhantomData,
+        env: PhantomData,
+    };
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| f(&scope)));
+
+    scope.wait_all();
+
+    if let Err(panic) = &result
+        && is_cancel(&**panic)
+    {
+        panic::resume_unwind(result.err().unwrap());
+    }
+
+    if let Some(panic) = scope.first_panic.lock().unwrap().take() {
+        panic::resume_unwind(panic);
+    }
+
+    result.unwrap_or_else(|panic| panic::resume_unwind(panic))
+}
+
+impl Scope<'_, '_> {
+    // Borrows handed to the coroutines must stay alive until they are all done, so a cancel
+    // can't cut the wait short and is only raised once it is over
+    fn wait_all(&self) {
+        let Some(local) = get_coroutine_local_data() else {
+            return self.running.wait();
+        };
+        let cancel = unsafe { local.as_ref() }.get_coroutine().get_cancel();
+
+        cancel.disable_cancel();
+        self.running.wait();
+        cancel.enable_cancel();
+
+        cancel.check_cancel();
+    }
+}
+
+impl<'scope> Scope<'scope, '_> {
+    /// Spawns a coroutine that may borrow anything outliving the scope
+    pub fn spawn<F, T>(&'scope self, f: F) -> ScopedJoinHandle<'scope, T>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        let packet = Arc::new(Packet {
+            result: Mutex::new(None),
+            first_panic: &self.first_panic,
+        });
+        let their_packet = packet.clone();
+        let running = self.running.clone();
+
+        running.add(1);
+
+        let main = move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(f));
+
+            *their_packet.result.lock().unwrap() = Some(result);
+
+            // Hand an unjoined panic over before the scope can observe the count drop
+            drop(their_packet);
+            running.done();
+        };
+
+        // SAFETY: `scope` waits for every coroutine before the borrowed data goes away
+        let handle = unsafe { CoroutineBuilder::new().spawn_unchecked(main) }
+            .expect("failed to spawn a scoped coroutine");
+
+        ScopedJoinHandle { handle, packet }
+    }
+}
+
+/// Handle to a coroutine spawned in a `Scope`
+pub struct ScopedJoinHandle<'scope, T> {
+    handle: JoinHandle<()>,
+    packet: Arc<Packet<'scope, T>>,
+}
+
+impl<T> ScopedJoinHandle<'_, T> {
+    /// Return true if the coroutine is finished
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Wait for the coroutine to finish and return its result
+    ///
+    /// A panic returned here is handled, the scope won't raise it again
+    pub fn join(self) -> Result<T> {
+        self.handle.wait();
+
+        self.packet.result.lock().unwrap().take().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        thread,
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::{sleep::sleep, spawn::spawn};
+
+    #[test]
+    fn test_scoped_spawn_borrows_local_slice() {
+        let numbers = (1..=100).collect::<Vec<u32>>();
+        let mut total = 0;
+
+        scope(|s| {
+            let handles = numbers
+                .chunks(10)
+                .map(|chunk| s.spawn(move || chunk.iter().sum::<u32>()))
+                .collect::<Vec<_>>();
+
+            total = handles.into_iter().map(|h| h.join().unwrap()).sum();
+        });
+
+        assert_eq!(total, 5050);
+    }
+
+    #[test]
+    fn test_scope_propagates_unjoined_panic() {
+        let err = panic::catch_unwind(|| {
+            scope(|s| {
+                s.spawn(|| panic!("scoped failure"));
+            })
+        })
+        .err()
+        .unwrap();
+
+        assert_eq!(err.downcast_ref::<&str>(), Some(&"scoped failure"));
+    }
+
+    #[test]
+    fn test_joined_panic_is_handled() {
+        let joined = scope(|s| s.spawn(|| panic!("handled")).join());
+
+        assert!(joined.is_err());
+    }
+
+    #[test]
+    fn test_cancelled_scope_waits_for_its_coroutines() {
+        let finished = Arc::new(AtomicBool::new(false));
+        let their_finished = finished.clone();
+
+        let handle = spawn(move || {
+            scope(|s| {
+                s.spawn(|| {
+                    sleep(Duration::from_millis(50)).unwrap();
+
+                    their_finished.store(true, Ordering::SeqCst);
+                });
+            });
+        });
+
+        // Let the scope start waiting
+        thread::sleep(Duration::from_millis(10));
+        handle.cancel();
+
+        // Cancelled rather than panicked, and only once the scoped coroutine was done
+        assert!(handle.join().err().unwrap().is_cancelled());
+        assert!(finished.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,137 @@
+use std::{io, sync::Arc, thread, time::Duration};
+
+use crate::{
+    CoroutineImpl,
+    cancel::Cancel,
+    event::EventSource,
+    get_coroutine_local, is_coroutine,
+    metrics::METRICS,
+    park::ParkError,
+    scheduler::get_scheduler,
+    sync::AtomicOption,
+    yield_now::{get_coroutine_para, yield_with_event},
+};
+
+/// Event parking the current coroutine until its timer fires or it is cancelled
+struct Sleep {
+    dur: Duration,
+}
+
+impl EventSource for Sleep {
+    fn subscribe(&mut self, coroutine_impl: CoroutineImpl) {
+        // The timer may resume the coroutine and free `self` as soon as it is added
+        let dur = self.dur;
+        let local = unsafe { &*get_coroutine_local(&coroutine_impl) };
+        let handle = local.get_coroutine().clone();
+        let cancel = handle.get_cancel();
+
+        // Shared by the timer and the cancel path, whichever takes the coroutine out first
+        // reschedules it
+        let coroutine = Arc::new(AtomicOption::none());
+
+        coroutine.store(coroutine_impl);
+        cancel.set_coroutine(coroutine.clone());
+        get_scheduler().add_timer(dur, coroutine);
+
+        // A cancel that raced with subscribing found nothing to take back, do it again now
+        if cancel.is_cancelled() {
+            unsafe { cancel.cancel() };
+        }
+    }
+
+    // Runs on the coroutine once it is resumed, the timeout or cancel error is left as the
+    // coroutine parameter for `sleep` to read
+    fn yield_back(&self, cancel: &'static Cancel) {
+        // Resumed, the cancel path must not take the coroutine back anymore
+        cancel.clear_coroutine();
+        cancel.check_cancel();
+    }
+}
+
+/// Suspends the current coroutine for at least `dur`
+///
+/// A cancelled coroutine unwinds out of its sleep, like it does at any other suspend point. Only
+/// where cancellation is disabled does the sleep end early with `ParkError::Cancelled`. Outside
+/// of a coroutine this blocks the thread instead and always succeeds
+pub fn sleep(dur: Duration) -> Result<(), ParkError> {
+    if !is_coroutine() {
+        thread::sleep(dur);
+
+        return Ok(());
+    }
+
+    {
+        let _parked = METRICS.parked();
+
+        yield_with_event(&Sleep { dur });
+    }
+
+    // The timer leaves a timeout as the coroutine parameter, the cancel path any other error
+    match get_coroutine_para() {
+        None => Ok(()),
+        Some(err) if err.kind() == io::ErrorKind::TimedOut => Ok(()),
+        Some(_) => Err(ParkError::Cancelled),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::{current, spawn::spawn};
+
+    #[test]
+    fn test_sleep_resumes_after_duration() {
+        let dur = Duration::from_millis(50);
+
+        let handle = spawn(move || {
+            let start = Instant::now();
+
+            sleep(dur).map(|()| start.elapsed())
+        });
+
+        let elapsed = handle.join().unwrap().unwrap();
+
+        assert!(elapsed >= dur, "{elapsed:?}");
+        assert!(elapsed < dur * 10, "{elapsed:?}");
+    }
+
+    #[test]
+    fn test_sleep_cancelled() {
+        let handle = spawn(|| {
+            let coroutine = current();
+            let cancel = coroutine.get_cancel();
+
+            // The cancel can't unwind the coroutine here, the sleep has to end by itself
+            cancel.disable_cancel();
+
+            let result = sleep(Duration::from_secs(60));
+
+            cancel.enable_cancel();
+
+            result
+        });
+
+        // Give the coroutine time to start sleeping
+        thread::sleep(Duration::from_millis(20));
+        handle.cancel();
+
+        assert_eq!(handle.join().unwrap(), Err(ParkError::Cancelled));
+    }
+
+    #[test]
+    fn test_cancel_sleep_loop() {
+        // Ignoring the result must not keep a cancelled coroutine looping
+        let handle = spawn(|| {
+            loop {
+                sleep(Duration::from_millis(5)).ok();
+            }
+        });
+
+        thread::sleep(Duration::from_millis(20));
+        handle.cancel();
+
+        assert!(handle.join().unwrap_err().is_cancelled());
+    }
+}
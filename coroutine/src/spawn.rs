@@ -1,11 +1,49 @@
-use std::thread::JoinHandle;
+use crate::{builder::CoroutineBuilder, join_handle::JoinHandle};
 
-use crate::builder::CoroutineBuilder;
-
-pub unsafe fn spawn<F, T>(f: F) -> JoinHandle<()>
+/// Spawns a new coroutine with the default configuration, returning a `JoinHandle` for it
+///
+/// Panics if the coroutine can not be created, use `CoroutineBuilder` to handle the error. See
+/// `CoroutineBuilder::spawn` for where the coroutine runs
+pub fn spawn<F, T>(f: F) -> JoinHandle<T>
 where
     F: FnOnce() -> T + Send + 'static,
     T: Send + 'static,
 {
-    spawn_coroutine_builder(f, CoroutineBuilder::new())
+    CoroutineBuilder::new().spawn(f).unwrap()
+}
+
+/// Same as `spawn`, except that `f` and its result only need to outlive `'a`
+///
+/// # Safety
+///
+/// See `CoroutineBuilder::spawn_unchecked`
+pub unsafe fn spawn_unchecked<'a, F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'a,
+    T: Send + 'a,
+{
+    unsafe { CoroutineBuilder::new().spawn_unchecked(f).unwrap() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spawn_returns_value() {
+        let handle = spawn(|| 6 * 7);
+
+        assert_eq!(handle.join().unwrap(), 42);
+    }
+
+    #[test]
+    fn test_builder_spawn_returns_value() {
+        let handle = CoroutineBuilder::new()
+            .name("answer")
+            .spawn(|| String::from("done"))
+            .unwrap();
+
+        assert_eq!(handle.coroutine().name(), Some("answer"));
+        assert_eq!(handle.join().unwrap(), "done");
+    }
 }
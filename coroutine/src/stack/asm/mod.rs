@@ -12,3 +12,8 @@ pub(crate) fn mut_offset<T>(ptr: *mut T, count: isize) -> *mut T {
 }
 
 pub(crate) type InitFn = extern "sysv64" fn(usize, *mut usize) -> !;
+
+mod x86_64;
+
+pub use x86_64::Register;
+pub(crate) use x86_64::{initialize_call_frame, swap_registers};
@@ -0,0 +1,191 @@
+//! Context switching on x86_64, using the System V calling convention on every platform
+
+use crate::stack::{
+    Stack,
+    asm::{InitFn, align_down, mut_offset},
+};
+
+#[cfg(unix)]
+const GPR_COUNT: usize = 8;
+#[cfg(windows)]
+const GPR_COUNT: usize = 11;
+
+// Switching saves the callee saved registers of the running side in the `Register` it hands
+// out, laid out as rbx, rsp, rbp, (unused), r12, r13, r14, r15, and loads those of the other
+// side. A new stack starts in `bootstrap_green_task`, which calls `r14(r12, r13)` with an aligned
+// stack, see `initialize_call_frame`
+#[cfg(unix)]
+core::arch::global_asm!(
+    ".text",
+    ".global prefetch",
+    ".type prefetch, @function",
+    ".align 16",
+    "prefetch:",
+    "    prefetcht1 (%rdi)",
+    "    ret",
+    ".size prefetch, .-prefetch",
+    "",
+    ".global bootstrap_green_task",
+    ".type bootstrap_green_task, @function",
+    ".align 16",
+    "bootstrap_green_task:",
+    "    mov %r12, %rdi",
+    "    mov %r13, %rsi",
+    "    and $-16, %rsp",
+    "    mov %r14, (%rsp)",
+    "    ret",
+    ".size bootstrap_green_task, .-bootstrap_green_task",
+    "",
+    ".global swap_registers",
+    ".type swap_registers, @function",
+    ".align 16",
+    "swap_registers:",
+    "    mov %rbx, (0*8)(%rdi)",
+    "    mov %rsp, (1*8)(%rdi)",
+    "    mov %rbp, (2*8)(%rdi)",
+    "    mov %r12, (4*8)(%rdi)",
+    "    mov %r13, (5*8)(%rdi)",
+    "    mov %r14, (6*8)(%rdi)",
+    "    mov %r15, (7*8)(%rdi)",
+    "    mov (0*8)(%rsi), %rbx",
+    "    mov (1*8)(%rsi), %rsp",
+    "    mov (2*8)(%rsi), %rbp",
+    "    mov (4*8)(%rsi), %r12",
+    "    mov (5*8)(%rsi), %r13",
+    "    mov (6*8)(%rsi), %r14",
+    "    mov (7*8)(%rsi), %r15",
+    "    pop %rax",
+    "    jmp *%rax",
+    ".size swap_registers, .-swap_registers",
+    options(att_syntax)
+);
+
+// Same as above, with COFF symbols, and the stack bounds of the thread information block saved
+// next to the registers, laid out as StackBase, StackLimit, DeallocationStack. Windows checks
+// unwinding against them, so they have to follow the stack being switched to
+#[cfg(windows)]
+core::arch::global_asm!(
+    ".text",
+    ".global prefetch",
+    ".align 16",
+    "prefetch:",
+    "    prefetcht1 (%rdi)",
+    "    ret",
+    "",
+    ".global bootstrap_green_task",
+    ".align 16",
+    "bootstrap_green_task:",
+    "    mov %r12, %rdi",
+    "    mov %r13, %rsi",
+    "    and $-16, %rsp",
+    "    mov %r14, (%rsp)",
+    "    ret",
+    "",
+    ".global swap_registers",
+    ".align 16",
+    "swap_registers:",
+    "    mov %rbx, (0*8)(%rdi)",
+    "    mov %rsp, (1*8)(%rdi)",
+    "    mov %rbp, (2*8)(%rdi)",
+    "    mov %r12, (4*8)(%rdi)",
+    "    mov %r13, (5*8)(%rdi)",
+    "    mov %r14, (6*8)(%rdi)",
+    "    mov %r15, (7*8)(%rdi)",
+    "    mov %gs:0x08, %rax",
+    "    mov %rax, (8*8)(%rdi)",
+    "    mov %gs:0x10, %rax",
+    "    mov %rax, (9*8)(%rdi)",
+    "    mov %gs:0x1478, %rax",
+    "    mov %rax, (10*8)(%rdi)",
+    "    mov (0*8)(%rsi), %rbx",
+    "    mov (1*8)(%rsi), %rsp",
+    "    mov (2*8)(%rsi), %rbp",
+    "    mov (4*8)(%rsi), %r12",
+    "    mov (5*8)(%rsi), %r13",
+    "    mov (6*8)(%rsi), %r14",
+    "    mov (7*8)(%rsi), %r15",
+    "    mov (8*8)(%rsi), %rax",
+    "    mov %rax, %gs:0x08",
+    "    mov (9*8)(%rsi), %rax",
+    "    mov %rax, %gs:0x10",
+    "    mov (10*8)(%rsi), %rax",
+    "    mov %rax, %gs:0x1478",
+    "    pop %rax",
+    "    jmp *%rax",
+    options(att_syntax)
+);
+
+unsafe extern "sysv64" {
+    pub fn bootstrap_green_task();
+    pub fn prefetch(data: *const usize);
+    pub fn swap_registers(out_regs: *mut Register, in_regs: *const Register);
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct Register {
+    gpr: [usize; GPR_COUNT],
+}
+
+impl Register {
+    pub fn new() -> Register {
+        Register {
+            gpr: [0; GPR_COUNT],
+        }
+    }
+
+    #[inline]
+    pub fn prefetch(&self) {
+        let ptr = self.gpr[1] as *const usize;
+
+        unsafe {
+            prefetch(ptr); // RSP
+            prefetch(ptr.add(8)); // RSP + 8
+        }
+    }
+}
+
+pub fn initialize_call_frame(
+    regs: &mut Register,
+    fptr: InitFn,
+    arg: usize,
+    arg2: *mut usize,
+    stack: &Stack,
+) {
+    // Redefinitions from runtime/arch/x86_64/regs.h
+    const RUSTRT_RSP: usize = 1;
+    const RUSTRT_RBP: usize = 2;
+    const RUSTRT_R12: usize = 4;
+    const RUSTRT_R13: usize = 5;
+    const RUSTRT_R14: usize = 6;
+
+    let sp = align_down(stack.end());
+
+    // These registers are frobbed by bootstrap_green_task into the right location so we can
+    // invoke the "real init function", `fptr`
+    regs.gpr[RUSTRT_R12] = arg;
+    regs.gpr[RUSTRT_R13] = arg2 as usize;
+    regs.gpr[RUSTRT_R14] = fptr as usize;
+
+    // Last base pointer on the stack should be 0
+    regs.gpr[RUSTRT_RBP] = 0;
+
+    // Setup the init stack
+    // This is prepared for the swap context
+    regs.gpr[RUSTRT_RSP] = mut_offset(sp, -2) as usize;
+
+    unsafe {
+        // Leave enough space for RET
+        *mut_offset(sp, -2) = bootstrap_green_task as *const () as usize;
+        *mut_offset(sp, -1) = 0;
+    }
+
+    // The stack bounds the thread information block takes on while the stack runs, the guard
+    // region below it is left out so Windows doesn't try to grow the stack into it
+    #[cfg(windows)]
+    {
+        regs.gpr[8] = stack.buf.top as usize;
+        regs.gpr[9] = stack.buf.bottom as usize;
+        regs.gpr[10] = stack.buf.bottom as usize;
+    }
+}
@@ -1,19 +1,33 @@
-use std::{os::raw::c_void, ptr};
+use std::{cell::Cell, os::raw::c_void, ptr};
 
-//pub(crate) use asm::{InitFn, align_down, mut_offset};
+use crate::config::config;
+
+pub use asm::Register;
+pub(crate) use asm::{InitFn, initialize_call_frame, swap_registers};
+pub(crate) use sys::{guard_size, overflow};
 pub use sys_stack::SysStack;
-pub use unix::{page_size, x86_64::Register};
 
 mod asm;
 mod stack_error;
 mod sys_stack;
+#[cfg(unix)]
 mod unix;
+#[cfg(windows)]
+mod windows;
+
+#[cfg(unix)]
+use unix as sys;
+#[cfg(windows)]
+use windows as sys;
 
 /// Generator stack
 /// This struct will not deallocate the memory
 /// `StackBox` will track and deallocate it
 pub struct Stack {
     buf: SysStack,
+
+    /// Words from the low end still holding the footprint pattern, only those are scanned
+    painted: Cell<usize>,
 }
 
 impl Stack {
@@ -22,22 +36,30 @@ impl Stack {
         let track = (size & 1) != 0;
         let bytes = usize::max(size * std::mem::size_of::<usize>(), SysStack::min_size());
         let buf = SysStack::allocate(bytes, true).expect("Failed to allocate sys stack");
-        let stack = Stack { buf };
-
-        // If size is not `even` we do the full footprint test
-        let count = if track {
-            stack.size()
-        } else {
-            // We only check the last few words
-            8
+        let stack = Stack {
+            buf,
+            painted: Cell::new(0),
         };
 
+        // If size is not `even` we do the full footprint test, eager allocation writes the
+        // footprint everywhere as well to commit the pages. Scrubbing on reuse relies on the
+        // measured usage, so it needs the full footprint too
+        let count =
+            if track || config().get_eager_stack_alloc() || config().get_zero_stack_on_reuse() {
+                stack.size()
+            } else {
+                // We only check the last few words
+                8
+            };
+
         unsafe {
             let buf = stack.buf.bottom as *mut usize;
 
             ptr::write_bytes(buf, 0xEE, count);
         }
 
+        stack.painted.set(count);
+
         // Initialize the box usage
         let offset = stack.get_offset();
 
@@ -56,8 +78,9 @@ impl Stack {
             ptr::write_bytes(&mut magic, 0xEE, 1);
 
             let mut ptr = self.buf.bottom as *mut usize;
+            let painted = self.painted.get();
 
-            while *ptr == magic {
+            while offset < painted && *ptr == magic {
                 offset += 1;
 
                 ptr = ptr.offset(1);
@@ -69,6 +92,21 @@ impl Stack {
         cap - offset
     }
 
+    /// Zero the used part of the stack so nothing is left for the next coroutine
+    /// The offset word is kept, and the footprint now ends where the zeroes begin
+    pub fn scrub_used(&self) {
+        let size = self.size();
+        let used = self.get_used_size();
+
+        unsafe {
+            let buf = (self.buf.bottom as *mut usize).add(size - used);
+
+            ptr::write_bytes(buf, 0, used.saturating_sub(1));
+        }
+
+        self.painted.set(size - used);
+    }
+
     /// Get the stack capacity
     #[inline]
     pub fn size(&self) -> usize {
@@ -87,21 +125,58 @@ impl Stack {
         self.buf.bottom as *mut _
     }
 
-    /// Get offset
+    /// Get offset, kept in the highest word of the stack
     fn get_offset(&self) -> *mut usize {
-        unsafe { (self.buf.top as *mut usize).offset(1) }
+        unsafe { (self.buf.top as *mut usize).offset(-1) }
     }
 
     /// Deallocate the stack
-    fn drop_stack(&self) {
+    pub(crate) fn drop_stack(&self) {
         if self.buf.len() == 0 {
             return;
         }
 
-        let page_size = unix::page_size();
-        let guard = (self.buf.bottom as usize - page_size) as *mut c_void;
-        let size_with_guard = self.buf.len() + page_size;
+        let guard_size = sys::guard_size();
+        let guard = (self.buf.bottom as usize - guard_size) as *mut c_void;
+        let size_with_guard = self.buf.len() + guard_size;
+
+        unsafe { sys::deallocate_stack(guard, size_with_guard) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrub_used_clears_sentinel() {
+        const SENTINEL: usize = 0x5EC2_E7AB;
+
+        // An odd size lays the footprint pattern over the whole stack
+        let stack = Stack::new(0x1001);
+        let size = stack.size();
+
+        // Pretend a coroutine used the 16 words below the offset, kept in the highest one
+        let used = unsafe { stack.begin().add(size - 17) };
+
+        for i in 0..16 {
+            unsafe { *used.add(i) = SENTINEL };
+        }
+
+        assert_eq!(stack.get_used_size(), 17);
+
+        let end = stack.end();
+
+        stack.scrub_used();
+
+        for i in 0..16 {
+            assert_eq!(unsafe { *used.add(i) }, 0);
+        }
+
+        // The offset survives, and the scan stops where the zeroes begin
+        assert_eq!(stack.end(), end);
+        assert_eq!(stack.get_used_size(), 17);
 
-        unsafe { unix::deallocate_stack(guard, size_with_guard) };
+        stack.drop_stack();
     }
 }
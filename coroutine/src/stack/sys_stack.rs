@@ -1,6 +1,6 @@
 use std::os::raw::c_void;
 
-use crate::stack::{stack_error::StackError, unix};
+use crate::stack::{stack_error::StackError, sys};
 
 /// Represents any kind of stack memory
 #[derive(Debug)]
@@ -39,16 +39,15 @@ impl SysStack {
     /// Returns the minimum stack size allowed by the current platform
     #[inline]
     pub fn min_size() -> usize {
-        unix::min_stack_size()
+        sys::min_stack_size()
     }
 
     /// Allocates a new stack of size: `size`
     pub(crate) fn allocate(mut size: usize, protected: bool) -> Result<SysStack, StackError> {
-        let page_size = unix::page_size();
-        let min_stack_size = unix::min_stack_size();
-        let max_stack_size = unix::max_stack_size();
-        let add_shift = i32::from(protected);
-        let add = page_size << add_shift;
+        let page_size = sys::page_size();
+        let min_stack_size = sys::min_stack_size();
+        let max_stack_size = sys::max_stack_size();
+        let add = page_size + if protected { sys::guard_size() } else { 0 };
 
         if size < min_stack_size {
             size = min_stack_size;
@@ -58,11 +57,11 @@ impl SysStack {
 
         if let Some(size) = size.checked_add(add) {
             if size <= max_stack_size {
-                let mut ret = unsafe { unix::allocate_stack(size) };
+                let mut ret = unsafe { sys::allocate_stack(size) };
 
                 if protected {
                     if let Ok(stack) = ret {
-                        ret = unsafe { unix::protect_stack(&stack) };
+                        ret = unsafe { sys::protect_stack(&stack) };
                     }
                 }
 
@@ -63,10 +63,7 @@ pub unsafe fn allocate_stack(size: usize) -> io::Result<SysStack> {
         if ptr == MAP_FAILED {
             Err(io::Error::last_os_error())
         } else {
-            Ok(SysStack::new(
-                (ptr as usize + size) as *mut c_void,
-                ptr as *mut c_void,
-            ))
+            Ok(SysStack::new((ptr as usize + size) as *mut c_void, ptr))
         }
     }
 }
@@ -115,6 +112,11 @@ pub fn page_size() -> usize {
     ret
 }
 
+/// Size of the region below a protected stack, a single page faulting on any access
+pub fn guard_size() -> usize {
+    page_size()
+}
+
 pub fn min_stack_size() -> usize {
     page_size()
 }
@@ -21,10 +21,11 @@ unsafe extern "C" fn signal_handler(signum: c_int, info: *mut siginfo_t, ctx: *m
     unsafe {
         let _ctx = &mut *ctx;
         let addr = (*info).si_addr() as usize;
-        let stack_guard = crate::guard::current();
+        let overflowed = crate::runtime::is_generator() && crate::guard::current().contains(&addr);
 
-        if !stack_guard.contains(&addr) {
-            println!("{}", Backtrace::force_capture());
+        if !overflowed {
+            // Not a panic, so the coroutine panic hook doesn't get to report it
+            eprintln!("{}", Backtrace::force_capture());
 
             // SIG_ACTION is available after we registered our handler
             let old_action = SIG_ACTION.lock().unwrap();
@@ -61,7 +62,7 @@ unsafe fn init() {
         let mut action: sigaction = mem::zeroed();
 
         action.sa_flags = SA_SIGINFO | SA_ONSTACK;
-        action.sa_sigaction = signal_handler as sighandler_t;
+        action.sa_sigaction = signal_handler as *const () as sighandler_t;
 
         let mut old_action = SIG_ACTION.lock().unwrap();
 
@@ -1,8 +1,6 @@
-use core::ffi::{c_int, c_uint, c_ulong, c_void};
-
-use crate::stack::{
-    Stack,
-    asm::{InitFn, align_down, mut_offset},
+use core::{
+    ffi::{c_int, c_uint, c_ulong, c_void},
+    ptr,
 };
 
 #[allow(non_camel_case_types)]
@@ -20,7 +18,7 @@ pub type greg_t = i64;
 
 pub const _SC_PAGESIZE: c_int = 30;
 
-pub const NULL: *mut c_void = 0 as *mut c_void;
+pub const NULL: *mut c_void = ptr::null_mut();
 
 pub const MAP_STACK: c_int = 0x020000;
 pub const MAP_PRIVATE: c_int = 0x0002;
@@ -40,12 +38,6 @@ pub const SIGBUS: c_int = 7;
 pub const SIGSEGV: c_int = 11;
 pub const SIG_UNBLOCK: c_int = 0x01;
 
-unsafe extern "sysv64" {
-    pub fn bootstrap_green_task();
-    pub fn prefetch(data: *const usize);
-    pub fn swap_registers(out_regs: *mut Register, in_regs: *const Register);
-}
-
 #[repr(C)]
 #[allow(non_camel_case_types)]
 pub struct rlimit {
@@ -153,61 +145,3 @@ impl siginfo_t {
         }
     }
 }
-
-#[repr(C)]
-#[derive(Debug)]
-pub struct Register {
-    gpr: [usize; 8],
-}
-
-impl Register {
-    pub fn new() -> Register {
-        Register { gpr: [0; 8] }
-    }
-
-    #[inline]
-    pub fn prefetch(&self) {
-        let ptr = self.gpr[1] as *const usize;
-
-        unsafe {
-            prefetch(ptr); // RSP
-            prefetch(ptr.add(8)); // RSP + 8
-        }
-    }
-}
-
-pub fn initialize_call_frame(
-    regs: &mut Register,
-    fptr: InitFn,
-    arg: usize,
-    arg2: *mut usize,
-    stack: &Stack,
-) {
-    // Redefinitions from runtime/arch/x86_64/regs.h
-    const RUSTRT_RSP: usize = 1;
-    const RUSTRT_RBP: usize = 2;
-    const RUSTRT_R12: usize = 4;
-    const RUSTRT_R13: usize = 5;
-    const RUSTRT_R14: usize = 6;
-
-    let sp = align_down(stack.end());
-
-    // These registers are frobbed by bootstrap_green_task into the right location so we can
-    // invoke the "real init function", `fptr`
-    regs.gpr[RUSTRT_R12] = arg;
-    regs.gpr[RUSTRT_R13] = arg2 as usize;
-    regs.gpr[RUSTRT_R14] = fptr as usize;
-
-    // Last base pointer on the stack should be 0
-    regs.gpr[RUSTRT_RBP] = 0;
-
-    // Setup the init stack
-    // This is prepared for the swap context
-    regs.gpr[RUSTRT_RSP] = mut_offset(sp, -2) as usize;
-
-    unsafe {
-        // Leave enough space for RET
-        *mut_offset(sp, -2) = bootstrap_green_task as usize;
-        *mut_offset(sp, -1) = 0;
-    }
-}
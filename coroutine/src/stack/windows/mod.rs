@@ -0,0 +1,177 @@
+use core::ffi::{c_int, c_long, c_ulong, c_void};
+use std::{
+    io,
+    mem::MaybeUninit,
+    ptr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::stack::sys_stack::SysStack;
+
+pub mod overflow;
+
+/// Committed space below the guard page, the exception dispatcher and the overflow handler run
+/// on it once the guard page is hit
+pub const STACK_GUARANTEE: usize = 0x10000;
+
+#[allow(non_camel_case_types)]
+pub type PVECTORED_EXCEPTION_HANDLER =
+    unsafe extern "system" fn(info: *mut EXCEPTION_POINTERS) -> c_long;
+
+pub const EXCEPTION_CONTINUE_EXECUTION: c_long = -1;
+pub const EXCEPTION_CONTINUE_SEARCH: c_long = 0;
+pub const EXCEPTION_MAXIMUM_PARAMETERS: usize = 15;
+
+pub const EXCEPTION_ACCESS_VIOLATION: u32 = 0xC000_0005;
+pub const EXCEPTION_GUARD_PAGE: u32 = 0x8000_0001;
+pub const EXCEPTION_STACK_OVERFLOW: u32 = 0xC000_00FD;
+
+#[repr(C)]
+#[allow(non_camel_case_types, non_snake_case)]
+pub struct EXCEPTION_RECORD {
+    pub ExceptionCode: u32,
+    pub ExceptionFlags: u32,
+    pub ExceptionRecord: *mut EXCEPTION_RECORD,
+    pub ExceptionAddress: *mut c_void,
+    pub NumberParameters: u32,
+    pub ExceptionInformation: [usize; EXCEPTION_MAXIMUM_PARAMETERS],
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types, non_snake_case)]
+pub struct EXCEPTION_POINTERS {
+    pub ExceptionRecord: *mut EXCEPTION_RECORD,
+    // Only the stack and instruction pointers are touched, so it is left opaque
+    pub ContextRecord: *mut c_void,
+}
+
+// Offsets of the stack and instruction pointers in the x86_64 `CONTEXT`
+pub const CONTEXT_RSP: usize = 0x98;
+pub const CONTEXT_RIP: usize = 0xF8;
+
+pub const MEM_COMMIT: u32 = 0x1000;
+pub const MEM_RESERVE: u32 = 0x2000;
+pub const MEM_RELEASE: u32 = 0x8000;
+
+pub const PAGE_NOACCESS: u32 = 0x01;
+pub const PAGE_READWRITE: u32 = 0x04;
+pub const PAGE_GUARD: u32 = 0x100;
+
+#[repr(C)]
+#[allow(non_camel_case_types, non_snake_case)]
+pub struct SYSTEM_INFO {
+    pub wProcessorArchitecture: u16,
+    pub wReserved: u16,
+    pub dwPageSize: u32,
+    pub lpMinimumApplicationAddress: *mut c_void,
+    pub lpMaximumApplicationAddress: *mut c_void,
+    pub dwActiveProcessorMask: usize,
+    pub dwNumberOfProcessors: u32,
+    pub dwProcessorType: u32,
+    pub dwAllocationGranularity: u32,
+    pub wProcessorLevel: u16,
+    pub wProcessorRevision: u16,
+}
+
+#[link(name = "kernel32")]
+unsafe extern "system" {
+    pub fn AddVectoredExceptionHandler(
+        first: c_ulong,
+        handler: PVECTORED_EXCEPTION_HANDLER,
+    ) -> *mut c_void;
+
+    fn VirtualAlloc(addr: *mut c_void, size: usize, alloc_type: u32, protect: u32) -> *mut c_void;
+
+    fn VirtualProtect(addr: *mut c_void, size: usize, protect: u32, old: *mut u32) -> c_int;
+
+    fn VirtualFree(addr: *mut c_void, size: usize, free_type: u32) -> c_int;
+
+    fn GetSystemInfo(info: *mut SYSTEM_INFO);
+}
+
+pub unsafe fn allocate_stack(size: usize) -> io::Result<SysStack> {
+    unsafe {
+        let ptr = VirtualAlloc(
+            ptr::null_mut(),
+            size,
+            MEM_COMMIT | MEM_RESERVE,
+            PAGE_READWRITE,
+        );
+
+        if ptr.is_null() {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(SysStack::new((ptr as usize + size) as *mut c_void, ptr))
+        }
+    }
+}
+
+// From the low end: a page faulting on any access, the guarantee space, and the guard page right
+// below the stack. Hitting the guard page clears its guard and raises an exception, which is
+// dispatched on the guarantee space
+pub unsafe fn protect_stack(stack: &SysStack) -> io::Result<SysStack> {
+    unsafe {
+        let page_size = page_size();
+
+        debug_assert!(stack.len() % page_size == 0 && stack.len() > guard_size());
+
+        let bottom = stack.bottom() as usize;
+        let guard_page = bottom + guard_size() - page_size;
+        let mut old = 0;
+
+        if VirtualProtect(stack.bottom(), page_size, PAGE_NOACCESS, &mut old) == 0
+            || VirtualProtect(
+                guard_page as *mut c_void,
+                page_size,
+                PAGE_READWRITE | PAGE_GUARD,
+                &mut old,
+            ) == 0
+        {
+            Err(io::Error::last_os_error())
+        } else {
+            let bottom = (bottom + guard_size()) as *mut c_void;
+
+            Ok(SysStack::new(stack.top(), bottom))
+        }
+    }
+}
+
+pub unsafe fn deallocate_stack(ptr: *mut c_void, _size: usize) {
+    unsafe {
+        VirtualFree(ptr, 0, MEM_RELEASE);
+    }
+}
+
+pub fn page_size() -> usize {
+    static PAGE_SIZE: AtomicUsize = AtomicUsize::new(0);
+
+    let mut ret = PAGE_SIZE.load(Ordering::Relaxed);
+
+    if ret == 0 {
+        let mut info = MaybeUninit::uninit();
+
+        unsafe {
+            GetSystemInfo(info.as_mut_ptr());
+
+            ret = info.assume_init().dwPageSize as usize;
+        }
+
+        PAGE_SIZE.store(ret, Ordering::Relaxed);
+    }
+
+    ret
+}
+
+/// Size of the region below a protected stack, the guard page and the guarantee space under it
+pub fn guard_size() -> usize {
+    page_size() * 2 + STACK_GUARANTEE
+}
+
+pub fn min_stack_size() -> usize {
+    page_size()
+}
+
+// There is no limit to query, the reservation is only bounded by the address space
+pub fn max_stack_size() -> usize {
+    usize::MAX
+}
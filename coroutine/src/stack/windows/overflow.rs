@@ -0,0 +1,121 @@
+use core::ffi::c_long;
+use std::{process, sync::Once, thread};
+
+use crate::{
+    error::Error,
+    runtime::{ContextStack, is_generator},
+    yield_now::raw_yield_now,
+};
+
+use super::{
+    AddVectoredExceptionHandler, CONTEXT_RIP, CONTEXT_RSP, EXCEPTION_ACCESS_VIOLATION,
+    EXCEPTION_CONTINUE_EXECUTION, EXCEPTION_CONTINUE_SEARCH, EXCEPTION_GUARD_PAGE,
+    EXCEPTION_POINTERS, EXCEPTION_STACK_OVERFLOW, guard_size, page_size,
+};
+
+// The handler runs on the guarantee space of the faulting stack, with the thread stopped at an
+// arbitrary instruction, maybe holding the allocator or stderr lock. So it only checks the
+// address and sends the thread on to `stack_overflow`, which does the rest once the dispatch is
+// over
+unsafe extern "system" fn exception_handler(info: *mut EXCEPTION_POINTERS) -> c_long {
+    unsafe {
+        let record = &*(*info).ExceptionRecord;
+
+        let faulting = matches!(
+            record.ExceptionCode,
+            EXCEPTION_ACCESS_VIOLATION | EXCEPTION_GUARD_PAGE | EXCEPTION_STACK_OVERFLOW
+        );
+
+        // The second parameter holds the address that could not be accessed
+        if !faulting || record.NumberParameters < 2 || !is_generator() {
+            return EXCEPTION_CONTINUE_SEARCH;
+        }
+
+        let addr = record.ExceptionInformation[1];
+        let stack_guard = crate::guard::current();
+
+        if !stack_guard.contains(&addr) {
+            // Not ours, let the next handler or the default crash reporting deal with it
+            return EXCEPTION_CONTINUE_SEARCH;
+        }
+
+        // Enter `stack_overflow` at the top of the guarantee space, aligned as if it was called
+        let sp = stack_guard.start + guard_size() - page_size() - 8;
+        let context = (*info).ContextRecord as *mut u8;
+
+        *(context.add(CONTEXT_RSP) as *mut usize) = sp;
+        *(context.add(CONTEXT_RIP) as *mut usize) = stack_overflow as *const () as usize;
+
+        EXCEPTION_CONTINUE_EXECUTION
+    }
+}
+
+// Still on the guarantee space, but out of the exception dispatch
+extern "sysv64" fn stack_overflow() -> ! {
+    eprintln!(
+        "\nCoroutine in thread '{}' has overflowed it's stack\n",
+        thread::current().name().unwrap_or("<unknown>")
+    );
+
+    let env = ContextStack::current();
+    let cur = env.top();
+
+    cur.err = Some(Box::new(Error::StackErr));
+
+    // Switch back to the parent for good, the coroutine is reported with the error set above
+    raw_yield_now(&env, cur);
+
+    process::abort();
+}
+
+#[cold]
+unsafe fn init() {
+    unsafe {
+        // Run before any frame based handler, the faulting stack can't unwind to them anyway
+        let handle = AddVectoredExceptionHandler(1, exception_handler);
+
+        assert!(
+            !handle.is_null(),
+            "Failed to install the stack overflow handler"
+        );
+    }
+}
+
+pub fn init_once() {
+    static INIT_ONCE: Once = Once::new();
+
+    INIT_ONCE.call_once(|| unsafe { init() });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hint::black_box;
+
+    use super::*;
+    use crate::{builder::CoroutineBuilder, error::Error};
+
+    fn recurse(depth: usize) -> usize {
+        let frame = black_box([depth; 64]);
+
+        // Never reached, the stack runs out long before
+        if depth == usize::MAX {
+            return 0;
+        }
+
+        recurse(depth + 1) + frame[0]
+    }
+
+    #[test]
+    fn test_stack_overflow_reports_stack_err() {
+        init_once();
+
+        let handle = CoroutineBuilder::new()
+            .stack_size(0x400)
+            .spawn(|| recurse(0))
+            .unwrap();
+
+        let err = handle.join().err().unwrap().into_panic();
+
+        assert_eq!(err.downcast_ref::<Error>(), Some(&Error::StackErr));
+    }
+}
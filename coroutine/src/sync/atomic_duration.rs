@@ -0,0 +1,38 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// An atomic `Option<Duration>` with nanosecond precision
+/// Stored as nanoseconds, 0 is `None`
+#[derive(Debug)]
+pub struct AtomicDuration(AtomicU64);
+
+impl AtomicDuration {
+    pub fn new(dur: Option<Duration>) -> AtomicDuration {
+        AtomicDuration(AtomicU64::new(encode(dur)))
+    }
+
+    #[inline]
+    pub fn store(&self, dur: Option<Duration>) {
+        self.0.store(encode(dur), Ordering::Release);
+    }
+
+    /// Take the duration out, leaving `None`
+    #[inline]
+    pub fn take(&self) -> Option<Duration> {
+        decode(self.0.swap(0, Ordering::AcqRel))
+    }
+}
+
+// A zero duration is kept as one nanosecond so it can't be mistaken for `None`, longer ones are
+// capped at about 584 years
+fn encode(dur: Option<Duration>) -> u64 {
+    dur.map_or(0, |dur| {
+        u64::try_from(dur.as_nanos()).unwrap_or(u64::MAX).max(1)
+    })
+}
+
+fn decode(nanos: u64) -> Option<Duration> {
+    (nanos != 0).then(|| Duration::from_nanos(nanos))
+}
@@ -1,4 +1,10 @@
-use crate::{is_coroutine, park::Park};
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    is_coroutine,
+    metrics::METRICS,
+    park::{Park, ParkError},
+};
 
 use super::{parker::Parker, thread_park::ThreadPark};
 
@@ -24,4 +30,29 @@ impl Blocker {
 
         Blocker { parker }
     }
+
+    /// Create a Blocker for the current coroutine or thread, to be shared with its waker
+    pub fn current() -> Arc<Blocker> {
+        Arc::new(Blocker::new(false))
+    }
+
+    /// Block the current coroutine or thread until `unpark` is called or `dur` expires
+    pub fn park(&self, dur: Option<Duration>) -> Result<(), ParkError> {
+        match &self.parker {
+            Parker::Coroutine(park) => {
+                let _parked = METRICS.parked();
+
+                park.park_timeout(dur)
+            }
+            Parker::Thread(thread_park) => thread_park.park_timeout(dur),
+        }
+    }
+
+    /// Wake up the blocked coroutine or thread, or make its next `park` return immediately
+    pub fn unpark(&self) {
+        match &self.parker {
+            Parker::Coroutine(park) => park.unpark(),
+            Parker::Thread(thread_park) => thread_park.unpark(),
+        }
+    }
 }
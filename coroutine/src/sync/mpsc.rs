@@ -0,0 +1,363 @@
+//! Multi-producer, single-consumer channel that parks the receiving coroutine instead of its
+//! worker thread
+
+use std::{
+    cell::Cell,
+    collections::VecDeque,
+    error::Error,
+    fmt,
+    marker::PhantomData,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+};
+
+use super::{AtomicOption, blocker::Blocker, select::Selectable};
+
+struct Inner<T> {
+    queue: Mutex<VecDeque<T>>,
+
+    /// The receiver waiting for an item or for the last sender to go away
+    to_wake: AtomicOption<Arc<Blocker>>,
+
+    /// Number of live senders, the channel is closed once it drops to zero
+    senders: AtomicUsize,
+
+    /// Cleared when the receiver is dropped, further sends hand the item back
+    receiver_alive: AtomicBool,
+}
+
+impl<T> Inner<T> {
+    fn pop(&self) -> Option<T> {
+        self.queue.lock().unwrap().pop_front()
+    }
+
+    fn wake(&self) {
+        if let Some(blocker) = self.to_wake.take() {
+            blocker.unpark();
+        }
+    }
+}
+
+/// Creates an unbounded channel, returning its sending and receiving halves
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let inner = Arc::new(Inner {
+        queue: Mutex::new(VecDeque::new()),
+        to_wake: AtomicOption::none(),
+        senders: AtomicUsize::new(1),
+        receiver_alive: AtomicBool::new(true),
+    });
+
+    (
+        Sender {
+            inner: inner.clone(),
+        },
+        Receiver {
+            inner,
+            _not_sync: PhantomData,
+        },
+    )
+}
+
+/// The sending half of a channel, clone it to get more producers
+pub struct Sender<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Queues `value` and wakes the receiver if it is parked
+    ///
+    /// Fails, handing `value` back, once the receiver has been dropped
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if !self.inner.receiver_alive.load(Ordering::Acquire) {
+            return Err(SendError(value));
+        }
+
+        self.inner.queue.lock().unwrap().push_back(value);
+        self.inner.wake();
+
+        Ok(())
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Sender<T> {
+        self.inner.senders.fetch_add(1, Ordering::Relaxed);
+
+        Sender {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        // The last sender closes the channel, the receiver must notice it
+        if self.inner.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.inner.wake();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Sender { .. }")
+    }
+}
+
+/// The receiving half of a channel
+pub struct Receiver<T> {
+    inner: Arc<Inner<T>>,
+
+    // Only one receiver may register itself in `to_wake` at a time
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+impl<T> Receiver<T> {
+    /// Takes the next item without blocking
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        if let Some(value) = self.inner.pop() {
+            return Ok(value);
+        }
+
+        if self.inner.senders.load(Ordering::Acquire) != 0 {
+            return Err(TryRecvError::Empty);
+        }
+
+        // The last send may have landed right before its sender was dropped
+        self.inner.pop().ok_or(TryRecvError::Disconnected)
+    }
+
+    /// Takes the next item, parking the current coroutine or thread until one arrives
+    ///
+    /// Fails once the channel is empty and every sender has been dropped, or if the coroutine
+    /// is cancelled while waiting in a region where cancellation is disabled
+    pub fn recv(&self) -> Result<T, RecvError> {
+        loop {
+            match self.try_recv() {
+                Ok(value) => return Ok(value),
+                Err(TryRecvError::Disconnected) => return Err(RecvError::Disconnected),
+                Err(TryRecvError::Empty) => {}
+            }
+
+            let current_blocker = Blocker::current();
+
+            // Register the blocker first
+            self.inner.to_wake.store(current_blocker.clone());
+
+            // Re-check the queue, a sender may have missed the registration
+            match self.try_recv() {
+                Err(TryRecvError::Empty) => {
+                    // Without a timeout only a cancel ends the park early
+                    if current_blocker.park(None).is_err() {
+                        self.inner.to_wake.take();
+
+                        return Err(RecvError::Cancelled);
+                    }
+                }
+                result => {
+                    self.inner.to_wake.take();
+
+                    return result.map_err(|_| RecvError::Disconnected);
+                }
+            }
+        }
+    }
+
+    /// Returns an iterator that blocks on `recv` until the channel is closed
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { receiver: self }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.inner.receiver_alive.store(false, Ordering::Release);
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Receiver { .. }")
+    }
+}
+
+impl<T> Selectable for Receiver<T> {
+    fn is_ready(&self) -> bool {
+        !self.inner.queue.lock().unwrap().is_empty()
+            || self.inner.senders.load(Ordering::Acquire) == 0
+    }
+
+    fn register(&self, blocker: &Arc<Blocker>) {
+        self.inner.to_wake.store(blocker.clone());
+    }
+
+    fn unregister(&self) {
+        self.inner.to_wake.take();
+    }
+}
+
+/// Blocking iterator over the items of a `Receiver`
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    receiver: &'a Receiver<T>,
+}
+
+impl<T> Iterator for Iter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.recv().ok()
+    }
+}
+
+/// Error returned by `Sender::send` when the receiver is gone, holding the unsent value
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SendError { .. }")
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("sending on a closed channel")
+    }
+}
+
+impl<T> Error for SendError<T> {}
+
+/// Error returned by `Receiver::recv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvError {
+    /// No item is queued and every sender has been dropped
+    Disconnected,
+    /// The coroutine was cancelled while waiting, with cancellation disabled
+    Cancelled,
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecvError::Disconnected => f.write_str("receiving on a closed channel"),
+            RecvError::Cancelled => f.write_str("receiving was cancelled"),
+        }
+    }
+}
+
+impl Error for RecvError {}
+
+/// Error returned by `Receiver::try_recv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// No item is queued yet
+    Empty,
+    /// No item is queued and every sender has been dropped
+    Disconnected,
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => f.write_str("receiving on an empty channel"),
+            TryRecvError::Disconnected => f.write_str("receiving on a closed channel"),
+        }
+    }
+}
+
+impl Error for TryRecvError {}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::*;
+    use crate::{current, spawn::spawn};
+
+    #[test]
+    fn test_producer_consumer_coroutines() {
+        let (tx, rx) = channel();
+
+        let producer = spawn(move || {
+            for i in 0..1000 {
+                tx.send(i).unwrap();
+            }
+        });
+
+        let consumer = spawn(move || {
+            let mut expected = 0;
+
+            while let Ok(i) = rx.recv() {
+                assert_eq!(i, expected);
+
+                expected += 1;
+            }
+
+            expected
+        });
+
+        producer.join().unwrap();
+
+        assert_eq!(consumer.join().unwrap(), 1000);
+    }
+
+    #[test]
+    fn test_try_recv_and_close() {
+        let (tx, rx) = channel();
+        let tx2 = tx.clone();
+
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+
+        tx.send(1).unwrap();
+        drop(tx);
+        tx2.send(2).unwrap();
+
+        assert_eq!(rx.try_recv(), Ok(1));
+
+        drop(tx2);
+
+        // Queued items are still delivered after the channel closes
+        assert_eq!(rx.recv(), Ok(2));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+        assert_eq!(rx.recv(), Err(RecvError::Disconnected));
+    }
+
+    #[test]
+    fn test_recv_returns_when_cancelled() {
+        let (tx, rx) = channel::<u32>();
+
+        let handle = spawn(move || {
+            let coroutine = current();
+            let cancel = coroutine.get_cancel();
+
+            // The cancel can't unwind the coroutine here, recv has to give up by itself
+            cancel.disable_cancel();
+
+            let result = rx.recv();
+
+            cancel.enable_cancel();
+
+            result
+        });
+
+        // Let the coroutine park first
+        thread::sleep(Duration::from_millis(10));
+        handle.cancel();
+
+        assert_eq!(handle.join().unwrap(), Err(RecvError::Cancelled));
+
+        drop(tx);
+    }
+
+    #[test]
+    fn test_send_after_receiver_dropped() {
+        let (tx, rx) = channel();
+
+        drop(rx);
+
+        assert_eq!(tx.send(5), Err(SendError(5)));
+    }
+}
@@ -0,0 +1,74 @@
+//! A wake-up flag that `select!` can wait on next to channels
+
+use std::{
+    fmt,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use super::{AtomicOption, blocker::Blocker, select::Selectable};
+
+/// An event source set by `unpark` from any coroutine or thread, waited on by a `park` arm
+///
+/// Unparks do not stack, several of them before the next `try_park` count as one
+pub struct ParkSource {
+    unparked: AtomicBool,
+
+    /// The selecting coroutine or thread waiting for the next unpark
+    to_wake: AtomicOption<Arc<Blocker>>,
+}
+
+impl ParkSource {
+    /// Creates a source with no pending unpark
+    pub fn new() -> ParkSource {
+        ParkSource {
+            unparked: AtomicBool::new(false),
+            to_wake: AtomicOption::none(),
+        }
+    }
+
+    /// Sets the pending unpark and wakes the coroutine or thread selecting on this source
+    pub fn unpark(&self) {
+        // Pairs with `register`, so either the flag or the registered blocker is seen
+        self.unparked.store(true, Ordering::SeqCst);
+
+        if let Some(blocker) = self.to_wake.take() {
+            blocker.unpark();
+        }
+    }
+
+    /// Consumes the pending unpark without blocking, returns false if there was none
+    pub fn try_park(&self) -> bool {
+        self.unparked.swap(false, Ordering::SeqCst)
+    }
+}
+
+impl Default for ParkSource {
+    fn default() -> Self {
+        ParkSource::new()
+    }
+}
+
+impl fmt::Debug for ParkSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParkSource")
+            .field("unparked", &self.unparked.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl Selectable for ParkSource {
+    fn is_ready(&self) -> bool {
+        self.unparked.load(Ordering::SeqCst)
+    }
+
+    fn register(&self, blocker: &Arc<Blocker>) {
+        self.to_wake.store(blocker.clone());
+    }
+
+    fn unregister(&self) {
+        self.to_wake.take();
+    }
+}
@@ -0,0 +1,299 @@
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    ops::{Deref, DerefMut},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use super::{Backoff, blocker::Blocker};
+
+/// Set while a writer holds the lock
+const WRITER: usize = 1;
+
+/// Added for each reader holding the lock
+const READER: usize = 2;
+
+/// Spin steps tried before parking, a lock is often released within them
+const SPIN_LIMIT: u32 = 6;
+
+/// A reader-writer lock that parks the current coroutine under contention instead of blocking
+/// its worker thread
+///
+/// Outside of a coroutine the calling thread is parked instead.
+pub struct RwLock<T: ?Sized> {
+    state: AtomicUsize,
+
+    /// The coroutines or threads waiting for the lock to be released
+    waiters: Mutex<Vec<Arc<Blocker>>>,
+
+    value: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for RwLock<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for RwLock<T> {}
+
+impl<T> RwLock<T> {
+    /// Create a new unlocked RwLock
+    pub const fn new(value: T) -> RwLock<T> {
+        RwLock {
+            state: AtomicUsize::new(0),
+            waiters: Mutex::new(Vec::new()),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consume the lock, returning the value
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> RwLock<T> {
+    /// Acquire shared access, parking while a writer holds the lock
+    pub fn read(&self) -> RwLockReadGuard<'_, T> {
+        self.acquire(|| self.try_lock_read());
+
+        RwLockReadGuard { lock: self }
+    }
+
+    /// Acquire exclusive access, parking while any reader or writer holds the lock
+    pub fn write(&self) -> RwLockWriteGuard<'_, T> {
+        self.acquire(|| self.try_lock_write());
+
+        RwLockWriteGuard { lock: self }
+    }
+
+    /// Acquire shared access if no writer holds the lock
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T>> {
+        self.try_lock_read()
+            .then_some(RwLockReadGuard { lock: self })
+    }
+
+    /// Acquire exclusive access if the lock is free
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T>> {
+        self.try_lock_write()
+            .then_some(RwLockWriteGuard { lock: self })
+    }
+
+    /// Get a mutable reference to the value, no locking is needed since it is borrowed mutably
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    fn try_lock_read(&self) -> bool {
+        let mut state = self.state.load(Ordering::Relaxed);
+
+        while state & WRITER == 0 {
+            match self.state.compare_exchange_weak(
+                state,
+                state + READER,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return true,
+                Err(current) => state = current,
+            }
+        }
+
+        false
+    }
+
+    fn try_lock_write(&self) -> bool {
+        self.state
+            .compare_exchange(0, WRITER, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    fn acquire(&self, try_lock: impl Fn() -> bool) {
+        loop {
+            let backoff = Backoff::with_limits(SPIN_LIMIT, SPIN_LIMIT);
+
+            while !backoff.is_completed() {
+                if try_lock() {
+                    return;
+                }
+
+                backoff.spin();
+            }
+
+            let current_blocker = Blocker::current();
+
+            // Register the blocker first
+            self.waiters.lock().unwrap().push(current_blocker.clone());
+
+            // Re-check the lock, it may have been released before the registration
+            if try_lock() {
+                self.waiters
+                    .lock()
+                    .unwrap()
+                    .retain(|blocker| !Arc::ptr_eq(blocker, &current_blocker));
+
+                return;
+            }
+
+            current_blocker.park(None).ok();
+        }
+    }
+
+    fn unlock_read(&self) {
+        if self.state.fetch_sub(READER, Ordering::Release) == READER {
+            self.wake_all();
+        }
+    }
+
+    fn unlock_write(&self) {
+        self.state.fetch_and(!WRITER, Ordering::Release);
+        self.wake_all();
+    }
+
+    // Everyone waiting retries, those that lose the race register again
+    fn wake_all(&self) {
+        let waiters = std::mem::take(&mut *self.waiters.lock().unwrap());
+
+        for blocker in waiters {
+            blocker.unpark();
+        }
+    }
+}
+
+impl<T: Default> Default for RwLock<T> {
+    fn default() -> RwLock<T> {
+        RwLock::new(T::default())
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("RwLock");
+
+        match self.try_read() {
+            Some(guard) => d.field("value", &&*guard),
+            None => d.field("value", &format_args!("<locked>")),
+        };
+
+        d.finish()
+    }
+}
+
+/// Shared access to the value of a `RwLock`, released when dropped
+pub struct RwLockReadGuard<'a, T: ?Sized> {
+    lock: &'a RwLock<T>,
+}
+
+impl<T: ?Sized> Deref for RwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockReadGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.unlock_read();
+    }
+}
+
+/// Exclusive access to the value of a `RwLock`, released when dropped
+pub struct RwLockWriteGuard<'a, T: ?Sized> {
+    lock: &'a RwLock<T>,
+}
+
+impl<T: ?Sized> Deref for RwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.unlock_write();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{spawn::spawn, yield_now::yield_now};
+
+    #[test]
+    fn test_try_read_and_try_write() {
+        let lock = RwLock::new(1);
+        let first = lock.try_read().unwrap();
+        let second = lock.try_read().unwrap();
+
+        assert!(lock.try_write().is_none());
+        assert_eq!(*first + *second, 2);
+
+        drop((first, second));
+
+        let mut writer = lock.try_write().unwrap();
+
+        *writer = 5;
+
+        assert!(lock.try_read().is_none());
+
+        drop(writer);
+
+        assert_eq!(lock.into_inner(), 5);
+    }
+
+    #[test]
+    fn test_readers_and_writer_exclusion() {
+        // The writer keeps both halves equal while holding the lock
+        let lock = Arc::new(RwLock::new((0u32, 0u32)));
+
+        let writer = {
+            let lock = lock.clone();
+
+            spawn(move || {
+                for _ in 0..100 {
+                    let mut pair = lock.write();
+
+                    pair.0 += 1;
+
+                    // Give readers a chance to observe a half done update
+                    yield_now();
+
+                    pair.1 += 1;
+                }
+            })
+        };
+
+        let readers = (0..4)
+            .map(|_| {
+                let lock = lock.clone();
+
+                spawn(move || {
+                    for _ in 0..100 {
+                        let pair = lock.read();
+
+                        assert_eq!(pair.0, pair.1);
+
+                        drop(pair);
+                        yield_now();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        writer.join().unwrap();
+
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        assert_eq!(*lock.read(), (100, 100));
+    }
+}
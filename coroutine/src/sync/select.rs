@@ -0,0 +1,266 @@
+//! Waiting on several event sources at once, see the `select!` macro
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::park::ParkError;
+
+use super::blocker::Blocker;
+
+/// An event source a coroutine can wait on together with others
+///
+/// Once `is_ready` returns true it must stay true until the owner consumes the event, so
+/// implementations are expected to have a single consumer
+pub trait Selectable {
+    /// Returns true if consuming the event would not block
+    fn is_ready(&self) -> bool;
+
+    /// Registers `blocker` to be unparked once the source becomes ready
+    fn register(&self, blocker: &Arc<Blocker>);
+
+    /// Removes the blocker set by `register`, if it has not been woken yet
+    fn unregister(&self);
+}
+
+impl<S: Selectable + ?Sized> Selectable for &S {
+    fn is_ready(&self) -> bool {
+        (**self).is_ready()
+    }
+
+    fn register(&self, blocker: &Arc<Blocker>) {
+        (**self).register(blocker)
+    }
+
+    fn unregister(&self) {
+        (**self).unregister()
+    }
+}
+
+impl<S: Selectable + ?Sized> Selectable for Arc<S> {
+    fn is_ready(&self) -> bool {
+        (**self).is_ready()
+    }
+
+    fn register(&self, blocker: &Arc<Blocker>) {
+        (**self).register(blocker)
+    }
+
+    fn unregister(&self) {
+        (**self).unregister()
+    }
+}
+
+/// Parks until one of `sources` is ready and returns its index, the first ready source wins
+///
+/// Returns `None` if `timeout` expires first
+pub fn select(sources: &[&dyn Selectable], timeout: Option<Duration>) -> Option<usize> {
+    let ready = || sources.iter().position(|source| source.is_ready());
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+    loop {
+        if let Some(index) = ready() {
+            return Some(index);
+        }
+
+        let current_blocker = Blocker::current();
+
+        // Register the blocker first
+        for source in sources {
+            source.register(&current_blocker);
+        }
+
+        // Re-check the sources, one may have become ready before the registration
+        let index = ready();
+        let result = match index {
+            None => current_blocker
+                .park(deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()))),
+            Some(_) => Ok(()),
+        };
+
+        // Deregister from the losers
+        for source in sources {
+            source.unregister();
+        }
+
+        match (index, result) {
+            (Some(_), _) => return index,
+            (None, Err(ParkError::Timeout)) => return ready(),
+            _ => {}
+        }
+    }
+}
+
+/// Waits on several channels and park sources, running the arm of the first one that is ready
+///
+/// Each `recv(rx) -> msg => body` arm binds the result of `rx.recv()` to `msg`, which is `Err`
+/// once the channel is closed. Each `park(source) => body` arm consumes the pending unpark of a
+/// `ParkSource`. An optional last `timeout(dur) => body` arm runs if nothing is ready within
+/// `dur`. Arms are checked in order, so an earlier ready arm wins.
+///
+/// ```ignore
+/// select! {
+///     recv(rx1) -> msg => println!("rx1: {msg:?}"),
+///     recv(rx2) -> msg => println!("rx2: {msg:?}"),
+///     park(source) => println!("unparked"),
+///     timeout(Duration::from_secs(1)) => println!("timed out"),
+/// }
+/// ```
+#[macro_export]
+macro_rules! select {
+    // Collects the arms one at a time, each with the operation that consumes its event
+    (@parse [$($arms:tt)*] recv($rx:expr) -> $msg:pat => $body:expr $(, $($rest:tt)*)?) => {
+        $crate::select!(@parse [$($arms)* ($rx, (recv $msg), $body)] $($($rest)*)?)
+    };
+
+    (@parse [$($arms:tt)*] park($source:expr) => $body:expr $(, $($rest:tt)*)?) => {
+        $crate::select!(@parse [$($arms)* ($source, (park), $body)] $($($rest)*)?)
+    };
+
+    (@parse [$($arms:tt)+] timeout($dur:expr) => $else:expr $(,)?) => {
+        $crate::select!(
+            @bind [] [$($arms)+]
+            (::std::option::Option::Some($dur))
+            ($else)
+        )
+    };
+
+    (@parse [$($arms:tt)+]) => {
+        $crate::select!(
+            @bind [] [$($arms)+]
+            (::std::option::Option::None)
+            (::std::unreachable!("select without a timeout returned no ready arm"))
+        )
+    };
+
+    // Binds each source to its own hygienic `rx` so its expression is only evaluated once
+    (@bind [$($bound:tt)*] [($rx:expr, $op:tt, $body:expr) $($rest:tt)*] $timeout:tt $else:tt) => {
+        match &$rx {
+            rx => $crate::select!(@bind [$($bound)* (rx, $op, $body)] [$($rest)*] $timeout $else),
+        }
+    };
+
+    (@bind [$(($rx:ident, $op:tt, $body:expr))+] [] ($timeout:expr) ($else:expr)) => {{
+        let ready = $crate::sync::select::select(
+            &[$($rx as &dyn $crate::sync::select::Selectable),+],
+            $timeout,
+        );
+
+        $crate::select!(@arms ready, 0usize, [$(($rx, $op, $body))+] $else)
+    }};
+
+    (@arms $ready:ident, $i:expr, [($rx:ident, $op:tt, $body:expr) $($rest:tt)*] $else:expr) => {
+        if $ready == ::std::option::Option::Some($i) {
+            $crate::select!(@consume $rx $op $body)
+        } else {
+            $crate::select!(@arms $ready, $i + 1, [$($rest)*] $else)
+        }
+    };
+
+    (@arms $ready:ident, $i:expr, [] $else:expr) => {
+        $else
+    };
+
+    // The ready source never blocks in `recv`
+    (@consume $rx:ident (recv $msg:pat) $body:expr) => {{
+        let $msg = $rx.recv();
+
+        $body
+    }};
+
+    (@consume $source:ident (park) $body:expr) => {{
+        $source.try_park();
+
+        $body
+    }};
+
+    ($($arms:tt)+) => {
+        $crate::select!(@parse [] $($arms)+)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        spawn::spawn,
+        sync::{ParkSource, mpsc::channel},
+        yield_now::yield_now,
+    };
+
+    #[test]
+    fn test_select_runs_the_ready_arm() {
+        let (_tx1, rx1) = channel::<u32>();
+        let (tx2, rx2) = channel();
+
+        let producer = spawn(move || {
+            // Let the selecting coroutine park first
+            for _ in 0..10 {
+                yield_now();
+            }
+
+            tx2.send("two").unwrap();
+        });
+
+        let selector = spawn(move || {
+            crate::select! {
+                recv(rx1) -> msg => panic!("rx1 is never sent to, got {msg:?}"),
+                recv(rx2) -> msg => msg.unwrap(),
+            }
+        });
+
+        producer.join().unwrap();
+
+        assert_eq!(selector.join().unwrap(), "two");
+    }
+
+    #[test]
+    fn test_select_runs_the_park_arm() {
+        let (_tx, rx) = channel::<u32>();
+        let source = Arc::new(ParkSource::new());
+
+        let unparker = {
+            let source = source.clone();
+
+            spawn(move || {
+                // Let the selecting coroutine park first
+                for _ in 0..10 {
+                    yield_now();
+                }
+
+                source.unpark();
+            })
+        };
+
+        let selector = {
+            let source = source.clone();
+
+            spawn(move || {
+                crate::select! {
+                    recv(rx) -> msg => panic!("rx is never sent to, got {msg:?}"),
+                    park(source) => "parked",
+                }
+            })
+        };
+
+        unparker.join().unwrap();
+
+        assert_eq!(selector.join().unwrap(), "parked");
+
+        // The arm consumed the unpark
+        assert!(!source.try_park());
+    }
+
+    #[test]
+    fn test_select_timeout() {
+        let (_tx, rx) = channel::<u32>();
+
+        let selected = crate::select! {
+            recv(rx) -> _msg => false,
+            timeout(Duration::from_millis(10)) => true,
+        };
+
+        assert!(selected);
+    }
+}
@@ -26,10 +26,12 @@ impl ThreadPark {
         while *guard == 0 && result.is_ok() {
             match dur {
                 None => {
-                    self.cvar.wait(guard);
+                    guard = self.cvar.wait(guard).unwrap();
                 }
                 Some(d) => {
-                    let t = self.cvar.wait_timeout(guard, d).unwrap().1;
+                    let (next, t) = self.cvar.wait_timeout(guard, d).unwrap();
+
+                    guard = next;
 
                     if t.timed_out() {
                         result = Err(ParkError::Timeout);
@@ -0,0 +1,192 @@
+use std::{
+    fmt,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use super::blocker::Blocker;
+
+struct Inner {
+    count: AtomicUsize,
+
+    /// The coroutines or threads waiting for the count to reach zero
+    waiters: Mutex<Vec<Arc<Blocker>>>,
+}
+
+/// Waits for a group of coroutines to finish
+///
+/// Each task is registered with `add` and reports back with `done`, while `wait` parks until
+/// the count of outstanding tasks drops to zero. Clones share the same count.
+#[derive(Clone)]
+pub struct WaitGroup {
+    inner: Arc<Inner>,
+}
+
+impl WaitGroup {
+    /// Creates a WaitGroup with no outstanding tasks
+    pub fn new() -> WaitGroup {
+        WaitGroup {
+            inner: Arc::new(Inner {
+                count: AtomicUsize::new(0),
+                waiters: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Registers `n` more outstanding tasks
+    pub fn add(&self, n: usize) {
+        self.inner.count.fetch_add(n, Ordering::AcqRel);
+    }
+
+    /// Marks one task as finished, waking every waiter once none are left
+    ///
+    /// # Panics
+    ///
+    /// Panics if called more times than tasks were added
+    pub fn done(&self) {
+        let mut count = self.inner.count.load(Ordering::Acquire);
+
+        // Checked before decrementing, so a misuse leaves the count at zero instead of wrapping
+        loop {
+            assert!(count != 0, "WaitGroup::done called more times than add");
+
+            match self.inner.count.compare_exchange_weak(
+                count,
+                count - 1,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => break,
+                Err(current) => count = current,
+            }
+        }
+
+        if count == 1 {
+            for blocker in self.inner.waiters.lock().unwrap().drain(..) {
+                blocker.unpark();
+            }
+        }
+    }
+
+    /// Parks the current coroutine or thread until the count reaches zero
+    pub fn wait(&self) {
+        while self.inner.count.load(Ordering::Acquire) != 0 {
+            let current_blocker = Blocker::current();
+
+            // Register the blocker first
+            self.inner
+                .waiters
+                .lock()
+                .unwrap()
+                .push(current_blocker.clone());
+
+            // Re-check the count, the last `done` may have missed the registration
+            if self.inner.count.load(Ordering::Acquire) == 0 {
+                self.inner
+                    .waiters
+                    .lock()
+                    .unwrap()
+                    .retain(|blocker| !Arc::ptr_eq(blocker, &current_blocker));
+
+                return;
+            }
+
+            current_blocker.park(None).ok();
+        }
+    }
+
+    /// Returns the number of outstanding tasks
+    pub fn count(&self) -> usize {
+        self.inner.count.load(Ordering::Acquire)
+    }
+}
+
+impl Default for WaitGroup {
+    fn default() -> WaitGroup {
+        WaitGroup::new()
+    }
+}
+
+impl fmt::Debug for WaitGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitGroup")
+            .field("count", &self.count())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{spawn::spawn, yield_now::yield_now};
+
+    #[test]
+    fn test_fan_out_fan_in() {
+        let wait_group = WaitGroup::new();
+        let finished = Arc::new(AtomicUsize::new(0));
+        let wakeups = Arc::new(AtomicUsize::new(0));
+
+        wait_group.add(50);
+
+        let waiter = {
+            let wait_group = wait_group.clone();
+            let finished = finished.clone();
+            let wakeups = wakeups.clone();
+
+            spawn(move || {
+                wait_group.wait();
+
+                wakeups.fetch_add(1, Ordering::SeqCst);
+
+                finished.load(Ordering::SeqCst)
+            })
+        };
+
+        for i in 0..50 {
+            let wait_group = wait_group.clone();
+            let finished = finished.clone();
+
+            spawn(move || {
+                for _ in 0..(i % 5) {
+                    yield_now();
+                }
+
+                finished.fetch_add(1, Ordering::SeqCst);
+                wait_group.done();
+            });
+        }
+
+        assert_eq!(waiter.join().unwrap(), 50);
+        assert_eq!(wakeups.load(Ordering::SeqCst), 1);
+        assert_eq!(wait_group.count(), 0);
+
+        // Nothing is outstanding anymore
+        wait_group.wait();
+    }
+
+    #[test]
+    #[should_panic(expected = "more times than add")]
+    fn test_done_without_add() {
+        WaitGroup::new().done();
+    }
+
+    #[test]
+    fn test_extra_done_keeps_count_at_zero() {
+        let wait_group = WaitGroup::new();
+
+        wait_group.add(1);
+        wait_group.done();
+
+        let extra = std::panic::catch_unwind(|| wait_group.done());
+
+        assert!(extra.is_err());
+        assert_eq!(wait_group.count(), 0);
+
+        // Still usable after the misuse
+        wait_group.add(1);
+        wait_group.done();
+        wait_group.wait();
+    }
+}
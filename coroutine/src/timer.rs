@@ -0,0 +1,175 @@
+//! Timer
+//! A thread rescheduling the coroutines whose timeout expired
+
+use std::{
+    collections::BTreeMap,
+    io,
+    sync::{Arc, Condvar, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{CoroutineImpl, scheduler::get_scheduler, set_coroutine_parameter, sync::AtomicOption};
+
+/// A suspended coroutine shared with the timer, whoever takes it out first reschedules it
+pub(crate) type TimerEntry = Arc<AtomicOption<CoroutineImpl>>;
+
+/// Handle to a pending timeout, see `Timer::remove`
+#[derive(Debug)]
+pub(crate) struct TimeoutHandle {
+    deadline: Instant,
+    seq: u64,
+}
+
+struct Entries {
+    /// Pending timeouts, ordered by deadline and then by insertion
+    queue: BTreeMap<(Instant, u64), TimerEntry>,
+
+    /// Sequence number of the next timeout, keeps equal deadlines apart
+    next_seq: u64,
+
+    /// Set by `stop`, the timer thread exits
+    stopping: bool,
+}
+
+pub(crate) struct Timer {
+    entries: Mutex<Entries>,
+
+    /// Signaled when a timeout earlier than all the others is added
+    changed: Condvar,
+
+    /// The timer thread, started by the first timeout
+    thread: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+impl Timer {
+    pub const fn new() -> Timer {
+        Timer {
+            entries: Mutex::new(Entries {
+                queue: BTreeMap::new(),
+                next_seq: 0,
+                stopping: false,
+            }),
+            changed: Condvar::new(),
+            thread: Mutex::new(None),
+        }
+    }
+
+    /// Reschedule the coroutine in `entry` once `dur` has elapsed, unless it is taken out first
+    /// The coroutine gets an `io::ErrorKind::TimedOut` error as parameter
+    pub fn add(&'static self, dur: Duration, entry: TimerEntry) -> TimeoutHandle {
+        let now = Instant::now();
+
+        // Durations too long for an `Instant` are as good as forever
+        let deadline = now
+            .checked_add(dur)
+            .unwrap_or_else(|| now + Duration::from_secs(100 * 365 * 24 * 3600));
+
+        let mut entries = self.entries.lock().unwrap();
+        let seq = entries.next_seq;
+        let first = entries
+            .queue
+            .first_key_value()
+            .is_none_or(|(&key, _)| (deadline, seq) < key);
+
+        entries.next_seq += 1;
+        entries.queue.insert((deadline, seq), entry);
+
+        drop(entries);
+
+        if first {
+            self.changed.notify_one();
+        }
+
+        self.start();
+
+        TimeoutHandle { deadline, seq }
+    }
+
+    /// Drop a pending timeout, the timer won't take the coroutine out once this returns
+    pub fn remove(&self, handle: TimeoutHandle) {
+        let mut entries = self.entries.lock().unwrap();
+
+        entries.queue.remove(&(handle.deadline, handle.seq));
+    }
+
+    /// Stop the timer thread, the next timeout added starts it again
+    /// Pending timeouts are kept, they fire once it is started again
+    pub fn stop(&self) {
+        // Held until the thread is gone, so `start` can't race with stopping
+        let mut thread = self.thread.lock().unwrap();
+
+        let Some(handle) = thread.take() else {
+            return;
+        };
+
+        self.entries.lock().unwrap().stopping = true;
+        self.changed.notify_one();
+
+        handle.join().expect("The timer thread panicked");
+
+        self.entries.lock().unwrap().stopping = false;
+    }
+
+    fn start(&'static self) {
+        let mut thread = self.thread.lock().unwrap();
+
+        if thread.is_none() {
+            let handle = thread::Builder::new()
+                .name("coroutine-timer".into())
+                .spawn(move || self.run())
+                .expect("Failed to spawn the timer thread");
+
+            *thread = Some(handle);
+        }
+    }
+
+    fn run(&self) {
+        let mut entries = self.entries.lock().unwrap();
+
+        while !entries.stopping {
+            let now = Instant::now();
+            let mut expired = Vec::new();
+
+            // Taken out while holding the lock, so `remove` never races with firing
+            while let Some(entry) = entries.queue.first_entry() {
+                if entry.key().0 > now {
+                    break;
+                }
+
+                if let Some(coroutine) = entry.remove().take() {
+                    expired.push(coroutine);
+                }
+            }
+
+            if !expired.is_empty() {
+                drop(entries);
+
+                for mut coroutine in expired {
+                    let timeout = io::Error::new(io::ErrorKind::TimedOut, "Timeout");
+
+                    set_coroutine_parameter(&mut coroutine, timeout);
+                    get_scheduler().schedule(coroutine);
+                }
+
+                entries = self.entries.lock().unwrap();
+
+                continue;
+            }
+
+            let next = entries
+                .queue
+                .first_key_value()
+                .map(|(&(deadline, _), _)| deadline);
+
+            entries = match next {
+                Some(deadline) => {
+                    let (entries, _) = self.changed.wait_timeout(entries, deadline - now).unwrap();
+
+                    entries
+                }
+                None => self.changed.wait(entries).unwrap(),
+            };
+        }
+    }
+}
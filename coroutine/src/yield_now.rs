@@ -1,11 +1,18 @@
 //! Yield
 //! Generator yield implementation
-use std::any::Any;
-use std::sync::atomic;
+use std::{any::Any, io, thread};
 
 use crate::{
-    event::EventResult,
+    CoroutineImpl,
+    cancel::Cancel,
+    coroutine_local::CoroutineLocal,
+    error::Error,
+    event::{EventResult, EventSource, EventSubscriber},
+    is_coroutine,
+    register_context::RegisterContext,
     runtime::{Context, ContextStack, is_generator},
+    scheduler::get_scheduler,
+    unlikely::unlikely,
 };
 
 /// This is a special return instruction that yield nothing but terminates the generator safely
@@ -22,22 +29,82 @@ macro_rules! done {
 pub fn done<T>() -> T {
     assert!(is_generator(), "done is only possible in a generator");
 
-    std::panic::panic_any(crate::error::Error::Done);
+    std::panic::panic_any(Error::Done);
 }
 
-/// Switch back to parent context
+/// Event putting the coroutine straight back on the ready queue, behind its peers
+struct Yield;
+
+impl EventSource for Yield {
+    fn subscribe(&mut self, coroutine_impl: CoroutineImpl) {
+        // The worker's own queue comes first, the coroutine would be picked again right away
+        get_scheduler().schedule_global(coroutine_impl);
+    }
+}
+
+/// Let the other ready coroutines run before the current one goes on
+/// Outside of a coroutine the thread yields to the OS instead
 #[inline]
 pub fn yield_now() {
+    if is_coroutine() {
+        yield_with_event(&Yield);
+    } else {
+        thread::yield_now();
+    }
+}
+
+/// Suspend the current coroutine until `resource` reschedules it
+///
+/// The coroutine is handed to `EventSource::subscribe` once it is off its stack, and
+/// `EventSource::yield_back` runs once it is resumed. Panics with `Error::ContextErr` if the
+/// running context is not a coroutine, e.g. a generator nested in it
+pub(crate) fn yield_with_event<T: EventSource + 'static>(resource: &T) {
     let env = ContextStack::current();
     let cur = env.top();
-    raw_yield_now(&env, cur);
+
+    if cur.local_data.is_null() {
+        std::panic::panic_any(Error::ContextErr);
+    }
+
+    // The local storage lives as long as the coroutine, which outlives this call
+    let local = unsafe { &*(cur.local_data as *const CoroutineLocal) };
+    let cancel = unsafe { &*(local.get_coroutine().get_cancel() as *const Cancel) };
+
+    // Already cancelled, don't wait for an event that may never come
+    if unlikely(cancel.is_cancelled()) {
+        cur.coroutine_set_para(io::Error::other("Cancelled"));
+
+        return resource.yield_back(cancel);
+    }
+
+    let resource = resource as &dyn EventSource as *const dyn EventSource;
+
+    cur.coroutine_set_ret(EventSubscriber::new(resource as *mut dyn EventSource));
+
+    yield_context(&env, cur);
+
+    unsafe { (*resource).yield_back(cancel) };
+}
+
+/// Switch back to the parent of `cur`, which must be the top context, once its value is set
+/// Unwinds with `Error::Cancel` if the generator is dropped instead of resumed
+#[inline]
+pub(crate) fn yield_context(env: &ContextStack, cur: &mut Context) {
+    cur._ref -= 1;
+
+    raw_yield_now(env, cur);
+
+    // Resumed without a matching `resume`, the generator is being dropped
+    if cur._ref != 1 {
+        std::panic::panic_any(Error::Cancel);
+    }
 }
 
 #[inline]
 pub fn raw_yield_now(env: &ContextStack, cur: &mut Context) {
     let parent = env.pop_context(cur as *mut _);
 
-    RegContext::swap(&mut cur.regs, &parent.regs);
+    RegisterContext::swap(&mut cur.regs, &parent.regs);
 }
 
 #[inline]
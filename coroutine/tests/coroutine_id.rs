@@ -0,0 +1,34 @@
+//! Reads coroutine ids through the public API only
+
+use coroutine::{JoinHandle, current, current_id, spawn};
+
+#[test]
+fn test_current_id_matches_handle() {
+    let handles = (0..2)
+        .map(|_| {
+            spawn(|| {
+                let id = current().id();
+
+                assert_eq!(current_id(), Some(id));
+
+                id
+            })
+        })
+        .collect::<Vec<_>>();
+    let ids = handles.iter().map(JoinHandle::id).collect::<Vec<_>>();
+
+    assert_ne!(ids[0], ids[1]);
+
+    for (handle, id) in handles.into_iter().zip(ids) {
+        assert_eq!(handle.coroutine().id(), id);
+        assert_eq!(handle.join().unwrap(), id);
+    }
+
+    assert_eq!(current_id(), None);
+}
+
+#[test]
+#[should_panic(expected = "no current coroutine")]
+fn test_current_outside_coroutine() {
+    current();
+}
@@ -0,0 +1,92 @@
+//! Runs in its own process on a single worker, so every turn counted here is one the worker ran
+
+use std::sync::{
+    Arc, Mutex, MutexGuard,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use coroutine::{config, spawn, sync::mpsc::channel, yield_now};
+
+/// Coroutines that each need a single turn
+const OTHERS: usize = 8;
+
+/// Keeps the tests from sharing the worker, which would skew the turns they count
+fn serial() -> MutexGuard<'static, ()> {
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    config().set_workers(1);
+
+    SERIAL.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+#[test]
+fn test_yielding_coroutine_does_not_starve_others() {
+    let _serial = serial();
+    let done = Arc::new(AtomicUsize::new(0));
+
+    let looper = {
+        let done = done.clone();
+
+        spawn(move || {
+            for _ in 0..OTHERS {
+                let done = done.clone();
+
+                spawn(move || done.fetch_add(1, Ordering::SeqCst));
+            }
+
+            let mut turns = 0;
+
+            while done.load(Ordering::SeqCst) < OTHERS {
+                turns += 1;
+
+                yield_now();
+            }
+
+            turns
+        })
+    };
+
+    // Yielding puts it behind all the others, they are done by the time it runs again
+    assert_eq!(looper.join().unwrap(), 1);
+}
+
+#[test]
+fn test_woken_coroutines_do_not_starve_spawned_ones() {
+    let _serial = serial();
+    let (ping_tx, ping_rx) = channel();
+    let (pong_tx, pong_rx) = channel();
+
+    // Each message wakes the other side on the worker's own queue, which is never empty
+    let ponger = spawn(move || {
+        while ping_rx.recv().is_ok() {
+            pong_tx.send(()).unwrap();
+        }
+    });
+
+    let pinger = spawn(move || {
+        let rounds = Arc::new(AtomicUsize::new(0));
+
+        // Queued on the global queue
+        let spawned = {
+            let rounds = rounds.clone();
+
+            spawn(move || rounds.load(Ordering::SeqCst))
+        };
+
+        while !spawned.is_finished() {
+            ping_tx.send(()).unwrap();
+            pong_rx.recv().unwrap();
+
+            rounds.fetch_add(1, Ordering::SeqCst);
+        }
+
+        spawned.join().unwrap()
+    });
+
+    let rounds = pinger.join().unwrap();
+
+    ponger.join().unwrap();
+
+    // The worker takes from the global queue at least every 16 turns, a round takes two
+    assert!(rounds <= 8, "spawned coroutine waited {rounds} rounds");
+}
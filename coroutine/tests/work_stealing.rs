@@ -0,0 +1,51 @@
+//! Runs in its own process, so the scheduler is started with the workers set here
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use coroutine::{CoroutineBuilder, config, metrics};
+
+#[test]
+fn test_idle_workers_steal() {
+    const N: usize = 200;
+
+    config().set_workers(4);
+
+    // Coroutines run per worker thread
+    let ran = Arc::new(Mutex::new(HashMap::<String, usize>::new()));
+
+    // All of them are queued on the first worker, the others only get some by stealing
+    let handles = (0..N)
+        .map(|i| {
+            let ran = ran.clone();
+
+            CoroutineBuilder::new()
+                .id(0)
+                .spawn(move || {
+                    let worker = thread::current().name().unwrap().to_owned();
+
+                    *ran.lock().unwrap().entry(worker).or_default() += 1;
+
+                    // Hold the worker for a bit, so its queue is still full when the others look
+                    thread::sleep(Duration::from_micros(100));
+
+                    i
+                })
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    for (i, handle) in handles.into_iter().enumerate() {
+        assert_eq!(handle.join().unwrap(), i);
+    }
+
+    let ran = ran.lock().unwrap();
+
+    assert_eq!(ran.values().sum::<usize>(), N);
+    assert!(ran.len() > 1, "only one worker ran coroutines: {ran:?}");
+    assert!(metrics().steals > 0);
+}
@@ -1,10 +1,13 @@
 use crate::date_time_error::DateTimeError;
 use std::{
-    fmt::Display,
+    fmt::{Debug, Display},
     str::FromStr,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+/// Seconds from the epoch to the start of year 10000
+const MAX_SECS: u64 = 253402300800;
+
 #[derive(PartialEq, Clone, Copy, Eq)]
 pub struct DateTime {
     /// Seconds: 0..59
@@ -25,6 +28,11 @@ pub struct DateTime {
 
 impl DateTime {
     fn is_valid(&self) -> bool {
+        self.in_range() && &DateTime::from(SystemTime::from(*self)) == self
+    }
+
+    /// Every field is within its range, the day may still not exist in that month
+    fn in_range(&self) -> bool {
         self.sec_c < 60
             && self.min_c < 60
             && self.hr_c < 24
@@ -34,7 +42,122 @@ impl DateTime {
             && self.mon_c <= 12
             && self.year_c >= 1970
             && self.year_c <= 9999
-            && &DateTime::from(SystemTime::from(*self)) == self
+    }
+
+    /// Returns `self` moved forward by `d`, or `None` past the end of year 9999
+    pub fn checked_add(&self, d: Duration) -> Option<DateTime> {
+        SystemTime::from(*self)
+            .checked_add(d)
+            .and_then(DateTime::from_system_time)
+    }
+
+    /// Returns `self` moved back by `d`, or `None` before 1970
+    pub fn checked_sub(&self, d: Duration) -> Option<DateTime> {
+        SystemTime::from(*self)
+            .checked_sub(d)
+            .and_then(DateTime::from_system_time)
+    }
+
+    /// Returns the time between `earlier` and `self`, or `None` if `earlier` is later
+    pub fn duration_since(&self, earlier: &DateTime) -> Option<Duration> {
+        SystemTime::from(*self)
+            .duration_since(SystemTime::from(*earlier))
+            .ok()
+    }
+
+    /// Same as `From<SystemTime>`, returning `None` instead of panicking outside 1970..=9999
+    fn from_system_time(sys_t: SystemTime) -> Option<DateTime> {
+        let secs = sys_t.duration_since(UNIX_EPOCH).ok()?.as_secs();
+
+        (secs < MAX_SECS).then(|| DateTime::from(sys_t))
+    }
+
+    /// Returns the year, 1970..=9999
+    pub fn year(&self) -> u16 {
+        self.year_c
+    }
+
+    /// Returns the month as a number, 1..=12
+    pub fn month_number(&self) -> u8 {
+        self.mon_c
+    }
+
+    /// Returns the day of the month, 1..=31
+    pub fn day(&self) -> u8 {
+        self.day_c
+    }
+
+    /// Returns the hour, 0..=23
+    pub fn hour(&self) -> u8 {
+        self.hr_c
+    }
+
+    /// Returns the minute, 0..=59
+    pub fn minute(&self) -> u8 {
+        self.min_c
+    }
+
+    /// Returns the second, 0..=59
+    pub fn second(&self) -> u8 {
+        self.sec_c
+    }
+
+    /// Returns the day of the week
+    pub fn weekday(&self) -> Weekday {
+        Weekday::from_u8(self.wday_c).expect("DateTime Out Of Bounds")
+    }
+
+    /// Returns the month of the year
+    pub fn month(&self) -> Month {
+        Month::from_u8(self.mon_c).expect("DateTime Out Of Bounds")
+    }
+
+    /// Parses an RFC 3339 timestamp in UTC, such as `2025-01-01T00:00:00Z`
+    /// Fractional seconds are accepted and truncated, offsets other than `Z` are rejected
+    pub fn parse_rfc3339(s: &str) -> Result<DateTime, DateTimeError> {
+        if !s.is_ascii() {
+            return Err(DateTimeError(()));
+        }
+
+        let mut date = parse_rfc3339(s.trim().as_bytes())?;
+
+        if !date.in_range() {
+            return Err(DateTimeError(()));
+        }
+
+        // The weekday is not part of the format, take it from the instant itself
+        date.wday_c = DateTime::from(SystemTime::from(date)).wday_c;
+
+        if !date.is_valid() {
+            return Err(DateTimeError(()));
+        }
+
+        Ok(date)
+    }
+
+    /// Formats as an RFC 3339 timestamp in UTC, such as `2025-01-01T00:00:00Z`
+    pub fn to_rfc3339(self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            self.year_c, self.mon_c, self.day_c, self.hr_c, self.min_c, self.sec_c
+        )
+    }
+
+    /// Returns true if `self` is strictly later than `other`
+    pub fn is_after(&self, other: &DateTime) -> bool {
+        self > other
+    }
+
+    /// Returns true if `self` is strictly earlier than `other`
+    pub fn is_before(&self, other: &DateTime) -> bool {
+        self < other
+    }
+
+    /// Returns the time passed since `self`, or zero if it is in the future
+    pub fn elapsed(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(SystemTime::from(*self))
+            .unwrap_or(Duration::ZERO)
     }
 }
 
@@ -45,7 +168,7 @@ impl From<SystemTime> for DateTime {
             .expect("DateTime should be after 1970 (UNIX EPOCH)");
         let sec_since_epoch = dur.as_secs();
 
-        if sec_since_epoch >= 253402300800 {
+        if sec_since_epoch >= MAX_SECS {
             // Year: 9999
             panic!("DateTime should be before 9999");
         }
@@ -89,7 +212,7 @@ impl From<SystemTime> for DateTime {
 
         rem_days -= rem_years * 365;
 
-        let mut year = 2000 + rem_years + 4 * q_cyles + 100 * c_cycles + 400 & qc_cycles;
+        let mut year = 2000 + rem_years + 4 * q_cyles + 100 * c_cycles + 400 * qc_cycles;
         let months = [31, 30, 31, 30, 31, 31, 30, 31, 30, 31, 31, 29];
         let mut mon = 0;
 
@@ -189,32 +312,8 @@ impl FromStr for DateTime {
 
 impl Display for DateTime {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let w_day = match self.wday_c {
-            1 => b"Mon",
-            2 => b"Tue",
-            3 => b"Wed",
-            4 => b"Thu",
-            5 => b"Fri",
-            6 => b"Sat",
-            7 => b"Sun",
-            _ => unreachable!(),
-        };
-
-        let mon = match self.mon_c {
-            1 => b"Jan",
-            2 => b"Feb",
-            3 => b"Mar",
-            4 => b"Apr",
-            5 => b"May",
-            6 => b"Jun",
-            7 => b"Jul",
-            8 => b"Aug",
-            9 => b"Sep",
-            10 => b"Oct",
-            11 => b"Nov",
-            12 => b"Dec",
-            _ => unreachable!(),
-        };
+        let w_day = self.weekday().as_str().as_bytes();
+        let mon = self.month().as_str().as_bytes();
 
         let mut buf: [u8; 29] = *b"   , 00     0000 00:00:00 GMT";
         buf[0] = w_day[0];
@@ -239,6 +338,12 @@ impl Display for DateTime {
     }
 }
 
+impl Debug for DateTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
 impl Ord for DateTime {
     fn cmp(&self, other: &DateTime) -> std::cmp::Ordering {
         SystemTime::from(*self).cmp(&SystemTime::from(*other))
@@ -251,6 +356,127 @@ impl PartialOrd for DateTime {
     }
 }
 
+/// Day of the week
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weekday {
+    Mon = 1,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Weekday {
+    const ALL: [Weekday; 7] = [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ];
+
+    /// Returns the weekday numbered `n`, from 1 for Monday to 7 for Sunday
+    pub fn from_u8(n: u8) -> Option<Weekday> {
+        Weekday::ALL.get(usize::from(n).checked_sub(1)?).copied()
+    }
+
+    /// Returns the three letter name used in HTTP dates, such as `Mon`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Weekday::Mon => "Mon",
+            Weekday::Tue => "Tue",
+            Weekday::Wed => "Wed",
+            Weekday::Thu => "Thu",
+            Weekday::Fri => "Fri",
+            Weekday::Sat => "Sat",
+            Weekday::Sun => "Sun",
+        }
+    }
+}
+
+impl FromStr for Weekday {
+    type Err = DateTimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Weekday::ALL
+            .into_iter()
+            .find(|w_day| w_day.as_str() == s)
+            .ok_or(DateTimeError(()))
+    }
+}
+
+/// Month of the year
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Month {
+    Jan = 1,
+    Feb,
+    Mar,
+    Apr,
+    May,
+    Jun,
+    Jul,
+    Aug,
+    Sep,
+    Oct,
+    Nov,
+    Dec,
+}
+
+impl Month {
+    const ALL: [Month; 12] = [
+        Month::Jan,
+        Month::Feb,
+        Month::Mar,
+        Month::Apr,
+        Month::May,
+        Month::Jun,
+        Month::Jul,
+        Month::Aug,
+        Month::Sep,
+        Month::Oct,
+        Month::Nov,
+        Month::Dec,
+    ];
+
+    /// Returns the month numbered `n`, from 1 for January to 12 for December
+    pub fn from_u8(n: u8) -> Option<Month> {
+        Month::ALL.get(usize::from(n).checked_sub(1)?).copied()
+    }
+
+    /// Returns the three letter name used in HTTP dates, such as `Jan`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Month::Jan => "Jan",
+            Month::Feb => "Feb",
+            Month::Mar => "Mar",
+            Month::Apr => "Apr",
+            Month::May => "May",
+            Month::Jun => "Jun",
+            Month::Jul => "Jul",
+            Month::Aug => "Aug",
+            Month::Sep => "Sep",
+            Month::Oct => "Oct",
+            Month::Nov => "Nov",
+            Month::Dec => "Dec",
+        }
+    }
+}
+
+impl FromStr for Month {
+    type Err = DateTimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Month::ALL
+            .into_iter()
+            .find(|mon| mon.as_str() == s)
+            .ok_or(DateTimeError(()))
+    }
+}
+
 fn to_int_1(x: u8) -> Result<u8, DateTimeError> {
     let result = x.wrapping_sub(b'0');
 
@@ -288,7 +514,7 @@ fn to_int_4(s: &[u8]) -> Result<u16, DateTimeError> {
 
 fn parse_imf_fixdate(s: &[u8]) -> Result<DateTime, DateTimeError> {
     // Date Eg: `Web, 01 Jan 2025 00:00:00 GMT`
-    if s.len() != 29 || &s[25..] != b"GMT" || s[16] != b' ' || s[19] != b':' || s[22] != b':' {
+    if s.len() != 29 || &s[25..] != b" GMT" || s[16] != b' ' || s[19] != b':' || s[22] != b':' {
         return Err(DateTimeError(()));
     }
 
@@ -432,6 +658,257 @@ fn parse_asctime(s: &[u8]) -> Result<DateTime, DateTimeError> {
     })
 }
 
+fn parse_rfc3339(s: &[u8]) -> Result<DateTime, DateTimeError> {
+    // Date Eg: `2025-01-01T00:00:00Z` or `2025-01-01T00:00:00.123Z`
+    if s.len() < 20 || s[4] != b'-' || s[7] != b'-' || s[13] != b':' || s[16] != b':' {
+        return Err(DateTimeError(()));
+    }
+
+    if !matches!(s[10], b'T' | b't') || !matches!(s[s.len() - 1], b'Z' | b'z') {
+        return Err(DateTimeError(()));
+    }
+
+    match &s[19..s.len() - 1] {
+        [] => {}
+        [b'.', fraction @ ..]
+            if !fraction.is_empty() && fraction.iter().all(u8::is_ascii_digit) => {}
+        _ => return Err(DateTimeError(())),
+    }
+
+    Ok(DateTime {
+        sec_c: to_int_2(&s[17..19])?,
+        min_c: to_int_2(&s[14..16])?,
+        hr_c: to_int_2(&s[11..13])?,
+        day_c: to_int_2(&s[8..10])?,
+        mon_c: to_int_2(&s[5..7])?,
+        year_c: to_int_4(&s[0..4])?,
+        // Filled in by `DateTime::parse_rfc3339`
+        wday_c: 0,
+    })
+}
+
 fn is_leap_year(y: u16) -> bool {
     y % 4 == 0 && (y % 100 != 0 || y % 400 == 0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year_c: u16, mon_c: u8, day_c: u8, hr_c: u8, min_c: u8, sec_c: u8) -> DateTime {
+        DateTime {
+            sec_c,
+            min_c,
+            hr_c,
+            day_c,
+            mon_c,
+            year_c,
+            // Not used when converting to `SystemTime`
+            wday_c: 1,
+        }
+    }
+
+    #[test]
+    fn test_is_after_is_before() {
+        let earlier = date(2024, 2, 29, 23, 59, 59);
+        let later = date(2024, 3, 1, 0, 0, 0);
+
+        assert!(later.is_after(&earlier));
+        assert!(earlier.is_before(&later));
+        assert!(!earlier.is_after(&later));
+        assert!(!later.is_before(&earlier));
+
+        // Neither holds for the same instant
+        assert!(!later.is_after(&later));
+        assert!(!later.is_before(&later));
+    }
+
+    #[test]
+    fn test_elapsed() {
+        let past = date(2020, 1, 1, 0, 0, 0);
+        let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+
+        // 2020-01-01 is 1577836800 seconds after the epoch
+        let expected = since_epoch - Duration::from_secs(1_577_836_800);
+
+        assert!(past.elapsed().abs_diff(expected) < Duration::from_secs(60));
+
+        let future = date(9999, 12, 31, 23, 59, 59);
+
+        assert_eq!(future.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_system_time_round_trip() {
+        // (seconds since the epoch, expected date, weekday)
+        let cases = [
+            (0, date(1970, 1, 1, 0, 0, 0), 4),
+            (951_868_800, date(2000, 3, 1, 0, 0, 0), 3),
+            (4_107_456_000 + 3_723, date(2100, 2, 28, 1, 2, 3), 7),
+            (13_569_465_600, date(2400, 1, 1, 0, 0, 0), 6),
+            (253_402_214_400 + 86_399, date(9999, 12, 31, 23, 59, 59), 5),
+        ];
+
+        for (secs, expected, wday_c) in cases {
+            let sys_t = UNIX_EPOCH + Duration::from_secs(secs);
+            let date_time = DateTime::from(sys_t);
+
+            assert_eq!(date_time, DateTime { wday_c, ..expected }, "{secs}");
+            assert_eq!(SystemTime::from(date_time), sys_t, "{secs}");
+            assert!(date_time.is_valid());
+        }
+    }
+
+    #[test]
+    fn test_parse_rfc3339() {
+        let parsed = DateTime::parse_rfc3339("2025-01-01T12:34:56Z").unwrap();
+
+        // 2025-01-01 is a Wednesday
+        assert_eq!(
+            parsed,
+            DateTime {
+                wday_c: 3,
+                ..date(2025, 1, 1, 12, 34, 56)
+            }
+        );
+        assert_eq!(parsed.to_rfc3339(), "2025-01-01T12:34:56Z");
+
+        // Fractional seconds are truncated
+        let parsed = DateTime::parse_rfc3339("2024-02-29T23:59:59.999999z").unwrap();
+
+        assert_eq!(parsed.to_rfc3339(), "2024-02-29T23:59:59Z");
+        assert_eq!(parsed.to_string(), "Thu, 29 Feb 2024 23:59:59 GMT");
+    }
+
+    #[test]
+    fn test_parse_rfc3339_invalid() {
+        let invalid = [
+            // Missing `T`
+            "2025-01-01 00:00:00Z",
+            // Invalid month
+            "2025-13-01T00:00:00Z",
+            // No such day
+            "2025-02-29T00:00:00Z",
+            // Offsets other than `Z`
+            "2025-01-01T00:00:00+01:00",
+            "2025-01-01T00:00:00",
+            // Empty or non numeric fraction
+            "2025-01-01T00:00:00.Z",
+            "2025-01-01T00:00:00.1aZ",
+            "1969-12-31T23:59:59Z",
+        ];
+
+        for s in invalid {
+            assert!(DateTime::parse_rfc3339(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_checked_add_sub() {
+        let day = Duration::from_secs(86400);
+
+        // Across a month boundary
+        let next = date(2025, 1, 31, 12, 0, 0).checked_add(day).unwrap();
+
+        assert_eq!(next.to_rfc3339(), "2025-02-01T12:00:00Z");
+        assert_eq!(next.to_string(), "Sat, 01 Feb 2025 12:00:00 GMT");
+
+        // Onto and over a leap day
+        let leap = date(2024, 2, 28, 0, 0, 0).checked_add(day).unwrap();
+
+        assert_eq!(leap.to_rfc3339(), "2024-02-29T00:00:00Z");
+        assert_eq!(
+            leap.checked_add(day).unwrap().to_rfc3339(),
+            "2024-03-01T00:00:00Z"
+        );
+        assert_eq!(
+            date(2024, 3, 1, 0, 0, 0)
+                .checked_sub(day)
+                .unwrap()
+                .to_rfc3339(),
+            "2024-02-29T00:00:00Z"
+        );
+
+        // Out of range
+        assert!(
+            date(9999, 12, 31, 23, 59, 59)
+                .checked_add(Duration::from_secs(1))
+                .is_none()
+        );
+        assert!(
+            date(1970, 1, 1, 0, 0, 0)
+                .checked_sub(Duration::from_secs(1))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_duration_since() {
+        let earlier = date(2024, 2, 28, 0, 0, 0);
+        let later = date(2024, 3, 1, 0, 0, 1);
+
+        assert_eq!(
+            later.duration_since(&earlier),
+            Some(Duration::from_secs(2 * 86400 + 1))
+        );
+        assert_eq!(earlier.duration_since(&later), None);
+        assert_eq!(earlier.duration_since(&earlier), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_weekday() {
+        let names = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+        for (n, name) in (1..=7).zip(names) {
+            let w_day = Weekday::from_u8(n).unwrap();
+
+            assert_eq!(w_day as u8, n);
+            assert_eq!(w_day.as_str(), name);
+            assert_eq!(w_day.as_str().parse::<Weekday>().unwrap(), w_day);
+        }
+
+        assert_eq!(Weekday::from_u8(0), None);
+        assert_eq!(Weekday::from_u8(8), None);
+        assert!("Monday".parse::<Weekday>().is_err());
+
+        // 2025-01-01 is a Wednesday
+        let date_time = DateTime::parse_rfc3339("2025-01-01T00:00:00Z").unwrap();
+
+        assert_eq!(date_time.weekday(), Weekday::Wed);
+    }
+
+    #[test]
+    fn test_month() {
+        let names = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+
+        for (n, name) in (1..=12).zip(names) {
+            let mon = Month::from_u8(n).unwrap();
+
+            assert_eq!(mon as u8, n);
+            assert_eq!(mon.as_str(), name);
+            assert_eq!(mon.as_str().parse::<Month>().unwrap(), mon);
+            assert_eq!(date(2025, n, 1, 0, 0, 0).month(), mon);
+        }
+
+        assert_eq!(Month::from_u8(0), None);
+        assert_eq!(Month::from_u8(13), None);
+        assert!("jan".parse::<Month>().is_err());
+    }
+
+    #[test]
+    fn test_debug_and_accessors() {
+        let date_time = DateTime::parse_rfc3339("2025-07-04T09:08:07Z").unwrap();
+
+        assert_eq!(format!("{date_time:?}"), "Fri, 04 Jul 2025 09:08:07 GMT");
+        assert_eq!(date_time.year(), 2025);
+        assert_eq!(date_time.month_number(), 7);
+        assert_eq!(date_time.month(), Month::Jul);
+        assert_eq!(date_time.day(), 4);
+        assert_eq!(date_time.hour(), 9);
+        assert_eq!(date_time.minute(), 8);
+        assert_eq!(date_time.second(), 7);
+        assert_eq!(date_time.weekday(), Weekday::Fri);
+    }
+}
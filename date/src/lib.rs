@@ -3,46 +3,51 @@
 mod date_time;
 mod date_time_error;
 
+pub use date_time::{DateTime, Month, Weekday};
+pub use date_time_error::DateTimeError;
+
 use bytes::BytesMut;
-use date_time::DateTime;
 use std::{
-    cell::UnsafeCell,
     fmt::{self, Write},
     sync::{Arc, LazyLock},
     thread,
     time::{Duration, SystemTime},
 };
+use sync::SeqLockCell;
 
 /// Date length: "Wed, 01 Jan 2025 00:00:00 GMT".len() = 29
 const DATE_VALUE_LENGTH: usize = 29;
 
-static CURRENT_DATE: LazyLock<Arc<DataWrap>> = LazyLock::new(|| {
-    let date = Arc::new(DataWrap(UnsafeCell::new(Date::now())));
+// Readers copy the date out of the seqlock, so they never see one half written by the updater
+static CURRENT_DATE: LazyLock<Arc<SeqLockCell<Date>>> = LazyLock::new(start_date_updater);
+
+/// Formats the current date and spawns the thread that keeps it up to date
+fn start_date_updater() -> Arc<SeqLockCell<Date>> {
+    let date = Arc::new(SeqLockCell::new(Date::now()));
     let date_clone = date.clone();
 
     thread::spawn(move || {
-        thread::sleep(Duration::from_millis(500));
+        let mut next = date_clone.read();
 
         loop {
-            unsafe { &mut *(date_clone.0).get() }.update()
+            next.update();
+            date_clone.write(next);
+
+            thread::sleep(Duration::from_millis(500));
         }
     });
 
     date
-});
-
-struct DataWrap(UnsafeCell<Date>);
-
-unsafe impl Sync for DataWrap {}
-// unsafe impl Sync for LazyCell<Arc<DataWrap>> {}
+}
 
 #[inline]
 pub fn append_date(dst: &mut BytesMut) {
-    let date = unsafe { &*CURRENT_DATE.0.get() };
+    let date = CURRENT_DATE.read();
 
     dst.extend_from_slice(date.as_bytes())
 }
 
+#[derive(Clone, Copy)]
 struct Date {
     bytes: [u8; DATE_VALUE_LENGTH],
 }
@@ -53,6 +58,8 @@ impl Date {
             bytes: [0; DATE_VALUE_LENGTH],
         };
 
+        date.update();
+
         date
     }
 
@@ -76,3 +83,72 @@ impl fmt::Write for Date {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    fn assert_imf_fixdate(date: &[u8]) {
+        let date = std::str::from_utf8(date).unwrap();
+        let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+
+        assert_eq!(date.len(), DATE_VALUE_LENGTH, "{date:?}");
+        assert!(DAYS.contains(&&date[..3]), "{date:?}");
+        assert_eq!(&date[3..5], ", ");
+        assert!(digits(&date[5..7]), "{date:?}");
+        assert!(MONTHS.contains(&&date[8..11]), "{date:?}");
+        assert!(digits(&date[12..16]), "{date:?}");
+        assert!(digits(&date[17..19]) && digits(&date[20..22]) && digits(&date[23..25]));
+        assert_eq!(&date[25..], " GMT");
+    }
+
+    #[test]
+    fn test_date_now_is_formatted() {
+        assert_imf_fixdate(Date::now().as_bytes());
+    }
+
+    #[test]
+    fn test_append_date_concurrent_readers() {
+        let readers: Vec<_> = (0..8)
+            .map(|_| {
+                thread::spawn(|| {
+                    let mut dst = BytesMut::new();
+
+                    for _ in 0..10_000 {
+                        dst.clear();
+                        append_date(&mut dst);
+
+                        let date = std::str::from_utf8(&dst).unwrap();
+
+                        assert!(date.parse::<DateTime>().is_ok(), "{date:?}");
+                    }
+                })
+            })
+            .collect();
+
+        // Race the readers against updates
+        for _ in 0..10_000 {
+            let mut date = CURRENT_DATE.read();
+
+            date.update();
+            CURRENT_DATE.write(date);
+        }
+
+        for reader in readers {
+            reader.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_date_is_set_on_first_access() {
+        // Other tests may have touched `CURRENT_DATE` already, so run its initializer anew
+        let current_date = LazyLock::new(start_date_updater);
+
+        assert_imf_fixdate(current_date.read().as_bytes());
+    }
+}
@@ -0,0 +1,406 @@
+use core::sync::atomic::{self, Ordering};
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    mem::{ManuallyDrop, MaybeUninit},
+};
+
+use super::{
+    atomic, atomic_compare_exchange, atomic_compare_exchange_weak, atomic_is_lock_free,
+    atomic_load, atomic_store, atomic_swap, can_transmute, lock,
+};
+
+/// A thread-safe mutable memory location
+/// Operations use atomic instructions when `T` fits a primitive atomic type, and fall back to a
+/// global seqlock otherwise
+#[repr(transparent)]
+pub struct AtomicCell<T> {
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+unsafe impl<T: Send> Send for AtomicCell<T> {}
+unsafe impl<T: Sync> Sync for AtomicCell<T> {}
+
+impl<T> AtomicCell<T> {
+    /// Creates a new atomic cell initialized with `value`
+    pub const fn new(value: T) -> AtomicCell<T> {
+        AtomicCell {
+            value: UnsafeCell::new(MaybeUninit::new(value)),
+        }
+    }
+
+    /// Consumes the atomic cell and returns the underlying value
+    pub fn into_inner(self) -> T {
+        let this = ManuallyDrop::new(self);
+
+        // SAFETY:
+        // - Passing `self` by value guarantees that no other threads are concurrently accessing
+        // the atomic data
+        // - The raw pointer passed in is valid because we got it from an owned value
+        // - `ManuallyDrop` prevents double dropping of `T`
+        unsafe { this.as_ptr().read() }
+    }
+
+    /// Returns `true` if the operations on values of this type are lock-free
+    pub const fn is_lock_free() -> bool {
+        atomic_is_lock_free::<T>()
+    }
+
+    /// Stores `value` into the atomic cell
+    pub fn store(&self, value: T) {
+        if core::mem::needs_drop::<T>() {
+            drop(self.swap(value));
+        } else {
+            unsafe { atomic_store(self.as_ptr(), value, Ordering::Release) };
+        }
+    }
+
+    /// Stores `value` into the atomic cell and returns the previous value
+    pub fn swap(&self, value: T) -> T {
+        unsafe { atomic_swap(self.as_ptr(), value) }
+    }
+
+    /// Returns a raw pointer to the underlying data in this atomic cell
+    #[inline]
+    pub fn as_ptr(&self) -> *mut T {
+        self.value.get().cast::<T>()
+    }
+}
+
+impl<T: Default> AtomicCell<T> {
+    /// Takes the value of the atomic cell, leaving `Default::default()` in its place
+    pub fn take(&self) -> T {
+        self.swap(Default::default())
+    }
+}
+
+impl<T: Copy> AtomicCell<T> {
+    /// Loads a value from the atomic cell
+    pub fn load(&self) -> T {
+        unsafe { atomic_load(self.as_ptr(), Ordering::Acquire) }
+    }
+
+    /// Loads a value from the atomic cell with the given memory ordering
+    /// The ordering only applies to the atomic path, the seqlock fallback is unchanged
+    pub fn load_ordered(&self, order: Ordering) -> T {
+        debug_assert!(
+            !matches!(order, Ordering::Release | Ordering::AcqRel),
+            "load_ordered: invalid ordering {order:?}"
+        );
+
+        unsafe { atomic_load(self.as_ptr(), order) }
+    }
+
+    /// Stores `value` into the atomic cell with the given memory ordering
+    /// The ordering only applies to the atomic path, the seqlock fallback is unchanged
+    pub fn store_ordered(&self, value: T, order: Ordering) {
+        debug_assert!(
+            !matches!(order, Ordering::Acquire | Ordering::AcqRel),
+            "store_ordered: invalid ordering {order:?}"
+        );
+
+        unsafe { atomic_store(self.as_ptr(), value, order) }
+    }
+}
+
+impl<T: Copy + Eq> AtomicCell<T> {
+    /// If the current value equals `current`, stores `new` into the atomic cell
+    pub fn compare_exchange(&self, current: T, new: T) -> Result<T, T> {
+        unsafe { atomic_compare_exchange(self.as_ptr(), current, new) }
+    }
+
+    /// Same as `compare_exchange`, spelled out for callers choosing between the two variants
+    /// Spurious failures of the underlying instruction are retried internally, so an `Err` always
+    /// holds a value different from `current`
+    pub fn compare_exchange_strong(&self, current: T, new: T) -> Result<T, T> {
+        self.compare_exchange(current, new)
+    }
+
+    /// If the current value equals `current`, stores `new` into the atomic cell
+    /// Unlike `compare_exchange`, this may fail spuriously, returning an `Err` holding a value
+    /// equal to `current`. Cheaper inside a loop that reloads anyway
+    pub fn compare_exchange_weak(&self, current: T, new: T) -> Result<T, T> {
+        unsafe { atomic_compare_exchange_weak(self.as_ptr(), current, new) }
+    }
+
+    /// Fetches the value and applies a function to it that returns an optional new value
+    /// Returns a `Result` of `Ok(previous_value)` if the function returned `Some(_)`, else
+    /// `Err(previous_value)`
+    pub fn fetch_update<F>(&self, mut f: F) -> Result<T, T>
+    where
+        F: FnMut(T) -> Option<T>,
+    {
+        let mut previous = self.load();
+
+        while let Some(next) = f(previous) {
+            match self.compare_exchange(previous, next) {
+                x @ Ok(_) => return x,
+                Err(next_previous) => previous = next_previous,
+            }
+        }
+
+        Err(previous)
+    }
+
+    /// Same as `fetch_update`, but built on `compare_exchange_weak`
+    /// `f` may be called again with the same value after a spurious failure, so prefer
+    /// `fetch_update` when `f` is expensive
+    pub fn fetch_update_weak<F>(&self, mut f: F) -> Result<T, T>
+    where
+        F: FnMut(T) -> Option<T>,
+    {
+        let mut previous = self.load();
+
+        while let Some(next) = f(previous) {
+            match self.compare_exchange_weak(previous, next) {
+                x @ Ok(_) => return x,
+                Err(next_previous) => previous = next_previous,
+            }
+        }
+
+        Err(previous)
+    }
+}
+
+/// `MaybeUninit` prevents `T` from being dropped, so we need to implement `Drop` for `AtomicCell`
+/// to avoid leaks of non-`Copy` types
+impl<T> Drop for AtomicCell<T> {
+    fn drop(&mut self) {
+        if core::mem::needs_drop::<T>() {
+            // SAFETY:
+            // - The mutable references guarantees that no other threads are concurrently accessing
+            // the atomic data
+            // - The raw pointer passed in is valid because we got it from a reference
+            // - `MaybeUninit` prevents double dropping of `T`
+            unsafe { self.as_ptr().drop_in_place() };
+        }
+    }
+}
+
+impl AtomicCell<bool> {
+    /// Applies logical `and` to the current value and returns the previous value
+    #[inline]
+    pub fn fetch_and(&self, value: bool) -> bool {
+        atomic! {
+            bool, _a,
+            {
+                let a = unsafe { &*(self.as_ptr() as *const atomic::AtomicBool) };
+
+                a.fetch_and(value, Ordering::AcqRel)
+            },
+            {
+                let _guard = lock(self.as_ptr() as usize).write();
+                let val = unsafe { &mut *(self.as_ptr()) };
+                let old = *val;
+
+                *val &= value;
+
+                old
+            }
+        }
+    }
+
+    /// Applies logical `nand` to the current value and returns the previous value
+    #[inline]
+    pub fn fetch_nand(&self, value: bool) -> bool {
+        atomic! {
+            bool, _a,
+            {
+                let a = unsafe { &*(self.as_ptr() as *const atomic::AtomicBool) };
+
+                a.fetch_nand(value, Ordering::AcqRel)
+            },
+            {
+                self.fetch_nand_locked(value)
+            }
+        }
+    }
+
+    // Fallback of `fetch_nand` through the global seqlock, split out so it can be tested even
+    // though `bool` always takes the atomic path
+    fn fetch_nand_locked(&self, value: bool) -> bool {
+        let _guard = lock(self.as_ptr() as usize).write();
+        let val = unsafe { &mut *(self.as_ptr()) };
+        let old = *val;
+
+        *val = !(old & value);
+
+        old
+    }
+
+    /// Applies logical `or` to the current value and returns the previous value
+    #[inline]
+    pub fn fetch_or(&self, value: bool) -> bool {
+        atomic! {
+            bool, _a,
+            {
+                let a = unsafe { &*(self.as_ptr() as *const atomic::AtomicBool) };
+
+                a.fetch_or(value, Ordering::AcqRel)
+            },
+            {
+                let _guard = lock(self.as_ptr() as usize).write();
+                let val = unsafe { &mut *(self.as_ptr()) };
+                let old = *val;
+
+                *val |= value;
+
+                old
+            }
+        }
+    }
+
+    /// Applies logical `xor` to the current value and returns the previous value
+    #[inline]
+    pub fn fetch_xor(&self, value: bool) -> bool {
+        atomic! {
+            bool, _a,
+            {
+                let a = unsafe { &*(self.as_ptr() as *const atomic::AtomicBool) };
+
+                a.fetch_xor(value, Ordering::AcqRel)
+            },
+            {
+                let _guard = lock(self.as_ptr() as usize).write();
+                let val = unsafe { &mut *(self.as_ptr()) };
+                let old = *val;
+
+                *val ^= value;
+
+                old
+            }
+        }
+    }
+}
+
+impl<T: Default> Default for AtomicCell<T> {
+    fn default() -> AtomicCell<T> {
+        AtomicCell::new(T::default())
+    }
+}
+
+impl<T> From<T> for AtomicCell<T> {
+    #[inline]
+    fn from(value: T) -> AtomicCell<T> {
+        AtomicCell::new(value)
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for AtomicCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AtomicCell")
+            .field("value", &self.load())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fetch_nand_fallback() {
+        for (old, value) in [(false, false), (false, true), (true, false), (true, true)] {
+            let cell = AtomicCell::new(old);
+
+            assert_eq!(cell.fetch_nand_locked(value), old);
+            assert_eq!(cell.load(), !(old & value));
+            assert_eq!(AtomicCell::new(old).fetch_nand(value), old);
+        }
+    }
+
+    #[test]
+    fn test_fetch_update_variants() {
+        let cell = AtomicCell::new(1u64);
+        let mut calls = 0;
+
+        let result = cell.fetch_update(|val| {
+            calls += 1;
+
+            Some(val * 10)
+        });
+
+        // Uncontended, the strong variant never calls `f` twice
+        assert_eq!(result, Ok(1));
+        assert_eq!(calls, 1);
+
+        let mut calls = 0;
+
+        let result = cell.fetch_update_weak(|val| {
+            calls += 1;
+
+            Some(val + 5)
+        });
+
+        assert_eq!(result, Ok(10));
+        assert!(calls >= 1);
+        assert_eq!(cell.load(), 15);
+        assert_eq!(cell.fetch_update_weak(|_| None), Err(15));
+    }
+
+    #[test]
+    fn test_compare_exchange_strong_and_weak() {
+        let cell = AtomicCell::new(3u32);
+
+        assert_eq!(cell.compare_exchange_strong(3, 4), Ok(3));
+        assert_eq!(cell.compare_exchange_strong(3, 5), Err(4));
+
+        while cell.compare_exchange_weak(4, 6).is_err() {}
+
+        assert_eq!(cell.load(), 6);
+        assert_eq!(cell.compare_exchange_weak(4, 7), Err(6));
+
+        let cell = AtomicCell::new([1u8; 3]);
+
+        assert_eq!(cell.compare_exchange_weak([1; 3], [2; 3]), Ok([1; 3]));
+    }
+
+    #[test]
+    fn test_ordered_u64() {
+        let cell = AtomicCell::new(0u64);
+
+        cell.store_ordered(1, Ordering::Relaxed);
+
+        assert_eq!(cell.load_ordered(Ordering::Relaxed), 1);
+
+        cell.store_ordered(u64::MAX, Ordering::SeqCst);
+
+        assert_eq!(cell.load_ordered(Ordering::SeqCst), u64::MAX);
+        assert_eq!(cell.load(), u64::MAX);
+    }
+
+    #[test]
+    fn test_ordered_fallback() {
+        let cell = AtomicCell::new([1u8; 3]);
+
+        cell.store_ordered([2; 3], Ordering::Relaxed);
+
+        assert_eq!(cell.load_ordered(Ordering::SeqCst), [2; 3]);
+    }
+
+    #[test]
+    fn test_unit_is_lock_free() {
+        assert!(AtomicCell::<()>::is_lock_free());
+        assert!(AtomicCell::<[u64; 0]>::is_lock_free());
+
+        let cell = AtomicCell::new(());
+
+        // Taking the lock for the cell would spin forever while it is held
+        let guard = lock(cell.as_ptr() as usize).write();
+
+        cell.store(());
+
+        assert_eq!(cell.load(), ());
+        assert_eq!(cell.swap(()), ());
+        assert_eq!(cell.compare_exchange_strong((), ()), Ok(()));
+
+        guard.abort();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "store_ordered: invalid ordering")]
+    fn test_store_ordered_acquire() {
+        AtomicCell::new(0u64).store_ordered(1, Ordering::Acquire);
+    }
+}
@@ -1,13 +1,14 @@
-use core::cmp;
-use std::sync::atomic::Ordering;
+use core::{cmp, sync::atomic::Ordering};
 
-use super::{AtomicCell, AtomicUnit, can_transmute, lock};
+use super::{AtomicCell, can_transmute, lock};
 
 macro_rules! atomic {
     // If values of type `$t` can be transmuted into values of the primitive atomic type
     // `$atomic`, declares variables `$a` of type `$atomic` and executes `$atomic_op`, breaking
     // out of the loop
     (@check, $t:ty, $atomic:ty, $a:ident, $atomic_op:expr) => {
+        // `AtomicUnit` makes the unit values of zero sized types flow through `$atomic_op`
+        #[allow(clippy::let_unit_value, clippy::unit_arg)]
         if can_transmute::<$t, $atomic>() {
             let $a: &$atomic;
 
@@ -20,13 +21,12 @@ macro_rules! atomic {
     // `$fallback_op`
     ($t:ty, $a:ident, $atomic_op:expr, $fallback_op:expr) => {
         loop {
-            atomic!(@check, $t, AtomicUnit, $a, $atomic_op);
-
-            atomic!(@check, $t, crate::sync::primitive::sync::atomic::AtomicU8, $a, $atomic_op);
-            atomic!(@check, $t, crate::sync::primitive::sync::atomic::AtomicU16, $a, $atomic_op);
-            atomic!(@check, $t, crate::sync::primitive::sync::atomic::AtomicU32, $a, $atomic_op);
+            atomic!(@check, $t, crate::atomic_unit::AtomicUnit, $a, $atomic_op);
+            atomic!(@check, $t, crate::primitive::sync::atomic::AtomicU8, $a, $atomic_op);
+            atomic!(@check, $t, crate::primitive::sync::atomic::AtomicU16, $a, $atomic_op);
+            atomic!(@check, $t, crate::primitive::sync::atomic::AtomicU32, $a, $atomic_op);
             #[cfg(target_has_atomic = "64")]
-            atomic!(@check, $t, crate::sync::primitive::sync::atomic::AtomicU64, $a, $atomic_op);
+            atomic!(@check, $t, crate::primitive::sync::atomic::AtomicU64, $a, $atomic_op);
             // TODO: AtomicU128 is unstable
             // atomic!(@check, $t, atomic::AtomicU128, $a, $atomic_op);
 
@@ -45,7 +45,7 @@ macro_rules! impl_arithmetic {
             /// # Examples
             ///
             /// ```
-            /// use crossbeam_utils::atomic::AtomicCell;
+            /// use sync::AtomicCell;
             ///
             #[doc = $example]
             ///
@@ -68,7 +68,7 @@ macro_rules! impl_arithmetic {
             /// # Examples
             ///
             /// ```
-            /// use crossbeam_utils::atomic::AtomicCell;
+            /// use sync::AtomicCell;
             ///
             #[doc = $example]
             ///
@@ -89,7 +89,7 @@ macro_rules! impl_arithmetic {
             /// # Examples
             ///
             /// ```
-            /// use crossbeam_utils::atomic::AtomicCell;
+            /// use sync::AtomicCell;
             ///
             #[doc = $example]
             ///
@@ -110,7 +110,7 @@ macro_rules! impl_arithmetic {
             /// # Examples
             ///
             /// ```
-            /// use crossbeam_utils::atomic::AtomicCell;
+            /// use sync::AtomicCell;
             ///
             #[doc = $example]
             ///
@@ -178,7 +178,7 @@ macro_rules! impl_arithmetic {
                 atomic! {
                     $t, _a,
                     {
-                        let a = unsafe { &*(self.as_ptr() as *const crate::sync::primitive::sync::atomic::$atomic) };
+                        let a = unsafe { &*(self.as_ptr() as *const crate::primitive::sync::atomic::$atomic) };
                         a.fetch_add(val, Ordering::AcqRel)
                     },
                     {
@@ -198,7 +198,7 @@ macro_rules! impl_arithmetic {
                 atomic! {
                     $t, _a,
                     {
-                        let a = unsafe { &*(self.as_ptr() as *const crate::sync::primitive::sync::atomic::$atomic) };
+                        let a = unsafe { &*(self.as_ptr() as *const crate::primitive::sync::atomic::$atomic) };
                         a.fetch_sub(val, Ordering::AcqRel)
                     },
                     {
@@ -217,7 +217,7 @@ macro_rules! impl_arithmetic {
                 atomic! {
                     $t, _a,
                     {
-                        let a = unsafe { &*(self.as_ptr() as *const crate::sync::primitive::sync::atomic::$atomic) };
+                        let a = unsafe { &*(self.as_ptr() as *const crate::primitive::sync::atomic::$atomic) };
                         a.fetch_and(val, Ordering::AcqRel)
                     },
                     {
@@ -236,7 +236,7 @@ macro_rules! impl_arithmetic {
                 atomic! {
                     $t, _a,
                     {
-                        let a = unsafe { &*(self.as_ptr() as *const crate::sync::primitive::sync::atomic::$atomic) };
+                        let a = unsafe { &*(self.as_ptr() as *const crate::primitive::sync::atomic::$atomic) };
                         a.fetch_nand(val, Ordering::AcqRel)
                     },
                     {
@@ -255,7 +255,7 @@ macro_rules! impl_arithmetic {
                 atomic! {
                     $t, _a,
                     {
-                        let a = unsafe { &*(self.as_ptr() as *const crate::sync::primitive::sync::atomic::$atomic) };
+                        let a = unsafe { &*(self.as_ptr() as *const crate::primitive::sync::atomic::$atomic) };
                         a.fetch_or(val, Ordering::AcqRel)
                     },
                     {
@@ -274,7 +274,7 @@ macro_rules! impl_arithmetic {
                 atomic! {
                     $t, _a,
                     {
-                        let a = unsafe { &*(self.as_ptr() as *const crate::sync::primitive::sync::atomic::$atomic) };
+                        let a = unsafe { &*(self.as_ptr() as *const crate::primitive::sync::atomic::$atomic) };
                         a.fetch_xor(val, Ordering::AcqRel)
                     },
                     {
@@ -294,7 +294,7 @@ macro_rules! impl_arithmetic {
                 atomic! {
                     $t, _a,
                     {
-                        let a = unsafe { &*(self.as_ptr() as *const crate::sync::primitive::sync::atomic::$atomic) };
+                        let a = unsafe { &*(self.as_ptr() as *const crate::primitive::sync::atomic::$atomic) };
                         a.fetch_max(val, Ordering::AcqRel)
                     },
                     {
@@ -314,7 +314,7 @@ macro_rules! impl_arithmetic {
                 atomic! {
                     $t, _a,
                     {
-                        let a = unsafe { &*(self.as_ptr() as *const crate::sync::primitive::sync::atomic::$atomic) };
+                        let a = unsafe { &*(self.as_ptr() as *const crate::primitive::sync::atomic::$atomic) };
                         a.fetch_min(val, Ordering::AcqRel)
                     },
                     {
@@ -353,4 +353,3 @@ impl_arithmetic!(usize, AtomicUsize, "let a = AtomicCell::new(7usize);");
 impl_arithmetic!(isize, AtomicIsize, "let a = AtomicCell::new(7isize);");
 
 pub(crate) use atomic;
-pub(crate) use impl_arithmetic;
@@ -0,0 +1,342 @@
+use core::{
+    mem::{self, ManuallyDrop},
+    sync::atomic::{self, AtomicUsize, Ordering},
+};
+#[cfg(feature = "std")]
+use std::{cell::RefCell, vec::Vec};
+
+use super::{AtomicCell, Backoff, CachePadded, atomic_load_raw};
+
+/// Number of borrow counters shared by all the `AtomicOption`s, picked by address
+const BORROW_STRIPES: usize = 64;
+
+/// Readers currently inside `with_ref`, a value taken out is only handed over once its stripe
+/// has no reader left
+static BORROWS: [CachePadded<AtomicUsize>; BORROW_STRIPES] =
+    [const { CachePadded::new(AtomicUsize::new(0)) }; BORROW_STRIPES];
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    /// Addresses of the options the current thread is inside `with_ref` of, innermost last
+    static BORROWING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Releases a `with_ref` borrow, even if the closure panics
+struct BorrowGuard(&'static AtomicUsize);
+
+impl BorrowGuard {
+    fn new(addr: usize) -> BorrowGuard {
+        let borrows = &*BORROWS[stripe(addr)];
+
+        borrows.fetch_add(1, Ordering::SeqCst);
+
+        // Fails only while the thread is being torn down, the borrow then only counts in its
+        // stripe
+        #[cfg(feature = "std")]
+        let _ = BORROWING.try_with(|borrowing| borrowing.borrow_mut().push(addr));
+
+        BorrowGuard(borrows)
+    }
+}
+
+impl Drop for BorrowGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "std")]
+        let _ = BORROWING.try_with(|borrowing| borrowing.borrow_mut().pop());
+
+        self.0.fetch_sub(1, Ordering::Release);
+    }
+}
+
+fn stripe(addr: usize) -> usize {
+    (addr >> 3) % BORROW_STRIPES
+}
+
+/// An atomic `Option<T>`, meant for pointer-sized `T` such as `Box` or `Arc`
+#[repr(transparent)]
+pub struct AtomicOption<T> {
+    inner: AtomicCell<Option<T>>,
+}
+
+// Values are only ever moved in and out, never shared by reference, so `T: Send` is enough
+unsafe impl<T: Send> Send for AtomicOption<T> {}
+unsafe impl<T: Send> Sync for AtomicOption<T> {}
+
+// Both wrappers must stay layout compatible with the value they hold
+const _: () = assert!(mem::size_of::<AtomicCell<u32>>() == mem::size_of::<u32>());
+const _: () = assert!(mem::align_of::<AtomicCell<u32>>() == mem::align_of::<u32>());
+const _: () = assert!(mem::size_of::<AtomicOption<&u8>>() == mem::size_of::<usize>());
+const _: () = assert!(AtomicCell::<Option<&u8>>::is_lock_free());
+
+impl<T> AtomicOption<T> {
+    pub const fn none() -> AtomicOption<T> {
+        AtomicOption {
+            inner: AtomicCell::new(None),
+        }
+    }
+
+    /// Returns true if a value is stored, without taking it
+    #[inline]
+    pub fn is_some(&self) -> bool {
+        self.load_copy().is_some()
+    }
+
+    /// Calls `f` with a reference to the stored value without taking it, returns `None` if
+    /// there is no value
+    ///
+    /// A concurrent `take`, `swap` or `store` waits for `f` to return before handing the old
+    /// value over, so `f` should be short. `f` must not modify this option itself: with the
+    /// `std` feature that panics, without it the writer waits for `f` forever.
+    pub fn with_ref<F, R>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(&T) -> R,
+        T: Sync,
+    {
+        let _guard = BorrowGuard::new(self.addr());
+
+        // Pairs with the fence in `handover`, either the writer sees the borrow or we see the
+        // new value
+        atomic::fence(Ordering::SeqCst);
+
+        self.load_copy().as_ref().map(f)
+    }
+
+    /// Stores `val`, dropping the previous value if there was one
+    #[inline]
+    pub fn store(&self, val: T) {
+        drop(self.swap(val));
+    }
+
+    /// Takes the value out, leaving `None` in its place
+    #[inline]
+    pub fn take(&self) -> Option<T> {
+        self.handover(self.inner.swap(None))
+    }
+
+    /// Atomically installs `val` and returns the previous value
+    #[inline]
+    pub fn swap(&self, val: T) -> Option<T> {
+        self.handover(self.inner.swap(Some(val)))
+    }
+
+    /// Same as `swap`
+    #[inline]
+    pub fn replace(&self, val: T) -> Option<T> {
+        self.swap(val)
+    }
+
+    /// Loads a copy of the stored value, which stays owned by the cell
+    #[inline]
+    fn load_copy(&self) -> ManuallyDrop<Option<T>> {
+        ManuallyDrop::new(unsafe { atomic_load_raw(self.inner.as_ptr(), Ordering::SeqCst) })
+    }
+
+    fn addr(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    /// Waits for the readers that may still see `prev` before giving it to the caller
+    #[inline]
+    fn handover(&self, prev: Option<T>) -> Option<T> {
+        if prev.is_some() {
+            atomic::fence(Ordering::SeqCst);
+
+            let borrows = &*BORROWS[stripe(self.addr())];
+
+            if borrows.load(Ordering::Acquire) != 0 && !self.wait_for_readers(borrows) {
+                // `f` may still use it while unwinding, leak it instead
+                mem::forget(prev);
+
+                panic!("AtomicOption modified inside its own with_ref");
+            }
+        }
+
+        prev
+    }
+
+    /// Returns false without waiting if the current thread is itself borrowing this option
+    #[cold]
+    fn wait_for_readers(&self, borrows: &AtomicUsize) -> bool {
+        // The borrows this thread holds on other options of the stripe can't see `prev`, and
+        // would never be released while it waits
+        let Some(own) = self.own_borrows() else {
+            return false;
+        };
+        let backoff = Backoff::new();
+
+        while borrows.load(Ordering::Acquire) > own {
+            backoff.snooze();
+        }
+
+        true
+    }
+
+    /// Number of borrows the current thread holds on the stripe of this option, `None` if one
+    /// of them is on this option itself
+    #[cfg(feature = "std")]
+    fn own_borrows(&self) -> Option<usize> {
+        let addr = self.addr();
+
+        BORROWING
+            .try_with(|borrowing| {
+                let borrowing = borrowing.borrow();
+
+                if borrowing.contains(&addr) {
+                    return None;
+                }
+
+                Some(
+                    borrowing
+                        .iter()
+                        .filter(|&&other| stripe(other) == stripe(addr))
+                        .count(),
+                )
+            })
+            .unwrap_or(Some(0))
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn own_borrows(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use core::sync::atomic::AtomicBool;
+    use std::{boxed::Box, sync::Arc, thread, vec::Vec};
+
+    use super::*;
+
+    #[test]
+    fn test_swap_and_take() {
+        let option = AtomicOption::none();
+
+        assert_eq!(option.swap(Box::new(1)), None);
+        assert_eq!(option.replace(Box::new(2)), Some(Box::new(1)));
+
+        option.store(Box::new(3));
+
+        assert_eq!(option.take(), Some(Box::new(3)));
+        assert_eq!(option.take(), None);
+    }
+
+    #[test]
+    fn test_swap_race() {
+        for _ in 0..1000 {
+            let option = Arc::new(AtomicOption::none());
+
+            option.store(Box::new(0));
+
+            let handles: Vec<_> = [1, 2]
+                .into_iter()
+                .map(|val| {
+                    let option = option.clone();
+
+                    thread::spawn(move || option.swap(Box::new(val)))
+                })
+                .collect();
+
+            let mut seen: Vec<i32> = handles
+                .into_iter()
+                .filter_map(|handle| handle.join().unwrap())
+                .map(|val| *val)
+                .collect();
+
+            seen.extend(option.take().map(|val| *val));
+            seen.sort();
+
+            // Every value is handed out exactly once, either by a swap or by the final take
+            assert_eq!(seen, [0, 1, 2]);
+        }
+    }
+
+    #[test]
+    fn test_is_some_and_with_ref() {
+        let option: AtomicOption<Box<i32>> = AtomicOption::none();
+
+        assert!(!option.is_some());
+        assert_eq!(option.with_ref(|val| **val), None);
+
+        option.store(Box::new(5));
+
+        assert!(option.is_some());
+        assert_eq!(option.with_ref(|val| **val + 1), Some(6));
+
+        // Borrowing leaves the value in place
+        assert_eq!(option.take(), Some(Box::new(5)));
+        assert!(!option.is_some());
+    }
+
+    #[test]
+    fn test_with_ref_does_not_block_other_options() {
+        let option = AtomicOption::none();
+        let others: Vec<_> = (0..256).map(|_| AtomicOption::none()).collect();
+
+        option.store(Box::new(1));
+
+        for (i, other) in others.iter().enumerate() {
+            other.store(Box::new(i));
+        }
+
+        // Some of them share the borrow counter of this option, taking from them must not wait
+        // for this borrow
+        let taken = option.with_ref(|_| {
+            others
+                .iter()
+                .filter_map(AtomicOption::take)
+                .map(|val| *val)
+                .sum::<usize>()
+        });
+
+        assert_eq!(taken, Some((0..256).sum()));
+    }
+
+    #[test]
+    #[should_panic(expected = "AtomicOption modified inside its own with_ref")]
+    fn test_store_inside_own_with_ref_panics() {
+        let option = AtomicOption::none();
+
+        option.store(Box::new(1));
+        option.with_ref(|_| option.store(Box::new(2)));
+    }
+
+    #[test]
+    fn test_is_some_after_racing_take() {
+        for _ in 0..200 {
+            let option = Arc::new(AtomicOption::none());
+            let taken = Arc::new(AtomicBool::new(false));
+
+            option.store(Box::new(7));
+
+            let reader = {
+                let option = option.clone();
+                let taken = taken.clone();
+
+                thread::spawn(move || {
+                    loop {
+                        let was_taken = taken.load(Ordering::Acquire);
+
+                        if let Some(val) = option.with_ref(|val| **val) {
+                            assert_eq!(val, 7);
+                        }
+
+                        if was_taken {
+                            // The take happened before this check, its value must be gone
+                            assert!(!option.is_some());
+
+                            break;
+                        }
+                    }
+                })
+            };
+
+            thread::yield_now();
+
+            assert_eq!(option.take(), Some(Box::new(7)));
+            taken.store(true, Ordering::Release);
+
+            reader.join().unwrap();
+        }
+    }
+}
@@ -1,8 +1,8 @@
-use std::sync::atomic::Ordering;
+use core::sync::atomic::Ordering;
 
-/// An Atomic `()`
-/// All Operations are noops
-pub struct AtomicUnit;
+/// An atomic `()`, used by `atomic!` for zero sized types
+/// All operations are noops, there is no data to race on
+pub(crate) struct AtomicUnit;
 
 impl AtomicUnit {
     #[inline]
@@ -0,0 +1,142 @@
+use core::{cell::Cell, fmt};
+
+use crate::primitive::hint;
+
+const SPIN_LIMIT: u32 = 6;
+const YIELD_LIMIT: u32 = 10;
+
+/// Performs exponential backoff in spin loops
+pub struct Backoff {
+    step: Cell<u32>,
+    spin_limit: u32,
+    yield_limit: u32,
+}
+
+impl Backoff {
+    /// Creates a new `Backoff`
+    pub fn new() -> Backoff {
+        Backoff::with_limits(SPIN_LIMIT, YIELD_LIMIT)
+    }
+
+    /// Creates a new `Backoff` which spins for `spin_limit` steps and completes after
+    /// `yield_limit` steps, instead of the default 6 and 10
+    pub fn with_limits(spin_limit: u32, yield_limit: u32) -> Backoff {
+        Backoff {
+            step: Cell::new(0),
+            spin_limit,
+            yield_limit,
+        }
+    }
+
+    /// Returns the current step, which grows with each `spin` or `snooze`
+    #[inline]
+    pub fn step(&self) -> u32 {
+        self.step.get()
+    }
+
+    /// Resets the `Backoff`
+    #[inline]
+    pub fn reset(&self) {
+        self.step.set(0);
+    }
+
+    /// Backs off in a lock-free loop
+    #[inline]
+    pub fn spin(&self) {
+        for _ in 0..1u64 << self.step.get().min(self.spin_limit).min(63) {
+            hint::spin_loop();
+        }
+
+        if self.step.get() <= self.spin_limit {
+            self.step.set(self.step.get() + 1);
+        }
+    }
+
+    /// Backs off in a blocking loop
+    #[inline]
+    pub fn snooze(&self) {
+        if self.step.get() <= self.spin_limit {
+            hint::spin_loop();
+        } else {
+            #[cfg(feature = "std")]
+            ::std::thread::yield_now();
+
+            // Without a scheduler to yield to, keep spinning
+            #[cfg(not(feature = "std"))]
+            hint::spin_loop();
+        }
+
+        if self.step.get() <= self.yield_limit {
+            self.step.set(self.step.get() + 1);
+        }
+    }
+
+    /// Returns `true` if exponential backoff has completed and blocking the thread is advised
+    #[inline]
+    pub fn is_completed(&self) -> bool {
+        self.step.get() > self.yield_limit
+    }
+}
+
+impl fmt::Debug for Backoff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Backoff")
+            .field("step", &self.step)
+            .field("is_completed", &self.is_completed())
+            .finish()
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Backoff {
+        Backoff::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snoozes_until_completed(backoff: &Backoff) -> u32 {
+        let mut snoozes = 0;
+
+        while !backoff.is_completed() {
+            backoff.snooze();
+            snoozes += 1;
+        }
+
+        snoozes
+    }
+
+    #[test]
+    fn test_default_limits() {
+        let backoff = Backoff::new();
+
+        assert_eq!(snoozes_until_completed(&backoff), YIELD_LIMIT + 1);
+        assert_eq!(backoff.step(), YIELD_LIMIT + 1);
+
+        backoff.reset();
+
+        assert_eq!(backoff.step(), 0);
+    }
+
+    #[test]
+    fn test_with_limits() {
+        let tiny = Backoff::with_limits(0, 2);
+
+        assert_eq!(snoozes_until_completed(&tiny), 3);
+        assert!(
+            snoozes_until_completed(&Backoff::with_limits(0, 2))
+                < snoozes_until_completed(&Backoff::new())
+        );
+
+        // Spinning stops counting once past the spin limit
+        let spin = Backoff::with_limits(1, 10);
+
+        for _ in 0..5 {
+            spin.spin();
+        }
+
+        assert_eq!(spin.step(), 2);
+    }
+}
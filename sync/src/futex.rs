@@ -0,0 +1,157 @@
+//! Futex
+//! Blocking on the value of an `AtomicCell<u32>`, backed by the platform futex on Linux and a
+//! global `Condvar` elsewhere
+use core::sync::atomic::AtomicU32;
+
+use super::AtomicCell;
+
+const _: () = assert!(AtomicCell::<u32>::is_lock_free());
+
+impl AtomicCell<u32> {
+    /// Blocks the current thread while the value equals `expected`
+    /// Returns immediately if the value is already different. Wakeups come from `notify_all`
+    pub fn wait(&self, expected: u32) {
+        while self.load() == expected {
+            sys::wait(self.as_atomic(), expected);
+        }
+    }
+
+    /// Wakes all the threads blocked in `wait` on this cell
+    /// The new value must be stored before calling this
+    pub fn notify_all(&self) {
+        sys::wake_all(self.as_atomic());
+    }
+
+    #[inline]
+    fn as_atomic(&self) -> &AtomicU32 {
+        // SAFETY: `AtomicCell<u32>` is lock-free, so it is accessed as an `AtomicU32`
+        unsafe { &*(self.as_ptr() as *const AtomicU32) }
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod sys {
+    use core::{ffi::c_long, ptr, sync::atomic::AtomicU32};
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_FUTEX: c_long = 202;
+    #[cfg(target_arch = "aarch64")]
+    const SYS_FUTEX: c_long = 98;
+
+    const FUTEX_WAIT_PRIVATE: i32 = 128;
+    const FUTEX_WAKE_PRIVATE: i32 = 129;
+
+    unsafe extern "C" {
+        fn syscall(num: c_long, ...) -> c_long;
+    }
+
+    /// Sleeps until woken if the value at `atomic` is still `expected`, may wake spuriously
+    pub(super) fn wait(atomic: &AtomicU32, expected: u32) {
+        unsafe {
+            syscall(
+                SYS_FUTEX,
+                atomic.as_ptr(),
+                FUTEX_WAIT_PRIVATE,
+                expected,
+                ptr::null::<()>(),
+            );
+        }
+    }
+
+    pub(super) fn wake_all(atomic: &AtomicU32) {
+        unsafe {
+            syscall(SYS_FUTEX, atomic.as_ptr(), FUTEX_WAKE_PRIVATE, i32::MAX);
+        }
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+mod sys {
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    use crate::primitive::sync::{Condvar, Mutex};
+
+    // All cells share one condvar, waiters re-check their own value after waking
+    static LOCK: Mutex<()> = Mutex::new(());
+    static CVAR: Condvar = Condvar::new();
+
+    pub(super) fn wait(atomic: &AtomicU32, expected: u32) {
+        let mut guard = LOCK.lock().unwrap();
+
+        // Checking under the lock can't miss a store followed by `wake_all`
+        while atomic.load(Ordering::Acquire) == expected {
+            guard = CVAR.wait(guard).unwrap();
+        }
+    }
+
+    pub(super) fn wake_all(_atomic: &AtomicU32) {
+        drop(LOCK.lock().unwrap());
+
+        CVAR.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread, time::Duration};
+
+    use super::*;
+
+    #[test]
+    fn test_wait_returns_on_change() {
+        let cell = AtomicCell::new(1u32);
+
+        // Already different, doesn't block
+        cell.wait(0);
+    }
+
+    #[test]
+    fn test_wait_notify_all() {
+        let cell = Arc::new(AtomicCell::new(0u32));
+
+        let waiters: std::vec::Vec<_> = (0..4)
+            .map(|_| {
+                let cell = cell.clone();
+
+                thread::spawn(move || {
+                    cell.wait(0);
+
+                    cell.load()
+                })
+            })
+            .collect();
+
+        thread::sleep(Duration::from_millis(50));
+
+        cell.store(1);
+        cell.notify_all();
+
+        for waiter in waiters {
+            assert_eq!(waiter.join().unwrap(), 1);
+        }
+    }
+
+    #[test]
+    fn test_notify_without_change_keeps_waiting() {
+        let cell = Arc::new(AtomicCell::new(0u32));
+        let their_cell = cell.clone();
+
+        let waiter = thread::spawn(move || their_cell.wait(0));
+
+        thread::sleep(Duration::from_millis(20));
+        cell.notify_all();
+        thread::sleep(Duration::from_millis(20));
+
+        assert!(!waiter.is_finished());
+
+        cell.store(2);
+        cell.notify_all();
+        waiter.join().unwrap();
+    }
+}
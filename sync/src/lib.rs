@@ -0,0 +1,239 @@
+#![no_std]
+
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::{mem::MaybeUninit, ptr, sync::atomic::Ordering};
+
+mod atomic_cell;
+mod atomic_macro;
+mod atomic_option;
+mod atomic_unit;
+mod backoff;
+mod cache_padded;
+#[cfg(feature = "std")]
+mod futex;
+mod seq_lock;
+
+pub(crate) use self::atomic_macro::atomic;
+pub use atomic_cell::AtomicCell;
+pub use atomic_option::AtomicOption;
+pub use backoff::Backoff;
+pub use cache_padded::CachePadded;
+pub use seq_lock::{SeqCell, SeqLock, SeqLockCell, SeqLockWriteGuard};
+
+#[allow(unused_imports)]
+mod primitive {
+    pub(crate) mod hint {
+        pub(crate) use core::hint::spin_loop;
+    }
+
+    pub(crate) mod sync {
+        pub(crate) use core::sync::atomic;
+        #[cfg(feature = "std")]
+        pub(crate) use std::sync::{Arc, Condvar, Mutex};
+    }
+}
+
+/// Returns `true` if values of type `A` can be transmuted into values of type `B`
+const fn can_transmute<A, B>() -> bool {
+    // Sizes must be equal, but alignment of `A` must be greater than or equal to that of `B`
+    (core::mem::size_of::<A>() == core::mem::size_of::<B>())
+        & (core::mem::align_of::<A>() >= core::mem::align_of::<B>())
+}
+
+/// Returns a reference to the global lock associated with the `AtomicCell` at address `addr`
+#[inline]
+#[must_use]
+fn lock(addr: usize) -> &'static SeqLock {
+    // The number of locks is a prime number because we want to make sure `addr % LEN` gets
+    // dispersed across all locks
+    const LEN: usize = 67;
+    static LOCKS: [CachePadded<SeqLock>; LEN] = [const { CachePadded::new(SeqLock::new()) }; LEN];
+
+    // If the modulus is a constant number, the compiler will use crazy math to transform this into
+    // a sequence of cheap atithmetic operations rather than using the slow modulo instruction
+    &LOCKS[addr % LEN]
+}
+
+/// Returns `true` if operations on `AtomicCell<T>` are lock-free
+pub(crate) const fn atomic_is_lock_free<T>() -> bool {
+    atomic! { T, _a, true, false }
+}
+
+/// Atomically read data from `src`
+/// This operation uses the given ordering. If possible, an atomic instruction is used or a
+/// global lock otherwise
+pub(crate) unsafe fn atomic_load<T>(src: *mut T, order: Ordering) -> T
+where
+    T: Copy,
+{
+    unsafe { atomic_load_raw(src, order) }
+}
+
+/// Same as `atomic_load` for any `T`, returning a bitwise copy that `src` still owns
+/// The caller must make sure the copy is never dropped
+pub(crate) unsafe fn atomic_load_raw<T>(src: *mut T, order: Ordering) -> T {
+    atomic! {
+        T, a,
+        {
+            a = unsafe { &*(src as *const _ as *const _) };
+
+            unsafe { core::mem::transmute_copy(&a.load(order)) }
+        },
+        {
+            let lock = lock(src as usize);
+
+            // Try doing an optimistic read first
+            if let Some(stamp) = lock.optimistic_read() {
+                // We need a volatile read here because other threads might concurrently modify the
+                // value. In theory, data races are *always* an UB (undefined behaviour), even if
+                // we use volatile reads and discard the data when a data race is detected. The
+                // proper solution would be to do atomic reads and atomic writes, but we can't
+                // atomically read and write all kinds of data since `AtomicU8` is not available on
+                // stable Rust yet. Load as `MaybeUninit` because we may load a value that is not
+                // valid as `T`
+                let val = unsafe { ptr::read_volatile(src.cast::<MaybeUninit<T>>()) };
+
+                if lock.validate_read(stamp) {
+                    return unsafe { val.assume_init() };
+                }
+            }
+
+            // Grab a regular write lock so that writers don't starve for this load
+            let guard = lock.write();
+            let val = unsafe { ptr::read(src) };
+
+            // The value hasn't changed. Drop the guard without incrementing the stamp
+            guard.abort();
+
+            val
+        }
+    }
+}
+
+/// Atomically writes `value` to `dst`
+/// This operation uses the given ordering. If possible, an atomic instruction is used or a
+/// global lock otherwise
+pub(crate) unsafe fn atomic_store<T>(dst: *mut T, value: T, order: Ordering) {
+    atomic! {
+        T, a,
+        {
+            a = unsafe { &*(dst as *const _ as *const _) };
+            a.store(unsafe { core::mem::transmute_copy(&value) }, order);
+
+            core::mem::forget(value);
+        },
+        {
+            let _guard = lock(dst as usize).write();
+
+            unsafe { core::ptr::write(dst, value) }
+        }
+    }
+}
+
+/// Atomically writes `value` to `dst` and returns the previous value
+/// This operation uses the `AcqRel` ordering. If possible, an atomic instruction is used or a
+/// global lock otherwise
+pub(crate) unsafe fn atomic_swap<T>(dst: *mut T, value: T) -> T {
+    atomic! {
+        T, a,
+        {
+            a = unsafe { &*(dst as *const _ as *const _) };
+
+            let previous =
+                unsafe { core::mem::transmute_copy(&a.swap(core::mem::transmute_copy(&value), Ordering::AcqRel)) };
+
+            core::mem::forget(value);
+
+            previous
+        },
+        {
+            let _guard = lock(dst as usize).write();
+
+            unsafe { ptr::replace(dst, value) }
+        }
+    }
+}
+
+/// Atomically compares data at `dst` to `current` and, if equal byte-for-byte, exchanges data at
+/// `dst` with `new`. Returns the previous value on success, or the current value at `dst` if it
+/// was not equal to `current`
+/// This operation uses the `AcqRel` ordering and retries internally on spurious failures. If
+/// possible, an atomic instruction is used or a global lock otherwise
+pub(crate) unsafe fn atomic_compare_exchange<T>(dst: *mut T, mut current: T, new: T) -> Result<T, T>
+where
+    T: Copy + Eq,
+{
+    atomic! {
+        T, a,
+        {
+            a = unsafe { &*(dst as *const _ as *const _) };
+
+            let mut current_raw = unsafe { core::mem::transmute_copy(&current) };
+            let new_raw = unsafe { core::mem::transmute_copy(&new) };
+
+            loop {
+                match a.compare_exchange_weak(current_raw, new_raw, Ordering::AcqRel, Ordering::Acquire) {
+                    Ok(_) => break Ok(current),
+                    Err(previous_raw) => {
+                        let previous = unsafe { core::mem::transmute_copy(&previous_raw) };
+
+                        if !T::eq(&previous, &current) {
+                            break Err(previous);
+                        }
+
+                        // The exchange failed spuriously, or `previous` is equal to `current`
+                        // without being byte-equal. Retry with `previous` as the new `current`
+                        current = previous;
+                        current_raw = previous_raw;
+                    }
+                }
+            }
+        },
+        {
+            let guard = lock(dst as usize).write();
+
+            if T::eq(unsafe { &*dst }, &current) {
+                Ok(unsafe { ptr::replace(dst, new) })
+            } else {
+                let val = unsafe { ptr::read(dst) };
+
+                // The value hasn't changed. Drop the guard without incrementing the stamp
+                guard.abort();
+
+                Err(val)
+            }
+        }
+    }
+}
+
+/// Same as `atomic_compare_exchange`, but makes a single attempt which may fail spuriously, in
+/// which case the returned value can be equal to `current`
+pub(crate) unsafe fn atomic_compare_exchange_weak<T>(
+    dst: *mut T,
+    current: T,
+    new: T,
+) -> Result<T, T>
+where
+    T: Copy + Eq,
+{
+    atomic! {
+        T, a,
+        {
+            a = unsafe { &*(dst as *const _ as *const _) };
+
+            let current_raw = unsafe { core::mem::transmute_copy(&current) };
+            let new_raw = unsafe { core::mem::transmute_copy(&new) };
+
+            match a.compare_exchange_weak(current_raw, new_raw, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => Ok(current),
+                Err(previous_raw) => Err(unsafe { core::mem::transmute_copy(&previous_raw) }),
+            }
+        },
+        {
+            // The lock never fails spuriously
+            unsafe { atomic_compare_exchange(dst, current, new) }
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    mem::{self, MaybeUninit},
+    ptr,
+    sync::atomic::{self, AtomicUsize, Ordering},
+};
+
+use super::Backoff;
+
+/// A simple stamped lock
+///
+/// Readers take a stamp with `optimistic_read`, read the protected data without locking and then
+/// check with `validate_read` that no writer got in between, retrying or locking if one did.
+/// Writers hold the lock through the guard returned by `write`, which bumps the stamp on drop
+pub struct SeqLock {
+    /// The current state of the lock
+    state: AtomicUsize,
+}
+
+impl SeqLock {
+    /// Creates a new unlocked `SeqLock`
+    pub const fn new() -> SeqLock {
+        SeqLock {
+            state: AtomicUsize::new(0),
+        }
+    }
+
+    /// If not locked, returns the current stamp
+    #[inline]
+    pub fn optimistic_read(&self) -> Option<usize> {
+        let state = self.state.load(Ordering::Acquire);
+
+        if state == 1 { None } else { Some(state) }
+    }
+
+    /// Returns `true` if the current stamp is equal to `stamp`, meaning nothing was written since
+    /// `stamp` was taken and the data read in between is consistent
+    #[inline]
+    pub fn validate_read(&self, stamp: usize) -> bool {
+        atomic::fence(Ordering::Acquire);
+
+        self.state.load(Ordering::Relaxed) == stamp
+    }
+
+    /// Grabs the lock for writing, spinning until it is available
+    #[inline]
+    pub fn write(&self) -> SeqLockWriteGuard<'_> {
+        let backoff = Backoff::new();
+
+        loop {
+            let previous = self.state.swap(1, Ordering::Acquire);
+
+            if previous != 1 {
+                atomic::fence(Ordering::Release);
+
+                return SeqLockWriteGuard {
+                    lock: self,
+                    state: previous,
+                };
+            }
+
+            backoff.snooze();
+        }
+    }
+}
+
+impl Default for SeqLock {
+    fn default() -> SeqLock {
+        SeqLock::new()
+    }
+}
+
+impl fmt::Debug for SeqLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SeqLock")
+            .field("state", &self.state.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+/// An RAII (Resource acquisition is initialization) guard that releases the lock and increments
+/// the stamp when dropped
+#[must_use = "the lock is released as soon as the guard is dropped"]
+pub struct SeqLockWriteGuard<'a> {
+    /// The parent lock
+    lock: &'a SeqLock,
+
+    /// The stamp before locking
+    state: usize,
+}
+
+impl SeqLockWriteGuard<'_> {
+    /// Releases the lock without incrementing the stamp
+    /// Only valid when nothing was written, readers won't notice the lock was held
+    #[inline]
+    pub fn abort(self) {
+        self.lock.state.store(self.state, Ordering::Release);
+
+        // We specifically don't want to call drop(), since that's what increments the stamp
+        mem::forget(self);
+    }
+}
+
+impl Drop for SeqLockWriteGuard<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        // Release the lock and increment the stamp
+        self.lock
+            .state
+            .store(self.state.wrapping_add(2), Ordering::Release);
+    }
+}
+
+/// A `Copy` value guarded by its own `SeqLock`
+///
+/// Loads are optimistic and never block writers unless they keep failing, which suits data that
+/// is read on a hot path but rarely written, such as a configuration snapshot
+pub struct SeqCell<T> {
+    lock: SeqLock,
+    value: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Send for SeqCell<T> {}
+unsafe impl<T: Send> Sync for SeqCell<T> {}
+
+impl<T: Copy> SeqCell<T> {
+    /// Creates a new `SeqCell` holding `value`
+    pub const fn new(value: T) -> SeqCell<T> {
+        SeqCell {
+            lock: SeqLock::new(),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Returns a copy of the value, never observing a partially written one
+    pub fn load(&self) -> T {
+        // Try doing an optimistic read first
+        if let Some(stamp) = self.lock.optimistic_read() {
+            // Volatile and as `MaybeUninit`, the read may race with a writer and is then discarded
+            let val = unsafe { ptr::read_volatile(self.value.get().cast::<MaybeUninit<T>>()) };
+
+            if self.lock.validate_read(stamp) {
+                return unsafe { val.assume_init() };
+            }
+        }
+
+        // Grab a regular write lock so that writers don't starve this load
+        let guard = self.lock.write();
+        let val = unsafe { ptr::read(self.value.get()) };
+
+        // The value hasn't changed. Drop the guard without incrementing the stamp
+        guard.abort();
+
+        val
+    }
+
+    /// Returns a copy of the value like `load`, but only ever reads optimistically, backing off
+    /// and retrying until no writer got in between instead of taking the lock
+    pub fn read(&self) -> T {
+        let backoff = Backoff::new();
+
+        loop {
+            if let Some(stamp) = self.lock.optimistic_read() {
+                let val = unsafe { ptr::read_volatile(self.value.get().cast::<MaybeUninit<T>>()) };
+
+                if self.lock.validate_read(stamp) {
+                    return unsafe { val.assume_init() };
+                }
+            }
+
+            backoff.snooze();
+        }
+    }
+
+    /// Replaces the value
+    pub fn store(&self, value: T) {
+        let _guard = self.lock.write();
+
+        unsafe { ptr::write(self.value.get(), value) }
+    }
+
+    /// Same as `store`, pairs with `read`
+    pub fn write(&self, value: T) {
+        self.store(value)
+    }
+
+    /// Returns a mutable reference to the value, no locking is needed with exclusive access
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Consumes the `SeqCell`, returning the value
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+/// Name of `SeqCell` matching its `read` and `write` pair
+pub type SeqLockCell<T> = SeqCell<T>;
+
+impl<T: Copy + Default> Default for SeqCell<T> {
+    fn default() -> SeqCell<T> {
+        SeqCell::new(T::default())
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for SeqCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SeqCell")
+            .field("value", &self.load())
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use super::*;
+
+    #[test]
+    fn test_seq_lock_stamps() {
+        let lock = SeqLock::new();
+        let stamp = lock.optimistic_read().unwrap();
+
+        assert!(lock.validate_read(stamp));
+
+        let guard = lock.write();
+
+        // Locked, there is no stamp to read under
+        assert!(lock.optimistic_read().is_none());
+
+        drop(guard);
+
+        assert!(!lock.validate_read(stamp));
+
+        // Aborting leaves the stamp unchanged
+        let stamp = lock.optimistic_read().unwrap();
+
+        lock.write().abort();
+
+        assert!(lock.validate_read(stamp));
+    }
+
+    #[test]
+    fn test_seq_cell_no_torn_reads() {
+        const WRITES: u64 = 20_000;
+
+        let cell = Arc::new(SeqCell::new([0u64; 8]));
+
+        let readers: std::vec::Vec<_> = (0..4)
+            .map(|_| {
+                let cell = cell.clone();
+
+                thread::spawn(move || {
+                    let mut last = 0;
+
+                    loop {
+                        let val = cell.load();
+
+                        assert!(val.iter().all(|&x| x == val[0]), "torn read: {val:?}");
+                        assert!(val[0] >= last);
+
+                        last = val[0];
+
+                        if last == WRITES {
+                            break;
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        for i in 1..=WRITES {
+            cell.store([i; 8]);
+        }
+
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        assert_eq!(cell.load(), [WRITES; 8]);
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Snapshot {
+        seq: u64,
+        double: u64,
+        square: u64,
+        check: u64,
+    }
+
+    impl Snapshot {
+        fn new(seq: u64) -> Snapshot {
+            Snapshot {
+                seq,
+                double: seq * 2,
+                square: seq * seq,
+                check: !seq,
+            }
+        }
+    }
+
+    #[test]
+    fn test_seq_lock_cell_read_write() {
+        const WRITES: u64 = 20_000;
+
+        assert_eq!(mem::size_of::<Snapshot>(), 32);
+
+        let cell = Arc::new(SeqLockCell::new(Snapshot::new(0)));
+
+        let readers: std::vec::Vec<_> = (0..4)
+            .map(|_| {
+                let cell = cell.clone();
+
+                thread::spawn(move || {
+                    loop {
+                        let val = cell.read();
+
+                        assert_eq!(val, Snapshot::new(val.seq), "torn read");
+
+                        if val.seq == WRITES {
+                            break;
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        for i in 1..=WRITES {
+            cell.write(Snapshot::new(i));
+        }
+
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        assert_eq!(cell.read(), Snapshot::new(WRITES));
+    }
+}
@@ -0,0 +1,37 @@
+//! Exercises the crate without its `std` feature
+//! Run with `cargo test -p sync --no-default-features`
+#![no_std]
+
+use sync::{AtomicCell, Backoff};
+
+#[test]
+fn test_atomic_cell_u32() {
+    let cell = AtomicCell::new(7u32);
+
+    assert!(AtomicCell::<u32>::is_lock_free());
+    assert_eq!(cell.fetch_add(3), 7);
+    assert_eq!(cell.swap(1), 10);
+    assert_eq!(cell.compare_exchange(1, 2), Ok(1));
+    assert_eq!(cell.compare_exchange(1, 3), Err(2));
+    assert_eq!(cell.into_inner(), 2);
+}
+
+#[test]
+fn test_atomic_cell_fallback() {
+    // Goes through the global seqlocks
+    let cell = AtomicCell::new([1u8; 3]);
+
+    assert!(!AtomicCell::<[u8; 3]>::is_lock_free());
+    assert_eq!(cell.swap([2; 3]), [1; 3]);
+    assert_eq!(cell.compare_exchange([2; 3], [3; 3]), Ok([2; 3]));
+    assert_eq!(cell.load(), [3; 3]);
+}
+
+#[test]
+fn test_backoff_completes() {
+    let backoff = Backoff::new();
+
+    while !backoff.is_completed() {
+        backoff.snooze();
+    }
+}